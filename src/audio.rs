@@ -186,9 +186,12 @@ use crate::prelude::*;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "terminal")))]
 pub use crate::renderer::sdl::{AudioDevice, AudioFormatNum};
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "terminal"))]
+pub use crate::renderer::terminal::{AudioDevice, AudioFormatNum};
+
 #[cfg(target_arch = "wasm32")]
 pub use crate::renderer::wasm::{AudioDevice, AudioFormatNum};
 
@@ -0,0 +1,597 @@
+//! 2D camera functions.
+//!
+//! Provides a [`Camera2D`] which transforms world coordinates to screen coordinates for all
+//! shape, text, [Image] and `Texture` drawing methods on [`PixState`].
+//!
+//! Some settings are transformed along with the drawing:
+//!
+//! - Text is positioned and rotated by the camera, and the font size, wrap width and outline are
+//!   scaled by the camera zoom. Text sizes returned by [`PixState::text`] and similar methods
+//!   stay in world units.
+//! - [`PixState::stroke_weight`] only applies to lines, so [`PixState::line`] scales it by the
+//!   camera zoom like the rest of the line. Other shapes are always outlined one pixel wide,
+//!   with or without a camera.
+//!
+//! UI widgets check the mouse in screen coordinates, so clear the camera with
+//! [`PixState::set_camera`] before drawing them.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::camera`]: Returns the current [`Camera2D`], if set.
+//! - [`PixState::set_camera`]: Sets or clears the [`Camera2D`] drawing is transformed by.
+//! - [`PixState::world_to_screen`]: Converts a world position to a screen position.
+//! - [`PixState::screen_to_world`]: Converts a screen position to a world position.
+//! - [`PixState::mouse_world_pos`]: Returns the mouse position in world coordinates.
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App { camera: Camera2D, player: Point<f64> };
+//! # impl PixEngine for App {
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     self.camera.follow(self.player, 0.1);
+//!     self.camera.update(s.delta_time());
+//!     s.set_camera(self.camera);
+//!     // Drawn relative to the camera
+//!     s.circle([self.player.x() as i32, self.player.y() as i32, 10])?;
+//!     s.set_camera(None);
+//!     // Drawn in screen coordinates
+//!     s.text("Score: 0")?;
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::{ops::clamp_size, prelude::*};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{f64::consts::TAU, time::Duration};
+
+/// A 2D camera which transforms world coordinates to screen coordinates.
+///
+/// The world `position` the camera looks at is drawn at the screen `offset`, which defaults to
+/// the center of the camera size. Drawing is scaled by `zoom` and rotated by `rotation` degrees
+/// about the `offset`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[must_use]
+pub struct Camera2D {
+    position: Point<f64>,
+    offset: Point<f64>,
+    size: (f64, f64),
+    zoom: f64,
+    rotation: f64,
+    bounds: Option<Rect<f64>>,
+    target: Option<Point<f64>>,
+    smoothing: f64,
+    shake_intensity: f64,
+    shake_duration: Duration,
+    shake_remaining: Duration,
+    shake_offset: Point<f64>,
+}
+
+impl Camera2D {
+    /// Constructs a `Camera2D` for a screen of `(width, height)` looking at the world origin.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let camera = Camera2D::new(800, 600);
+    /// assert_eq!(camera.offset(), point![400.0, 300.0]);
+    /// assert_eq!(camera.world_to_screen([0.0, 0.0]), point![400.0, 300.0]);
+    /// ```
+    pub fn new(width: u32, height: u32) -> Self {
+        let size = (f64::from(width), f64::from(height));
+        Self {
+            position: Point::origin(),
+            offset: point![size.0 / 2.0, size.1 / 2.0],
+            size,
+            zoom: 1.0,
+            rotation: 0.0,
+            bounds: None,
+            target: None,
+            smoothing: 0.0,
+            shake_intensity: 0.0,
+            shake_duration: Duration::ZERO,
+            shake_remaining: Duration::ZERO,
+            shake_offset: Point::origin(),
+        }
+    }
+
+    /// Returns the world position the camera is looking at.
+    #[inline]
+    pub const fn position(&self) -> Point<f64> {
+        self.position
+    }
+
+    /// Set the world position the camera is looking at, limited by the camera bounds.
+    #[inline]
+    pub fn set_position<P: Into<Point<f64>>>(&mut self, position: P) {
+        self.position = position.into();
+        self.clamp_to_bounds();
+    }
+
+    /// Returns the screen position the camera `position` is drawn at.
+    #[inline]
+    pub const fn offset(&self) -> Point<f64> {
+        self.offset
+    }
+
+    /// Set the screen position the camera `position` is drawn at.
+    #[inline]
+    pub fn set_offset<P: Into<Point<f64>>>(&mut self, offset: P) {
+        self.offset = offset.into();
+        self.clamp_to_bounds();
+    }
+
+    /// Returns the screen size of the camera as `(width, height)`.
+    #[inline]
+    #[must_use]
+    pub const fn size(&self) -> (f64, f64) {
+        self.size
+    }
+
+    /// Set the screen size of the camera, re-centering the `offset`.
+    #[inline]
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self.size = (f64::from(width), f64::from(height));
+        self.offset = point![self.size.0 / 2.0, self.size.1 / 2.0];
+        self.clamp_to_bounds();
+    }
+
+    /// Returns the camera zoom. A zoom of `2.0` draws everything at twice the size.
+    #[inline]
+    #[must_use]
+    pub const fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Set the camera zoom. A zoom of `2.0` draws everything at twice the size. Zoom is clamped to
+    /// be greater than `0.0`.
+    #[inline]
+    pub fn set_zoom(&mut self, zoom: f64) {
+        self.zoom = zoom.max(f64::EPSILON);
+        self.clamp_to_bounds();
+    }
+
+    /// Returns the camera rotation in degrees.
+    #[inline]
+    #[must_use]
+    pub const fn rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    /// Set the camera rotation in degrees. Rotating the camera clockwise rotates the world drawn
+    /// counter-clockwise.
+    #[inline]
+    pub fn set_rotation(&mut self, rotation: f64) {
+        self.rotation = rotation;
+    }
+
+    /// Returns the world bounds the camera view is limited to, if set.
+    #[inline]
+    #[must_use]
+    pub const fn bounds(&self) -> Option<Rect<f64>> {
+        self.bounds
+    }
+
+    /// Set world bounds to limit the camera view to, or `None` to allow the camera to move
+    /// anywhere. If the view is larger than the bounds, the camera is centered on the bounds.
+    /// Rotation is not taken into account.
+    #[inline]
+    pub fn set_bounds<R: Into<Option<Rect<f64>>>>(&mut self, bounds: R) {
+        self.bounds = bounds.into();
+        self.clamp_to_bounds();
+    }
+
+    /// Follow a world `target` position on each [`Camera2D::update`], or `None` to stop following.
+    /// `smoothing` is the fraction of the distance to the target that remains after one second,
+    /// clamped to `0.0..1.0`, where `0.0` keeps the target exactly in view.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::time::Duration;
+    /// let mut camera = Camera2D::new(800, 600);
+    /// camera.follow(point![100.0, 0.0], 0.5);
+    /// camera.update(Duration::from_secs(1));
+    /// assert_eq!(camera.position(), point![50.0, 0.0]);
+    /// ```
+    #[inline]
+    pub fn follow<P: Into<Option<Point<f64>>>>(&mut self, target: P, smoothing: f64) {
+        self.target = target.into();
+        self.smoothing = smoothing.clamp(0.0, 1.0);
+    }
+
+    /// Shake the camera by up to `intensity` screen pixels, fading out over `duration`.
+    #[inline]
+    pub fn shake(&mut self, intensity: f64, duration: Duration) {
+        self.shake_intensity = intensity.abs();
+        self.shake_duration = duration;
+        self.shake_remaining = duration;
+    }
+
+    /// Returns whether the camera is currently shaking.
+    #[inline]
+    #[must_use]
+    pub fn is_shaking(&self) -> bool {
+        !self.shake_remaining.is_zero()
+    }
+
+    /// Update the camera by the elapsed `delta_time`, moving towards the followed target and
+    /// advancing any screen shake. Should be called once per frame with
+    /// [`PixState::delta_time`].
+    pub fn update(&mut self, delta_time: Duration) {
+        if let Some(target) = self.target {
+            let t = 1.0 - self.smoothing.powf(delta_time.as_secs_f64());
+            let [x, y] = self.position.coords();
+            let [target_x, target_y] = target.coords();
+            self.position = point![(target_x - x).mul_add(t, x), (target_y - y).mul_add(t, y)];
+            self.clamp_to_bounds();
+        }
+
+        self.shake_remaining = self.shake_remaining.saturating_sub(delta_time);
+        self.shake_offset = if self.is_shaking() {
+            let fade = self.shake_remaining.as_secs_f64() / self.shake_duration.as_secs_f64();
+            let intensity = self.shake_intensity * fade;
+            point![
+                random_rng(-1.0..1.0) * intensity,
+                random_rng(-1.0..1.0) * intensity
+            ]
+        } else {
+            Point::origin()
+        };
+    }
+
+    /// Returns the axis-aligned world area visible to the camera, ignoring rotation and shake.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut camera = Camera2D::new(800, 600);
+    /// camera.set_zoom(2.0);
+    /// assert_eq!(camera.view_rect(), rect![-200.0, -150.0, 400.0, 300.0]);
+    /// ```
+    pub fn view_rect(&self) -> Rect<f64> {
+        let [x, y] = self.position.coords();
+        let [offset_x, offset_y] = self.offset.coords();
+        let (width, height) = self.size;
+        rect![
+            x - offset_x / self.zoom,
+            y - offset_y / self.zoom,
+            width / self.zoom,
+            height / self.zoom,
+        ]
+    }
+
+    /// Converts a world position to a screen position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut camera = Camera2D::new(800, 600);
+    /// camera.set_position([100.0, 100.0]);
+    /// camera.set_zoom(2.0);
+    /// assert_eq!(camera.world_to_screen([110.0, 100.0]), point![420.0, 300.0]);
+    /// ```
+    pub fn world_to_screen<P: Into<Point<f64>>>(&self, p: P) -> Point<f64> {
+        let [x, y] = p.into().coords();
+        let [px, py] = self.position.coords();
+        let (sin, cos) = (-self.rotation).to_radians().sin_cos();
+        let (dx, dy) = ((x - px) * self.zoom, (y - py) * self.zoom);
+        let [offset_x, offset_y] = self.shake_offset.coords();
+        let (offset_x, offset_y) = (self.offset.x() + offset_x, self.offset.y() + offset_y);
+        point![
+            dx.mul_add(cos, -dy * sin) + offset_x,
+            dx.mul_add(sin, dy * cos) + offset_y
+        ]
+    }
+
+    /// Converts a screen position to a world position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut camera = Camera2D::new(800, 600);
+    /// camera.set_position([100.0, 100.0]);
+    /// camera.set_zoom(2.0);
+    /// assert_eq!(camera.screen_to_world([420.0, 300.0]), point![110.0, 100.0]);
+    /// ```
+    pub fn screen_to_world<P: Into<Point<f64>>>(&self, p: P) -> Point<f64> {
+        let [x, y] = p.into().coords();
+        let [offset_x, offset_y] = self.shake_offset.coords();
+        let (offset_x, offset_y) = (self.offset.x() + offset_x, self.offset.y() + offset_y);
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let (dx, dy) = (x - offset_x, y - offset_y);
+        let [px, py] = self.position.coords();
+        point![
+            dx.mul_add(cos, -dy * sin) / self.zoom + px,
+            dx.mul_add(sin, dy * cos) / self.zoom + py
+        ]
+    }
+
+    /// Keeps the camera view within the camera bounds, if set.
+    fn clamp_to_bounds(&mut self) {
+        let bounds = match self.bounds {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let view = self.view_rect();
+        let clamp = |position: f64, view_min: f64, view_size: f64, min: f64, size: f64| {
+            let start = position - view_min;
+            if view_size >= size {
+                min + (size - view_size) / 2.0 + start
+            } else {
+                view_min.clamp(min, min + size - view_size) + start
+            }
+        };
+        let [x, y] = self.position.coords();
+        self.position = point![
+            clamp(x, view.x(), view.width(), bounds.x(), bounds.width()),
+            clamp(y, view.y(), view.height(), bounds.y(), bounds.height())
+        ];
+    }
+
+    /// Returns whether the camera draws without rotation, so shapes stay axis-aligned.
+    #[inline]
+    pub(crate) fn is_axis_aligned(&self) -> bool {
+        self.rotation % 360.0 == 0.0
+    }
+
+    /// Transforms a world [Point] to a rounded screen [Point].
+    #[inline]
+    pub(crate) fn transform_point(&self, p: Point<i32>) -> Point<i32> {
+        self.world_to_screen(p.as_::<f64>()).round().as_()
+    }
+
+    /// Transforms a world length to a rounded screen length.
+    #[inline]
+    pub(crate) fn transform_length(&self, length: i32) -> i32 {
+        (f64::from(length) * self.zoom).round() as i32
+    }
+
+    /// Transforms an axis-aligned world [Rect] to a screen [Rect], ignoring rotation.
+    #[inline]
+    pub(crate) fn transform_rect(&self, rect: Rect<i32>) -> Rect<i32> {
+        Rect::with_position(
+            self.transform_point(rect.top_left()),
+            self.transform_length(rect.width()),
+            self.transform_length(rect.height()),
+        )
+    }
+
+    /// Transforms a world destination [Rect], `angle` in degrees and rotation `center` relative to
+    /// the destination for drawing an [Image] or `Texture` to the screen.
+    pub(crate) fn transform_dst(
+        &self,
+        dst: Rect<i32>,
+        angle: f64,
+        center: Option<Point<i32>>,
+    ) -> (Rect<i32>, f64, Option<Point<i32>>) {
+        if self.is_axis_aligned() && center.is_none() {
+            return (self.transform_rect(dst), angle, None);
+        }
+        // Rotation happens about `center`, so place the destination to keep it fixed
+        let center = center.unwrap_or_else(|| point![dst.width() / 2, dst.height() / 2]);
+        let pivot = self.transform_point(point![dst.x() + center.x(), dst.y() + center.y()]);
+        let center = point![
+            self.transform_length(center.x()),
+            self.transform_length(center.y())
+        ];
+        let dst = rect![
+            pivot.x() - center.x(),
+            pivot.y() - center.y(),
+            self.transform_length(dst.width()),
+            self.transform_length(dst.height()),
+        ];
+        (dst, angle - self.rotation, Some(center))
+    }
+
+    /// Transforms a world text `position` and `size`, `angle` in degrees and rotation `center`
+    /// relative to the position for drawing text to the screen.
+    pub(crate) fn transform_text(
+        &self,
+        position: Point<i32>,
+        (width, height): (u32, u32),
+        angle: Option<f64>,
+        center: Option<Point<i32>>,
+    ) -> (Point<i32>, Option<f64>, Option<Point<i32>>) {
+        let dst = rect![position, clamp_size(width), clamp_size(height)];
+        let (dst, angle, center) = self.transform_dst(dst, angle.unwrap_or(0.0), center);
+        (
+            dst.top_left(),
+            Some(angle).filter(|&angle| angle != 0.0),
+            center,
+        )
+    }
+}
+
+impl PixState {
+    /// Returns the current [`Camera2D`] drawing is transformed by, if set.
+    #[inline]
+    #[must_use]
+    pub const fn camera(&self) -> Option<&Camera2D> {
+        self.settings.camera.as_ref()
+    }
+
+    /// Sets the [`Camera2D`] that shape, text, [Image] and `Texture` drawing is transformed by, or
+    /// `None` to draw in screen coordinates. See the [module-level documentation] for details.
+    ///
+    /// [module-level documentation]: crate::camera
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { camera: Camera2D };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.set_camera(self.camera);
+    ///     s.rect([-50, -50, 100, 100])?; // Centered on the camera position
+    ///     s.set_camera(None);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_camera<C: Into<Option<Camera2D>>>(&mut self, camera: C) {
+        self.settings.camera = camera.into();
+    }
+
+    /// Converts a world position to a screen position using the current [`Camera2D`]. Returns the
+    /// position unchanged if no camera is set.
+    #[inline]
+    pub fn world_to_screen<P: Into<Point<i32>>>(&self, p: P) -> Point<i32> {
+        transform_point(self.settings.camera, p.into())
+    }
+
+    /// Converts a screen position to a world position using the current [`Camera2D`]. Returns the
+    /// position unchanged if no camera is set.
+    #[inline]
+    pub fn screen_to_world<P: Into<Point<i32>>>(&self, p: P) -> Point<i32> {
+        let p = p.into();
+        self.settings.camera.map_or(p, |camera| {
+            camera.screen_to_world(p.as_::<f64>()).round().as_()
+        })
+    }
+
+    /// Returns the current mouse position in world coordinates using the current [`Camera2D`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { camera: Camera2D };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.set_camera(self.camera);
+    ///     // Draws a circle under the mouse cursor, regardless of camera position or zoom
+    ///     s.circle([s.mouse_world_pos().x(), s.mouse_world_pos().y(), 10])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn mouse_world_pos(&self) -> Point<i32> {
+        self.screen_to_world(self.mouse_pos())
+    }
+}
+
+impl PixState {
+    /// Transforms world [Point]s to screen [Point]s using the current [`Camera2D`], if set.
+    #[inline]
+    pub(crate) fn camera_points<const N: usize>(&self, points: [Point<i32>; N]) -> [Point<i32>; N] {
+        let camera = self.settings.camera;
+        points.map(|p| transform_point(camera, p))
+    }
+
+    /// Transforms a world length to a screen length using the current [`Camera2D`], if set.
+    #[inline]
+    pub(crate) fn camera_length(&self, length: i32) -> i32 {
+        self.settings
+            .camera
+            .map_or(length, |camera| camera.transform_length(length))
+    }
+}
+
+/// Transforms a world [Point] to a screen [Point] using a [`Camera2D`], if provided.
+#[inline]
+pub(crate) fn transform_point(camera: Option<Camera2D>, p: Point<i32>) -> Point<i32> {
+    camera.map_or(p, |camera| camera.transform_point(p))
+}
+
+/// Transforms a world stroke weight to a screen line width using a [`Camera2D`], if provided.
+/// Widths beyond the widest line a renderer can draw are clamped instead of wrapping around.
+#[inline]
+pub(crate) fn transform_stroke_weight(camera: Option<Camera2D>, weight: u16) -> u8 {
+    let weight = i32::from(weight);
+    let width = camera.map_or(weight, |camera| camera.transform_length(weight));
+    width.clamp(0, i32::from(u8::MAX)) as u8
+}
+
+/// Returns the points of a polygon approximating an [Ellipse] centered on its position, used to
+/// draw ellipses which can't be rotated directly.
+pub(crate) fn ellipse_points(ellipse: Ellipse<i32>) -> Vec<Point<i32>> {
+    const SEGMENTS: usize = 64;
+    let [x, y] = ellipse.center().as_::<f64>().coords();
+    let (rx, ry) = (
+        f64::from(ellipse.width()) / 2.0,
+        f64::from(ellipse.height()) / 2.0,
+    );
+    (0..SEGMENTS)
+        .map(|i| {
+            let (sin, cos) = (TAU * i as f64 / SEGMENTS as f64).sin_cos();
+            point![rx.mul_add(cos, x), ry.mul_add(sin, y)].round().as_()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_screen_round_trip() {
+        let mut camera = Camera2D::new(800, 600);
+        camera.set_position([120.0, -40.0]);
+        camera.set_zoom(1.5);
+        camera.set_rotation(30.0);
+
+        let p = point![37.0, 250.0];
+        let screen = camera.world_to_screen(p);
+        let world = camera.screen_to_world(screen);
+        assert!((world.x() - p.x()).abs() < 1e-9);
+        assert!((world.y() - p.y()).abs() < 1e-9);
+
+        camera.set_rotation(90.0);
+        let screen = camera.world_to_screen([130.0, -40.0]);
+        assert!((screen.x() - 400.0).abs() < 1e-9);
+        assert!((screen.y() - 285.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bounds_limit_view() {
+        let mut camera = Camera2D::new(100, 100);
+        camera.set_bounds(rect![0.0, 0.0, 500.0, 500.0]);
+        assert_eq!(camera.position(), point![50.0, 50.0]);
+
+        camera.set_position([480.0, 250.0]);
+        assert_eq!(camera.position(), point![450.0, 250.0]);
+
+        camera.set_bounds(rect![0.0, 0.0, 50.0, 50.0]);
+        assert_eq!(camera.position(), point![25.0, 25.0]);
+    }
+
+    #[test]
+    fn text_follows_camera() {
+        let mut camera = Camera2D::new(100, 100);
+        camera.set_position([10.0, 10.0]);
+        camera.set_zoom(2.0);
+        let (position, angle, center) = camera.transform_text(point![10, 10], (20, 10), None, None);
+        assert_eq!(position, point![50, 50]);
+        assert_eq!((angle, center), (None, None));
+
+        camera.set_rotation(90.0);
+        let (position, angle, center) = camera.transform_text(point![10, 10], (20, 10), None, None);
+        // Rotates about the center of the text, which stays at its transformed position
+        assert_eq!(center, Some(point![20, 10]));
+        let pivot = point![position.x() + 20, position.y() + 10];
+        assert_eq!(pivot, camera.transform_point(point![20, 15]));
+        assert_eq!(angle, Some(-90.0));
+    }
+
+    #[test]
+    fn stroke_weight_saturates() {
+        let mut camera = Camera2D::new(100, 100);
+        assert_eq!(transform_stroke_weight(None, 300), u8::MAX);
+        camera.set_zoom(3.0);
+        assert_eq!(transform_stroke_weight(Some(camera), 10), 30);
+        assert_eq!(transform_stroke_weight(Some(camera), 100), u8::MAX);
+    }
+}
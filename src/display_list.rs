@@ -0,0 +1,594 @@
+//! Display list recording and replay.
+//!
+//! A [`DisplayList`] records every shape, text, [Image] and `Texture` drawn to the current canvas
+//! as a list of [`DrawCommand`]s, along with the colors and settings they were drawn with. Display
+//! lists can be replayed with [`Draw::draw`], inspected or compared to assert what was drawn
+//! without comparing pixels, and serialized with the `serde` feature.
+//!
+//! Commands are recorded in screen coordinates after [`RectMode`], [`EllipseMode`] and any
+//! [`Camera2D`] have been applied, so a display list replays the same regardless of the settings
+//! active when it's drawn.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::record_display_list`]: Record drawing into a [`DisplayList`].
+//! - [`PixState::save_svg`]: Record drawing and save it to an SVG file.
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App { background: Option<DisplayList> };
+//! # impl PixEngine for App {
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     if let Some(background) = &self.background {
+//!         background.draw(s)?;
+//!     } else {
+//!         self.background = Some(s.record_display_list(|s| {
+//!             s.fill(Color::DARK_GREEN);
+//!             s.rect([0, 300, 800, 300])?;
+//!             s.fill(Color::SKY_BLUE);
+//!             s.rect([0, 0, 800, 300])
+//!         })?);
+//!     }
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::{
+    prelude::*, renderer::Rendering, state::settings::intersect_clip, texture::TextureRenderer,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::slice;
+
+pub mod svg;
+
+/// A single draw call recorded in a [`DisplayList`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DrawCommand {
+    /// Clear the canvas to a [Color].
+    Clear {
+        /// Background color.
+        color: Color,
+    },
+    /// Draw a [Point].
+    Point {
+        /// Point position.
+        p: Point<i32>,
+        /// Point color.
+        color: Color,
+    },
+    /// Draw a [Line].
+    Line {
+        /// Line end points.
+        line: Line<i32>,
+        /// Whether the line is anti-aliased.
+        smooth: bool,
+        /// Line thickness.
+        width: u8,
+        /// Line color.
+        color: Color,
+    },
+    /// Draw a cubic Bezier curve.
+    Bezier {
+        /// Anchor and control points.
+        points: Vec<Point<i32>>,
+        /// Curve resolution.
+        detail: i32,
+        /// Curve color.
+        stroke: Option<Color>,
+    },
+    /// Draw a [Triangle][Tri].
+    Triangle {
+        /// Triangle points.
+        tri: Tri<i32>,
+        /// Whether the triangle is anti-aliased.
+        smooth: bool,
+        /// Fill color.
+        fill: Option<Color>,
+        /// Outline color.
+        stroke: Option<Color>,
+    },
+    /// Draw a [Rect], optionally with rounded corners.
+    Rect {
+        /// Rectangle position and size.
+        rect: Rect<i32>,
+        /// Corner radius.
+        radius: Option<i32>,
+        /// Fill color.
+        fill: Option<Color>,
+        /// Outline color.
+        stroke: Option<Color>,
+    },
+    /// Draw a [Quad].
+    Quad {
+        /// Quad points.
+        quad: Quad<i32>,
+        /// Whether the quad is anti-aliased.
+        smooth: bool,
+        /// Fill color.
+        fill: Option<Color>,
+        /// Outline color.
+        stroke: Option<Color>,
+    },
+    /// Draw a polygon.
+    Polygon {
+        /// Polygon points.
+        points: Vec<Point<i32>>,
+        /// Whether the polygon is anti-aliased.
+        smooth: bool,
+        /// Fill color.
+        fill: Option<Color>,
+        /// Outline color.
+        stroke: Option<Color>,
+    },
+    /// Draw an [Ellipse] centered on its position.
+    Ellipse {
+        /// Ellipse center and size.
+        ellipse: Ellipse<i32>,
+        /// Whether the ellipse is anti-aliased.
+        smooth: bool,
+        /// Fill color.
+        fill: Option<Color>,
+        /// Outline color.
+        stroke: Option<Color>,
+    },
+    /// Draw an arc.
+    Arc {
+        /// Arc center.
+        p: Point<i32>,
+        /// Arc radius.
+        radius: i32,
+        /// Start angle in degrees.
+        start: i32,
+        /// End angle in degrees.
+        end: i32,
+        /// Whether the arc is open or a pie.
+        mode: ArcMode,
+        /// Fill color.
+        fill: Option<Color>,
+        /// Outline color.
+        stroke: Option<Color>,
+    },
+    /// Draw text using the current font family. Text is recorded after alignment and
+    /// [`TextOverflow`] have been applied to its position, text and font size, with any
+    /// [`PixState::text_shadow`] and [`PixState::text_outline`] recorded as separate commands
+    /// drawn first.
+    Text {
+        /// Text position.
+        position: Point<i32>,
+        /// Text to draw.
+        text: String,
+        /// Width to wrap text at.
+        wrap_width: Option<u32>,
+        /// Rotation in degrees.
+        angle: Option<f64>,
+        /// Center of rotation.
+        center: Option<Point<i32>>,
+        /// Flip direction.
+        flipped: Option<Flipped>,
+        /// Text color.
+        fill: Option<Color>,
+        /// Outline width.
+        outline: u16,
+        /// Font family, including its fallbacks. Fonts aren't serialized, so deserialized text
+        /// is drawn with the default font.
+        #[cfg_attr(feature = "serde", serde(skip))]
+        font_family: Font,
+        /// Font size.
+        font_size: u32,
+        /// Font style.
+        font_style: FontStyle,
+        /// Horizontal alignment of each line.
+        align: TextAlign,
+        /// Area the text is clipped to when drawn with [`TextOverflow::Clip`].
+        clip: Option<Rect<i32>>,
+    },
+    /// Draw a portion `src` of an [Image] to `dst`.
+    Image {
+        /// Image to draw.
+        image: Image,
+        /// Source rectangle.
+        src: Option<Rect<i32>>,
+        /// Destination rectangle.
+        dst: Option<Rect<i32>>,
+        /// Rotation in degrees.
+        angle: f64,
+        /// Center of rotation.
+        center: Option<Point<i32>>,
+        /// Flip direction.
+        flipped: Option<Flipped>,
+        /// Tint color.
+        tint: Option<Color>,
+    },
+    /// Draw a portion `src` of a `Texture` to `dst`.
+    Texture {
+        /// Texture to draw.
+        texture_id: TextureId,
+        /// Source rectangle.
+        src: Option<Rect<i32>>,
+        /// Destination rectangle.
+        dst: Option<Rect<i32>>,
+        /// Rotation in degrees.
+        angle: f64,
+        /// Center of rotation.
+        center: Option<Point<i32>>,
+        /// Flip direction.
+        flipped: Option<Flipped>,
+        /// Tint color.
+        tint: Option<Color>,
+    },
+}
+
+impl DrawCommand {
+    /// Draw the command to the current canvas, where `clip` is the clip rect currently applied to
+    /// the canvas.
+    fn render(&self, s: &mut PixState, clip: Option<Rect<i32>>) -> PixResult<()> {
+        let renderer = &mut s.renderer;
+        match *self {
+            Self::Clear { color } => {
+                renderer.set_draw_color(color)?;
+                renderer.clear()
+            }
+            Self::Point { p, color } => renderer.point(p, color),
+            Self::Line {
+                line,
+                smooth,
+                width,
+                color,
+            } => renderer.line(line, smooth, width, color),
+            Self::Bezier {
+                ref points,
+                detail,
+                stroke,
+            } => renderer.bezier(points.iter().copied(), detail, stroke),
+            Self::Triangle {
+                tri,
+                smooth,
+                fill,
+                stroke,
+            } => renderer.triangle(tri, smooth, fill, stroke),
+            Self::Rect {
+                rect,
+                radius,
+                fill,
+                stroke,
+            } => renderer.rect(rect, radius, fill, stroke),
+            Self::Quad {
+                quad,
+                smooth,
+                fill,
+                stroke,
+            } => renderer.quad(quad, smooth, fill, stroke),
+            Self::Polygon {
+                ref points,
+                smooth,
+                fill,
+                stroke,
+            } => renderer.polygon(points.iter().copied(), smooth, fill, stroke),
+            Self::Ellipse {
+                ellipse,
+                smooth,
+                fill,
+                stroke,
+            } => renderer.ellipse(ellipse, smooth, fill, stroke),
+            Self::Arc {
+                p,
+                radius,
+                start,
+                end,
+                mode,
+                fill,
+                stroke,
+            } => renderer.arc(p, radius, start, end, mode, fill, stroke),
+            Self::Text {
+                position,
+                ref text,
+                wrap_width,
+                angle,
+                center,
+                flipped,
+                fill,
+                outline,
+                ref font_family,
+                font_size,
+                font_style,
+                align,
+                clip: text_clip,
+            } => {
+                // Reloading the font family is expensive, so only change it when it differs
+                let family_changed = *font_family != s.settings.font_family;
+                if family_changed {
+                    renderer.font_family(font_family)?;
+                }
+                renderer.font_size(font_size)?;
+                renderer.font_style(font_style);
+                renderer.text_align(align);
+                if let Some(text_clip) = text_clip {
+                    renderer.clip(Some(intersect_clip(clip, text_clip)))?;
+                }
+                let result = renderer.text(
+                    position, text, wrap_width, angle, center, flipped, fill, outline,
+                );
+                if text_clip.is_some() {
+                    renderer.clip(clip)?;
+                }
+                // Restore current font settings
+                if family_changed {
+                    renderer.font_family(&s.settings.font_family)?;
+                }
+                renderer.font_size(s.settings.font_size)?;
+                renderer.font_style(s.settings.font_style);
+                renderer.text_align(s.settings.text_align);
+                result.map(|_| ())
+            }
+            Self::Image {
+                ref image,
+                src,
+                dst,
+                angle,
+                center,
+                flipped,
+                tint,
+            } => renderer.image(image, src, dst, angle, center, flipped, tint),
+            Self::Texture {
+                texture_id,
+                src,
+                dst,
+                angle,
+                center,
+                flipped,
+                tint,
+            } => renderer.texture(texture_id, src, dst, angle, center, flipped, tint),
+        }
+    }
+}
+
+/// A recorded list of [`DrawCommand`]s that can be replayed, compared or serialized.
+#[derive(Default, Debug, Clone, PartialEq)]
+#[must_use]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DisplayList {
+    commands: Vec<DrawCommand>,
+}
+
+impl DisplayList {
+    /// Constructs an empty `DisplayList`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded [`DrawCommand`]s in the order they were drawn.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.fill(Color::RED);
+    ///     s.stroke(None);
+    ///     let list = s.record_display_list(|s| s.rect([10, 20, 30, 40]))?;
+    ///     assert_eq!(
+    ///         list.commands(),
+    ///         [DrawCommand::Rect {
+    ///             rect: rect![10, 20, 30, 40],
+    ///             radius: None,
+    ///             fill: Some(Color::RED),
+    ///             stroke: None,
+    ///         }]
+    ///     );
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Returns an iterator over the recorded [`DrawCommand`]s.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, DrawCommand> {
+        self.commands.iter()
+    }
+
+    /// Returns the number of recorded [`DrawCommand`]s.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns whether no [`DrawCommand`]s have been recorded.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Appends a [`DrawCommand`] to the end of the list.
+    #[inline]
+    pub fn push(&mut self, command: DrawCommand) {
+        self.commands.push(command);
+    }
+
+    /// Removes all recorded [`DrawCommand`]s.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+impl DisplayList {
+    /// Render all recorded [`DrawCommand`]s to the current canvas without deferring them to a
+    /// layer or recording them, where `clip` is the clip rect currently applied to the canvas.
+    pub(crate) fn render(&self, s: &mut PixState, clip: Option<Rect<i32>>) -> PixResult<()> {
+        self.commands
+            .iter()
+            .try_for_each(|command| command.render(s, clip))
+    }
+}
+
+impl Draw for DisplayList {
+    /// Replay all recorded [`DrawCommand`]s to the current canvas or layer.
+    fn draw(&self, s: &mut PixState) -> PixResult<()> {
+        self.commands
+            .iter()
+            .try_for_each(|command| s.draw_command(command.clone()))
+    }
+}
+
+impl<'a> IntoIterator for &'a DisplayList {
+    type Item = &'a DrawCommand;
+    type IntoIter = slice::Iter<'a, DrawCommand>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<DrawCommand> for DisplayList {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = DrawCommand>>(iter: I) -> Self {
+        Self {
+            commands: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<DrawCommand> for DisplayList {
+    #[inline]
+    fn extend<I: IntoIterator<Item = DrawCommand>>(&mut self, iter: I) {
+        self.commands.extend(iter);
+    }
+}
+
+impl PixState {
+    /// Records everything drawn by `f` into a [`DisplayList`], which is returned. Drawing is still
+    /// rendered to the current canvas while recording. Recordings can be nested, in which case
+    /// the outer recording also contains everything drawn by `f`.
+    ///
+    /// # Errors
+    ///
+    /// If `f` returns an error, then an error is returned and the recording is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let list = s.record_display_list(|s| {
+    ///         s.line([0, 0, 100, 100])?;
+    ///         s.circle([50, 50, 20])
+    ///     })?;
+    ///     assert_eq!(list.len(), 2);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn record_display_list<F>(&mut self, f: F) -> PixResult<DisplayList>
+    where
+        F: FnOnce(&mut PixState) -> PixResult<()>,
+    {
+        let outer = self.display_list.replace(DisplayList::new());
+        let result = f(self);
+        let list = self.display_list.take().unwrap_or_default();
+        self.display_list = outer;
+        result?;
+        if let Some(outer) = &mut self.display_list {
+            outer.extend(list.iter().cloned());
+        }
+        Ok(list)
+    }
+}
+
+impl PixState {
+    /// Draw a [`DrawCommand`] to the current canvas or layer, recording it if a [`DisplayList`] is
+    /// being recorded.
+    #[inline]
+    pub(crate) fn draw_command(&mut self, command: DrawCommand) -> PixResult<()> {
+        if !self.defer(|| command.clone()) {
+            command.render(self, self.settings.clip)?;
+        }
+        if let Some(list) = &mut self.display_list {
+            list.push(command);
+        }
+        Ok(())
+    }
+
+    /// Record a [`DrawCommand`] if a [`DisplayList`] is being recorded. Used when a command is
+    /// expensive to construct or is drawn directly by the renderer.
+    #[inline]
+    pub(crate) fn record<F>(&mut self, command: F)
+    where
+        F: FnOnce() -> DrawCommand,
+    {
+        if let Some(list) = &mut self.display_list {
+            list.push(command());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(font_family: Font) -> DrawCommand {
+        DrawCommand::Text {
+            position: point![10, 10],
+            text: "Hello".to_owned(),
+            wrap_width: None,
+            angle: None,
+            center: None,
+            flipped: None,
+            fill: Some(Color::WHITE),
+            outline: 0,
+            font_family,
+            font_size: 12,
+            font_style: FontStyle::NORMAL,
+            align: TextAlign::Left,
+            clip: None,
+        }
+    }
+
+    #[test]
+    fn display_list_records_commands() {
+        let clear = DrawCommand::Clear {
+            color: Color::BLACK,
+        };
+        let mut list = DisplayList::new();
+        assert!(list.is_empty());
+        list.push(clear.clone());
+        list.extend([text(Font::default())]);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.iter().next(), Some(&clear));
+
+        let collected: DisplayList = [clear, text(Font::default())].into_iter().collect();
+        assert_eq!(list, collected);
+
+        list.clear();
+        assert!(list.is_empty());
+        assert_ne!(list, collected);
+    }
+
+    #[test]
+    fn text_commands_compare_fonts() {
+        assert_eq!(text(Font::NOTO), text(Font::NOTO));
+        assert_ne!(text(Font::NOTO), text(Font::INCONSOLATA));
+        assert_ne!(
+            text(Font::NOTO),
+            text(Font::NOTO.with_fallback(Font::INCONSOLATA))
+        );
+    }
+}
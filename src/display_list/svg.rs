@@ -0,0 +1,728 @@
+//! SVG export of a [`DisplayList`].
+//!
+//! Converts recorded shape and text [`DrawCommand`]s into an SVG document, keeping fill, stroke
+//! and stroke weight, for print-quality output of drawings.
+//!
+//! Provided methods:
+//!
+//! - [`DisplayList::to_svg`]: Convert a [`DisplayList`] to an SVG document.
+//! - [`DisplayList::save_svg`]: Save a [`DisplayList`] to an SVG file.
+//! - [`PixState::display_list_svg`]: Convert a [`DisplayList`] to an SVG document, laying out
+//!   text with the current renderer.
+//! - [`PixState::save_svg`]: Record drawing and save it to an SVG file.
+//!
+//! [Image] and `Texture` commands are not exported. Text is laid out into lines with the same
+//! [`TextLayout`] the renderer uses, which needs the fonts text was drawn with, so only
+//! [`PixState`] methods wrap, align and flip text. Without a renderer, text is split into one
+//! line per `\n`, spaced `1.2em` apart and left-aligned.
+
+use super::{DisplayList, DrawCommand};
+use crate::{prelude::*, renderer::Rendering};
+use anyhow::Context;
+use log::info;
+use std::{
+    f64::consts::PI,
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Write},
+    iter,
+    path::Path,
+};
+
+impl DisplayList {
+    /// Convert the recorded shapes and text to an SVG document of a given `width` and `height`.
+    /// [Image] and `Texture` commands are skipped. Text falls back to a generic `sans-serif` font
+    /// family and, without a renderer to lay it out, is not wrapped, aligned or flipped. Use
+    /// [`PixState::display_list_svg`] to export text as it was drawn.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let list: DisplayList = [DrawCommand::Rect {
+    ///     rect: rect![10, 20, 30, 40],
+    ///     radius: None,
+    ///     fill: Some(Color::RED),
+    ///     stroke: None,
+    /// }]
+    /// .into_iter()
+    /// .collect();
+    /// let svg = list.to_svg(100, 100);
+    /// assert!(svg.contains(r##"<rect x="10" y="20" width="30" height="40" fill="#ff0000" stroke="none"/>"##));
+    /// ```
+    #[must_use]
+    pub fn to_svg(&self, width: u32, height: u32) -> String {
+        self.svg_with_layouts(width, height, iter::repeat(None))
+    }
+
+    /// Convert the recorded commands to an SVG document, drawing text commands with the
+    /// corresponding [`TextLayout`] from `layouts`, if provided.
+    fn svg_with_layouts<I>(&self, width: u32, height: u32, layouts: I) -> String
+    where
+        I: IntoIterator<Item = Option<TextLayout>>,
+    {
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        );
+        svg.push('\n');
+        let mut clips = 0;
+        for (command, layout) in self.iter().zip(layouts) {
+            write_command(&mut svg, command, layout.as_ref(), &mut clips);
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Save the recorded shapes and text to an SVG file of a given `width` and `height`. See
+    /// [`DisplayList::to_svg`] for details.
+    ///
+    /// # Errors
+    ///
+    /// If an [`io::Error`] occurs attempting to create or write to the SVG file, then an error is
+    /// returned.
+    ///
+    /// [`io::Error`]: std::io::Error
+    pub fn save_svg<P>(&self, path: P, width: u32, height: u32) -> PixResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        info!("Saving SVG to {}", path.display());
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(self.to_svg(width, height).as_bytes())
+            .and_then(|()| file.flush())
+            .with_context(|| format!("failed to write svg: {path:?}"))
+    }
+}
+
+impl PixState {
+    /// Convert the recorded shapes and text of a [`DisplayList`] to an SVG document the size of
+    /// the current render target. Text is wrapped, aligned and spaced using the fonts it was
+    /// drawn with. See [`DisplayList::to_svg`] for details.
+    ///
+    /// # Errors
+    ///
+    /// If the current render target is closed or dropped, or the renderer fails to load a font,
+    /// then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let list = s.record_display_list(|s| {
+    ///         s.text_align(TextAlign::Center, VerticalAlign::Top);
+    ///         s.text("Centered\ntext")?;
+    ///         Ok(())
+    ///     })?;
+    ///     let svg = s.display_list_svg(&list)?;
+    ///     assert!(svg.contains(r#"text-anchor="middle""#));
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn display_list_svg(&mut self, list: &DisplayList) -> PixResult<String> {
+        let layouts = list
+            .iter()
+            .map(|command| self.layout_text_command(command))
+            .collect::<PixResult<Vec<_>>>()?;
+        let (width, height) = self.dimensions()?;
+        Ok(list.svg_with_layouts(width, height, layouts))
+    }
+
+    /// Records everything drawn by `f` and saves the shapes and text to an SVG file the size of
+    /// the current render target. Drawing is still rendered to the current canvas. See
+    /// [`DisplayList::to_svg`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for any of the following:
+    ///     - The current render target is closed or dropped.
+    ///     - `f` returns an error.
+    ///     - An [`io::Error`] occurs attempting to create or write to the SVG file.
+    ///
+    /// [`io::Error`]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { values: Vec<i32> };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let draw_chart = |s: &mut PixState| -> PixResult<()> {
+    ///         for (i, value) in self.values.iter().enumerate() {
+    ///             s.rect([i as i32 * 20, 200 - value, 15, *value])?;
+    ///         }
+    ///         Ok(())
+    ///     };
+    ///     if s.key_pressed() {
+    ///         s.save_svg("chart.svg", draw_chart)?;
+    ///     } else {
+    ///         draw_chart(s)?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn save_svg<P, F>(&mut self, path: P, f: F) -> PixResult<()>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&mut PixState) -> PixResult<()>,
+    {
+        let list = self.record_display_list(f)?;
+        let svg = self.display_list_svg(&list)?;
+        let path = path.as_ref();
+        info!("Saving SVG to {}", path.display());
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(svg.as_bytes())
+            .and_then(|()| file.flush())
+            .with_context(|| format!("failed to write svg: {path:?}"))
+    }
+
+    /// Lay out the text of a [`DrawCommand::Text`] with the font it was drawn with, restoring the
+    /// current font afterwards. Returns `None` for any other command.
+    fn layout_text_command(&mut self, command: &DrawCommand) -> PixResult<Option<TextLayout>> {
+        let (text, wrap_width, font_family, font_size, font_style, align) = match *command {
+            DrawCommand::Text {
+                ref text,
+                wrap_width,
+                ref font_family,
+                font_size,
+                font_style,
+                align,
+                ..
+            } => (text, wrap_width, font_family, font_size, font_style, align),
+            _ => return Ok(None),
+        };
+        let s = &self.settings;
+        let family_changed = *font_family != s.font_family;
+        if family_changed {
+            self.renderer.font_family(font_family)?;
+        }
+        self.renderer.font_style(font_style);
+        self.renderer.text_align(align);
+        let layout = self
+            .renderer
+            .font_size(font_size)
+            .and_then(|()| self.renderer.layout_text(text, wrap_width));
+        let s = &self.settings;
+        if family_changed {
+            self.renderer.font_family(&s.font_family)?;
+        }
+        self.renderer.font_size(s.font_size)?;
+        self.renderer.font_style(s.font_style);
+        self.renderer.text_align(s.text_align);
+        layout.map(Some)
+    }
+}
+
+/// Write a single [`DrawCommand`] as an SVG element. Text is drawn using its [`TextLayout`], if
+/// provided, and `clips` counts the clip paths written so far to give each a unique id.
+fn write_command(
+    svg: &mut String,
+    command: &DrawCommand,
+    layout: Option<&TextLayout>,
+    clips: &mut usize,
+) {
+    // Writing to a `String` can't fail
+    let _ = match *command {
+        DrawCommand::Clear { color } => writeln!(
+            svg,
+            r#"<rect width="100%" height="100%"{}/>"#,
+            paint("fill", Some(color))
+        ),
+        DrawCommand::Point { p, color } => writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="1" height="1"{}/>"#,
+            p.x(),
+            p.y(),
+            paint("fill", Some(color))
+        ),
+        DrawCommand::Line {
+            line, width, color, ..
+        } => writeln!(
+            svg,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{} stroke-width="{}" stroke-linecap="round"/>"#,
+            line.start().x(),
+            line.start().y(),
+            line.end().x(),
+            line.end().y(),
+            paint("stroke", Some(color)),
+            width.max(1)
+        ),
+        DrawCommand::Bezier {
+            ref points,
+            detail,
+            stroke,
+        } => writeln!(
+            svg,
+            r#"<path d="{}" fill="none"{}/>"#,
+            bezier_path(points, detail),
+            paint("stroke", stroke)
+        ),
+        DrawCommand::Triangle {
+            tri, fill, stroke, ..
+        } => writeln!(
+            svg,
+            r#"<polygon points="{}"{}{}/>"#,
+            points_attr(tri.iter()),
+            paint("fill", fill),
+            paint("stroke", stroke)
+        ),
+        DrawCommand::Rect {
+            rect,
+            radius,
+            fill,
+            stroke,
+        } => {
+            let radius = radius.map_or_else(String::new, |radius| {
+                format!(r#" rx="{radius}" ry="{radius}""#)
+            });
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}"{radius}{}{}/>"#,
+                rect.x(),
+                rect.y(),
+                rect.width(),
+                rect.height(),
+                paint("fill", fill),
+                paint("stroke", stroke)
+            )
+        }
+        DrawCommand::Quad {
+            quad, fill, stroke, ..
+        } => writeln!(
+            svg,
+            r#"<polygon points="{}"{}{}/>"#,
+            points_attr(quad.iter()),
+            paint("fill", fill),
+            paint("stroke", stroke)
+        ),
+        DrawCommand::Polygon {
+            ref points,
+            fill,
+            stroke,
+            ..
+        } => writeln!(
+            svg,
+            r#"<polygon points="{}"{}{}/>"#,
+            points_attr(points.iter()),
+            paint("fill", fill),
+            paint("stroke", stroke)
+        ),
+        DrawCommand::Ellipse {
+            ellipse,
+            fill,
+            stroke,
+            ..
+        } => writeln!(
+            svg,
+            r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}"{}{}/>"#,
+            ellipse.x(),
+            ellipse.y(),
+            ellipse.width() / 2,
+            ellipse.height() / 2,
+            paint("fill", fill),
+            paint("stroke", stroke)
+        ),
+        DrawCommand::Arc {
+            p,
+            radius,
+            start,
+            end,
+            mode,
+            fill,
+            stroke,
+        } => {
+            let (path, fill) = arc_path(p, radius, start, end, mode, fill);
+            writeln!(
+                svg,
+                r#"<path d="{path}"{}{}/>"#,
+                paint("fill", fill),
+                paint("stroke", stroke)
+            )
+        }
+        DrawCommand::Text {
+            position,
+            ref text,
+            angle,
+            center,
+            flipped,
+            fill,
+            outline,
+            ref font_family,
+            font_size,
+            font_style,
+            align,
+            clip,
+            ..
+        } => {
+            // Outlined glyphs are drawn inset by the outline width
+            let outline = i32::from(outline);
+            let [x, y] = [position.x() + outline, position.y() + outline];
+            let mut attrs = format!(
+                r#"font-family="{}, sans-serif" font-size="{font_size}""#,
+                escape(font_family.name()),
+            );
+            if font_style.contains(FontStyle::BOLD) {
+                attrs.push_str(r#" font-weight="bold""#);
+            }
+            if font_style.contains(FontStyle::ITALIC) {
+                attrs.push_str(r#" font-style="italic""#);
+            }
+            match (
+                font_style.contains(FontStyle::UNDERLINE),
+                font_style.contains(FontStyle::STRIKETHROUGH),
+            ) {
+                (true, true) => attrs.push_str(r#" text-decoration="underline line-through""#),
+                (true, false) => attrs.push_str(r#" text-decoration="underline""#),
+                (false, true) => attrs.push_str(r#" text-decoration="line-through""#),
+                (false, false) => (),
+            }
+            let transform = text_transform(position, angle, center, flipped, layout);
+            if !transform.is_empty() {
+                let _ = write!(attrs, r#" transform="{transform}""#);
+            }
+            attrs.push_str(&paint("fill", fill));
+            if outline > 0 {
+                let _ = write!(
+                    attrs,
+                    r#"{} stroke-width="{}" stroke-linejoin="round""#,
+                    paint("stroke", fill),
+                    2 * outline
+                );
+            }
+            if let Some(clip) = clip {
+                *clips += 1;
+                let _ = write!(
+                    svg,
+                    r#"<clipPath id="clip{clips}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath><g clip-path="url(#clip{clips})">"#,
+                    clip.x(),
+                    clip.y(),
+                    clip.width(),
+                    clip.height()
+                );
+            }
+            match layout {
+                Some(layout) => {
+                    let anchor = match align {
+                        TextAlign::Left => "start",
+                        TextAlign::Center => "middle",
+                        TextAlign::Right => "end",
+                    };
+                    let _ = write!(svg, r#"<text {attrs} text-anchor="{anchor}">"#);
+                    for line in layout.lines() {
+                        let rect = line.rect();
+                        let line_x = match align {
+                            TextAlign::Left => rect.left(),
+                            TextAlign::Center => rect.center().x(),
+                            TextAlign::Right => rect.right(),
+                        };
+                        let _ = write!(
+                            svg,
+                            r#"<tspan x="{}" y="{}">{}</tspan>"#,
+                            x + line_x,
+                            y + line.baseline(),
+                            escape(&text[line.range()])
+                        );
+                    }
+                }
+                None => {
+                    let _ = write!(svg, r#"<text {attrs} dominant-baseline="hanging">"#);
+                    for (i, line) in text.lines().enumerate() {
+                        let dy = if i > 0 { "1.2em" } else { "0" };
+                        let _ = write!(
+                            svg,
+                            r#"<tspan x="{x}" y="{y}" dy="{dy}">{}</tspan>"#,
+                            escape(line)
+                        );
+                    }
+                }
+            }
+            svg.push_str("</text>");
+            if clip.is_some() {
+                svg.push_str("</g>");
+            }
+            writeln!(svg)
+        }
+        DrawCommand::Image { .. } | DrawCommand::Texture { .. } => Ok(()),
+    };
+}
+
+/// Returns an SVG paint attribute for an optional [Color], with opacity if it's not opaque.
+fn paint(attr: &str, color: Option<Color>) -> String {
+    match color {
+        Some(color) => {
+            let [red, green, blue, alpha] = color.channels();
+            let mut paint = format!(r##" {attr}="#{red:02x}{green:02x}{blue:02x}""##);
+            if alpha < 255 {
+                let _ = write!(
+                    paint,
+                    r#" {attr}-opacity="{:.3}""#,
+                    f64::from(alpha) / 255.0
+                );
+            }
+            paint
+        }
+        None => format!(r#" {attr}="none""#),
+    }
+}
+
+/// Returns the SVG transform of text drawn at `position`, rotated by `angle` degrees about
+/// `center` and flipped about the center of the text. Text is only flipped if its [`TextLayout`]
+/// is known, since that requires the size of the text.
+fn text_transform(
+    position: Point<i32>,
+    angle: Option<f64>,
+    center: Option<Point<i32>>,
+    flipped: Option<Flipped>,
+    layout: Option<&TextLayout>,
+) -> String {
+    let size = layout.map(|layout| {
+        let (width, height) = layout.size();
+        point![f64::from(width), f64::from(height)]
+    });
+    let mut transform = String::new();
+    if let Some(angle) = angle.filter(|&angle| angle != 0.0) {
+        // Rotation defaults to the center of the text, or its top-left if the size isn't known
+        let center = center.map_or_else(
+            || size.map_or_else(Point::origin, |size| size / 2.0),
+            |center| center.as_(),
+        );
+        let _ = write!(
+            transform,
+            "rotate({angle} {} {})",
+            f64::from(position.x()) + center.x(),
+            f64::from(position.y()) + center.y()
+        );
+    }
+    let scale = match flipped {
+        Some(Flipped::Horizontal) => Some((-1, 1)),
+        Some(Flipped::Vertical) => Some((1, -1)),
+        Some(Flipped::Both) => Some((-1, -1)),
+        Some(Flipped::None) | None => None,
+    };
+    if let (Some((scale_x, scale_y)), Some(size)) = (scale, size) {
+        let x = f64::from(position.x()) + size.x() / 2.0;
+        let y = f64::from(position.y()) + size.y() / 2.0;
+        if !transform.is_empty() {
+            transform.push(' ');
+        }
+        let _ = write!(
+            transform,
+            "translate({x} {y}) scale({scale_x} {scale_y}) translate({} {})",
+            -x, -y
+        );
+    }
+    transform
+}
+
+/// Returns a list of points formatted for an SVG `points` attribute.
+fn points_attr<'a, I>(points: I) -> String
+where
+    I: Iterator<Item = &'a Point<i32>>,
+{
+    points
+        .map(|p| format!("{},{}", p.x(), p.y()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns SVG path data for a Bezier curve through a list of anchor and control points. Curves
+/// of higher order than cubic are approximated by `detail` line segments.
+fn bezier_path(points: &[Point<i32>], detail: i32) -> String {
+    let coords = |ps: &[Point<i32>]| {
+        ps.iter()
+            .map(|p| format!("{} {}", p.x(), p.y()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    match points {
+        [] => String::new(),
+        [p] => format!("M {} {}", p.x(), p.y()),
+        [p, rest @ ..] if rest.len() <= 3 => {
+            let command = ["L", "Q", "C"][rest.len() - 1];
+            format!("M {} {} {command} {}", p.x(), p.y(), coords(rest))
+        }
+        _ => {
+            let steps = detail.max(2);
+            let mut path = String::new();
+            for step in 0..=steps {
+                let t = f64::from(step) / f64::from(steps);
+                // De Casteljau's algorithm
+                let mut ps: Vec<Point<f64>> = points.iter().map(Point::as_).collect();
+                while ps.len() > 1 {
+                    ps = ps
+                        .windows(2)
+                        .map(|w| {
+                            point![
+                                w[0].x() + (w[1].x() - w[0].x()) * t,
+                                w[0].y() + (w[1].y() - w[0].y()) * t
+                            ]
+                        })
+                        .collect();
+                }
+                let command = if step == 0 { "M" } else { " L" };
+                let _ = write!(path, "{command} {:.2} {:.2}", ps[0].x(), ps[0].y());
+            }
+            path
+        }
+    }
+}
+
+/// Returns SVG path data for an arc, along with the fill to use. Angles are in degrees, clockwise
+/// from the positive x-axis. Open arcs are never filled.
+fn arc_path(
+    p: Point<i32>,
+    radius: i32,
+    start: i32,
+    end: i32,
+    mode: ArcMode,
+    fill: Option<Color>,
+) -> (String, Option<Color>) {
+    let point_at = |angle: i32| {
+        let (sin, cos) = (f64::from(angle) * PI / 180.0).sin_cos();
+        let radius = f64::from(radius);
+        (
+            f64::from(p.x()) + radius * cos,
+            f64::from(p.y()) + radius * sin,
+        )
+    };
+    let sweep = (end - start).rem_euclid(360);
+    let ((x1, y1), (x2, y2)) = (point_at(start), point_at(end));
+    let large_arc = u8::from(sweep > 180);
+    let arc = format!("A {radius} {radius} 0 {large_arc} 1 {x2:.2} {y2:.2}");
+    match mode {
+        ArcMode::Pie => (
+            format!("M {} {} L {x1:.2} {y1:.2} {arc} Z", p.x(), p.y()),
+            fill,
+        ),
+        ArcMode::Default => (format!("M {x1:.2} {y1:.2} {arc}"), None),
+    }
+}
+
+/// Escape text for use in SVG content.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::text_layout::{FontMetrics, GlyphAdvances};
+
+    #[test]
+    fn svg_keeps_fill_and_stroke() {
+        let list: DisplayList = [
+            DrawCommand::Ellipse {
+                ellipse: ellipse![50, 50, 20, 10],
+                smooth: true,
+                fill: Some(Color::rgba(255, 0, 0, 51)),
+                stroke: Some(Color::BLACK),
+            },
+            DrawCommand::Line {
+                line: line_![0, 0, 10, 10],
+                smooth: false,
+                width: 3,
+                color: Color::WHITE,
+            },
+        ]
+        .into_iter()
+        .collect();
+        let svg = list.to_svg(100, 100);
+        assert!(svg.contains(
+            r##"<ellipse cx="50" cy="50" rx="10" ry="5" fill="#ff0000" fill-opacity="0.200" stroke="#000000"/>"##
+        ));
+        assert!(svg.contains(r#"stroke-width="3""#));
+    }
+
+    fn text(text: &str, align: TextAlign, clip: Option<Rect<i32>>) -> DrawCommand {
+        DrawCommand::Text {
+            position: point![10, 20],
+            text: text.to_owned(),
+            wrap_width: None,
+            angle: None,
+            center: None,
+            flipped: None,
+            fill: Some(Color::WHITE),
+            outline: 0,
+            font_family: Font::default(),
+            font_size: 12,
+            font_style: FontStyle::NORMAL,
+            align,
+            clip,
+        }
+    }
+
+    fn layout(text: &str, align: TextAlign) -> PixResult<TextLayout> {
+        let metrics = FontMetrics {
+            height: 10,
+            line_skip: 12,
+            ascent: 8,
+        };
+        let lines = text
+            .split('\n')
+            .scan(0, |start, line| {
+                let range = *start..*start + line.len();
+                *start = range.end + 1;
+                Some(range)
+            })
+            .collect();
+        let measure = |line: &str| Ok(8 * line.len() as u32);
+        TextLayout::from_lines(
+            text,
+            lines,
+            align,
+            metrics,
+            &mut GlyphAdvances::default(),
+            measure,
+        )
+    }
+
+    #[test]
+    fn svg_text_uses_layout() -> PixResult<()> {
+        let list: DisplayList = [text("ab\nc", TextAlign::Center, None)]
+            .into_iter()
+            .collect();
+        let layouts = [Some(layout("ab\nc", TextAlign::Center)?)];
+        let svg = list.svg_with_layouts(100, 100, layouts);
+        assert!(svg.contains(r#"text-anchor="middle""#));
+        assert!(svg.contains(r#"<tspan x="18" y="28">ab</tspan><tspan x="18" y="40">c</tspan>"#));
+
+        let list: DisplayList = [text("ab", TextAlign::Right, Some(rect![0, 0, 5, 5]))]
+            .into_iter()
+            .collect();
+        let layouts = [Some(layout("ab", TextAlign::Right)?)];
+        let svg = list.svg_with_layouts(100, 100, layouts);
+        assert!(svg.contains(r#"<clipPath id="clip1"><rect x="0" y="0" width="5" height="5"/>"#));
+        assert!(svg.contains(r#"text-anchor="end""#));
+        assert!(svg.contains(r#"<tspan x="26" y="28">ab</tspan></text></g>"#));
+        Ok(())
+    }
+
+    #[test]
+    fn svg_escapes_text() {
+        assert_eq!(escape("a < b & c"), "a &lt; b &amp; c");
+    }
+
+    #[test]
+    fn svg_bezier_paths() {
+        let points = [point![0, 0], point![10, 0], point![10, 10], point![0, 10]];
+        assert_eq!(bezier_path(&points, 20), "M 0 0 C 10 0 10 10 0 10");
+        assert_eq!(bezier_path(&points[..2], 20), "M 0 0 L 10 0");
+    }
+}
@@ -79,8 +79,9 @@ impl PixState {
     /// ```
     #[inline]
     pub fn clear(&mut self) -> PixResult<()> {
-        self.renderer.set_draw_color(self.settings.background)?;
-        self.renderer.clear()
+        self.draw_command(DrawCommand::Clear {
+            color: self.settings.background,
+        })
     }
 
     /// Save a portion `src` of the currently rendered target to a [png] file. Passing `None` for
@@ -124,10 +125,10 @@ impl PixState {
             self.update_texture(render_texture, None, bytes, self.width()? as usize * 4)?;
             // Render the `src` rect from texture onto another texture, and save it
             let src_texture = self.create_texture(src.width() as u32, src.height() as u32, None)?;
-            self.set_texture_target(src_texture)?;
+            self.push_texture_target(src_texture)?;
             self.texture(render_texture, src, None)?;
             self.save_canvas(None, path)?;
-            self.clear_texture_target();
+            self.pop_texture_target();
             self.delete_texture(render_texture)?;
             self.delete_texture(src_texture)?;
             Ok(())
@@ -910,6 +910,21 @@ impl EngineBuilder {
         self
     }
 
+    /// Set a logical rendering size independent of the window dimensions, which is scaled to fit
+    /// the window based on the [`ScaleMode`]. Render target dimensions and mouse coordinates are
+    /// reported in logical size coordinates.
+    pub fn logical_size(&mut self, width: u32, height: u32) -> &mut Self {
+        self.settings.logical_size = Some((width, height));
+        self
+    }
+
+    /// Set how the [`EngineBuilder::logical_size`] is scaled to fit the window. Defaults to
+    /// [`ScaleMode::Fit`].
+    pub fn scale_mode(&mut self, mode: ScaleMode) -> &mut Self {
+        self.settings.scale_mode = mode;
+        self
+    }
+
     /// Set audio sample rate in Hz (samples per second). Defaults to device fallback sample rate.
     pub fn audio_sample_rate(&mut self, sample_rate: i32) -> &mut Self {
         self.settings.audio_sample_rate = Some(sample_rate);
@@ -994,7 +1009,8 @@ impl EngineBuilder {
     }
 
     /// Set a custom text cache size other than the default of `500`.
-    /// Affects text rendering caching operations.
+    /// Affects font data and glyph atlas caching, where a glyph atlas is created for each font
+    /// family, size and style used to render text.
     pub fn text_cache(&mut self, size: NonZeroUsize) -> &mut Self {
         self.settings.text_cache_size = size;
         self
@@ -1120,7 +1136,10 @@ impl Engine {
             let on_stop = app.on_stop(&mut self.state);
             if self.state.should_quit() {
                 info!("Quitting `Engine`...");
-                break 'on_stop on_stop.and(result);
+                let result = on_stop.and(result);
+                #[cfg(feature = "recording")]
+                let result = result.and(self.state.stop_recording());
+                break 'on_stop result;
             }
         }
     }
@@ -104,6 +104,7 @@ use crate::{
 
 pub mod layout;
 pub mod system;
+pub mod text_layout;
 pub mod theme;
 pub mod widgets;
 
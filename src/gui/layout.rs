@@ -120,6 +120,9 @@ impl PixState {
     /// change any drawing settings. Settings changed inside the closure will not persist. Returns
     /// `true` if a tab selection was changed.
     ///
+    /// [`PixState::next_width`] limits the width of each tab, truncating long tab labels with an
+    /// ellipsis.
+    ///
     /// # Errors
     ///
     /// If the renderer fails to draw to the current render target, then an error is returned.
@@ -176,8 +179,10 @@ impl PixState {
         let font_size = s.theme.font_size;
         let fpad = s.theme.spacing.frame_pad;
         let ipad = s.theme.spacing.item_pad;
+        let max_width = s.ui.next_width.take();
 
         let mut changed = false;
+        let mut bar: Option<Rect<i32>> = None;
         for (i, tab) in tabs.iter().enumerate() {
             if i > 0 {
                 s.same_line([-ipad.x() + 2, 0]);
@@ -193,7 +198,12 @@ impl PixState {
 
             // Calculate tab size
             let (width, height) = s.text_size(tab_label)?;
-            let tab_rect = rect![pos, width, height].offset_size(4 * ipad);
+            let text_rect = rect![pos, width, height];
+            let text_rect = max_width.map_or(text_rect, |max_width| {
+                text_rect.split_horizontally(max_width).0
+            });
+            let tab_rect = text_rect.offset_size(4 * ipad);
+            bar = Some(bar.map_or(tab_rect, |bar| bar.union(tab_rect)));
 
             // Check hover/active/keyboard focus
             let hovered = s.focused() && s.ui.try_hover(id, &tab_rect);
@@ -207,7 +217,7 @@ impl PixState {
             // Render
             s.rect_mode(RectMode::Corner);
             let clip = tab_rect.offset_size([1, 0]);
-            s.clip(clip)?;
+            s.push_clip(clip)?;
             if hovered {
                 s.frame_cursor(&Cursor::hand())?;
             }
@@ -223,7 +233,8 @@ impl PixState {
                 s.fill(colors.background);
             }
             if active {
-                s.clip(tab_rect.offset_size([2, 0]))?;
+                s.pop_clip()?;
+                s.push_clip(tab_rect.offset_size([2, 0]))?;
                 s.rect(tab_rect.offset([1, 1]))?;
             } else {
                 s.rect(tab_rect)?;
@@ -241,8 +252,10 @@ impl PixState {
             } else {
                 s.fill(colors.secondary_variant.blended(bg, 0.60));
             }
+            s.wrap(text_rect.width().max(0) as u32);
+            s.text_overflow(TextOverflow::Ellipsis);
             s.text(tab_label)?;
-            s.clip(None)?;
+            s.pop_clip()?;
 
             s.ui.pop_cursor();
             s.pop();
@@ -256,8 +269,10 @@ impl PixState {
             }
         }
 
+        // Overlap the separator with the bottom of the tabs
         let pos = s.cursor_pos();
-        s.set_cursor_pos([pos.x(), pos.y() - ipad.y() - font_size as i32 / 2]);
+        let bottom = bar.map_or(pos.y() - ipad.y(), |bar| bar.bottom());
+        s.set_cursor_pos([pos.x(), bottom - font_size as i32 / 2]);
         s.separator()?;
         s.spacing()?;
 
@@ -352,16 +367,17 @@ impl PixState {
         let colors = s.theme.colors;
         let pad = s.theme.spacing.frame_pad;
         let height = clamp_size(s.theme.font_size);
-        let y = pos.y() + height / 2;
+        let width = s.ui_width()?;
+        let separator = rect![pos, width, height];
+        let y = separator.center().y();
 
         s.push();
 
         s.stroke(colors.disabled());
-        let width = s.ui_width()?;
         s.line(line_![pad.x(), y, width, y])?;
 
         s.pop();
-        s.advance_cursor([width, height]);
+        s.advance_cursor(separator.size());
 
         Ok(())
     }
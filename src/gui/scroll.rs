@@ -72,26 +72,26 @@ impl PixState {
         let right = scroll_area.width() - fpad.x();
         let bottom = scroll_area.height() - fpad.y();
 
-        s.set_texture_target(texture_id)?;
+        s.push_texture_target(texture_id)?;
         s.background(colors.background);
 
         s.set_cursor_pos(s.cursor_pos() - scroll);
         s.stroke(None);
         s.fill(fg);
+        s.push_clip(rect![
+            fpad.x(),
+            fpad.y(),
+            right - fpad.x(),
+            bottom - fpad.y()
+        ])?;
         f(s)?;
+        s.pop_clip()?;
         let max_cursor_pos = s.cursor_pos() + scroll;
 
-        // Since clip doesn't work texture targets, we fake it
-        s.fill(colors.background);
-        s.rect([0, 0, scroll_width, fpad.y()])?; // Top
-        s.rect([0, 0, fpad.x(), scroll_height])?; // Left
-        s.rect([right, 0, fpad.x(), scroll_height])?; // Right
-        s.rect([0, bottom, scroll_width, fpad.y()])?; // Bottom
-
         s.stroke(stroke);
         s.fill(None);
         s.rect([0, 0, scroll_width, scroll_height])?;
-        s.clear_texture_target();
+        s.pop_texture_target();
 
         s.ui.reset_column_offset();
         s.ui.clear_mouse_offset();
@@ -105,7 +105,8 @@ impl PixState {
         let total_width = max_cursor_pos.x() + s.ui.last_width() + fpad.x();
         let total_height = max_cursor_pos.y() + fpad.y();
         let rect = s.scroll(id, scroll_area, total_width, total_height)?;
-        s.advance_cursor([rect.width().max(label_width), rect.bottom() - pos.y()]);
+        let label_rect = rect![pos, label_width, label_height];
+        s.advance_cursor(rect.union(label_rect).size());
 
         Ok(())
     }
@@ -303,11 +304,11 @@ impl PixState {
         if active {
             new_value = match dir {
                 Horizontal => {
-                    let mx = (s.mouse_pos().x() - rect.x()).clamp(0, rect.width());
+                    let mx = rect.clamp_point(s.mouse_pos()).x() - rect.x();
                     (mx * max) / rect.width()
                 }
                 Vertical => {
-                    let my = (s.mouse_pos().y() - rect.y()).clamp(0, rect.height());
+                    let my = rect.clamp_point(s.mouse_pos()).y() - rect.y();
                     (my * max) / rect.height()
                 }
             };
@@ -0,0 +1,557 @@
+//! Text layout and measurement methods.
+//!
+//! A [`TextLayout`] describes where each line and glyph of a piece of text is drawn using the
+//! current font, font size, [`PixState::wrap`] width and [`PixState::text_align`]. This allows
+//! building editors, selection highlighting and caret placement on top of [`PixState::text`].
+//!
+//! All positions are relative to the top-left of the text as drawn with [`TextAlign::Left`] and
+//! [`VerticalAlign::Top`], and indexes are byte indexes into the laid out text.
+//!
+//! Provided types:
+//!
+//! - [`TextLayout`]: The lines and glyphs of laid out text.
+//! - [`TextLine`]: A single line of laid out text.
+//! - [`TextGlyph`]: A single laid out character.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::layout_text`]
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App { caret: usize };
+//! # impl PixEngine for App {
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     let text = "Click to place the caret";
+//!     let pos = s.cursor_pos();
+//!     let layout = s.layout_text(text, None)?;
+//!     if s.mouse_clicked(Mouse::Left) {
+//!         self.caret = layout.hit_test(s.mouse_pos() - pos);
+//!     }
+//!     s.text(text)?;
+//!     let caret: Point<i32> = (pos + layout.caret_position(self.caret)).into();
+//!     let height = (layout.ascent() + layout.descent()) as i32;
+//!     s.line(line_![caret, point![caret.x(), caret.y() + height]])?;
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+// Every getter is marked `#[must_use]`, including those returning types that already are
+#![allow(clippy::double_must_use)]
+
+use crate::{error::Result, prelude::*, renderer::Rendering};
+use std::{collections::HashMap, ops::Range};
+
+/// The vertical metrics of a font used to lay out text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct FontMetrics {
+    /// The height of a line.
+    pub(crate) height: u32,
+    /// The distance between the tops of consecutive lines.
+    pub(crate) line_skip: i32,
+    /// The distance from the top of a line to its baseline.
+    pub(crate) ascent: u32,
+}
+
+/// Cached advances of characters used to lay out text with a single font, keyed by each character
+/// and the character following it so that kerning is preserved.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GlyphAdvances {
+    advances: HashMap<(char, Option<char>), i32>,
+}
+
+impl GlyphAdvances {
+    /// Returns the advance of `c` when followed by `next`, or the rendered width of `c` if it ends
+    /// a line, measuring it with `measure` if it hasn't been cached.
+    fn get<F>(&mut self, c: char, next: Option<char>, measure: &mut F) -> Result<i32>
+    where
+        F: FnMut(&str) -> Result<u32>,
+    {
+        if let Some(&advance) = self.advances.get(&(c, next)) {
+            return Ok(advance);
+        }
+        let advance = match next {
+            Some(next) => {
+                let mut pair = String::with_capacity(8);
+                pair.push(c);
+                pair.push(next);
+                measure(&pair)? as i32 - self.get(next, None, measure)?
+            }
+            None => measure(c.encode_utf8(&mut [0; 4]))? as i32,
+        };
+        self.advances.insert((c, next), advance);
+        Ok(advance)
+    }
+}
+
+/// A single laid out character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TextGlyph {
+    index: usize,
+    c: char,
+    line: usize,
+    rect: Rect<i32>,
+}
+
+impl TextGlyph {
+    /// Returns the byte index of the character in the laid out text.
+    #[inline]
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the character.
+    #[inline]
+    #[must_use]
+    pub const fn char(&self) -> char {
+        self.c
+    }
+
+    /// Returns the index of the line the character is on.
+    #[inline]
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the bounding rectangle of the character, spanning its advance horizontally and
+    /// the height of its line vertically.
+    #[inline]
+    #[must_use]
+    pub const fn rect(&self) -> Rect<i32> {
+        self.rect
+    }
+}
+
+/// A single line of laid out text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextLine {
+    range: Range<usize>,
+    glyphs: Range<usize>,
+    rect: Rect<i32>,
+    baseline: i32,
+}
+
+impl TextLine {
+    /// Returns the byte range of the line in the laid out text, excluding any line break or the
+    /// space the line was wrapped on.
+    #[inline]
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the range of the line in [`TextLayout::glyphs`].
+    #[inline]
+    #[must_use]
+    pub fn glyphs(&self) -> Range<usize> {
+        self.glyphs.clone()
+    }
+
+    /// Returns the bounding rectangle of the line.
+    #[inline]
+    #[must_use]
+    pub const fn rect(&self) -> Rect<i32> {
+        self.rect
+    }
+
+    /// Returns the y-coordinate of the baseline of the line.
+    #[inline]
+    #[must_use]
+    pub const fn baseline(&self) -> i32 {
+        self.baseline
+    }
+}
+
+/// The lines and glyphs of laid out text, relative to the top-left of the text.
+///
+/// Returned by [`PixState::layout_text`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct TextLayout {
+    size: (u32, u32),
+    ascent: u32,
+    descent: u32,
+    lines: Vec<TextLine>,
+    glyphs: Vec<TextGlyph>,
+}
+
+impl TextLayout {
+    /// Lay out text, breaking lines on newlines and, if `wrap_width` is provided, on spaces once a
+    /// line exceeds `wrap_width`. Each line is then aligned within the width of the widest line.
+    ///
+    /// `measure` returns the rendered width of a single line of text. Glyphs are positioned using
+    /// the advance of each character pair, so kerning is preserved. Advances are cached in
+    /// `advances`, so that each character pair is only measured once.
+    pub(crate) fn new<F>(
+        text: &str,
+        wrap_width: Option<u32>,
+        align: TextAlign,
+        metrics: FontMetrics,
+        advances: &mut GlyphAdvances,
+        mut measure: F,
+    ) -> Result<Self>
+    where
+        F: FnMut(&str) -> Result<u32>,
+    {
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for paragraph in text.split('\n') {
+            let paragraph_start = offset;
+            offset += paragraph.len() + 1;
+            let paragraph = paragraph.strip_suffix('\r').unwrap_or(paragraph);
+            let wrap_width = match wrap_width {
+                Some(wrap_width) => wrap_width as i32,
+                None => {
+                    lines.push(paragraph_start..paragraph_start + paragraph.len());
+                    continue;
+                }
+            };
+            // Line widths are summed from character advances as each space is reached
+            let mut start = 0;
+            let mut start_pen = 0;
+            let mut end: Option<(usize, i32)> = None;
+            let mut pen = 0;
+            let mut last: Option<(char, i32)> = None;
+            let mut chars = paragraph.char_indices().peekable();
+            loop {
+                let current = chars.next();
+                if current.map_or(true, |(_, c)| c == ' ') {
+                    if let Some((end, end_pen)) = end.filter(|&(end, _)| end > start) {
+                        // The last character ends the line, so isn't kerned
+                        let width = match last {
+                            Some((c, advance)) => {
+                                pen - advance + advances.get(c, None, &mut measure)?
+                            }
+                            None => pen,
+                        };
+                        if width - start_pen > wrap_width {
+                            lines.push(paragraph_start + start..paragraph_start + end);
+                            start = end + 1;
+                            start_pen = end_pen;
+                        }
+                    }
+                }
+                let (idx, c) = match current {
+                    Some(current) => current,
+                    None => break,
+                };
+                let next = chars.peek().map(|&(_, next)| next);
+                let advance = advances.get(c, next, &mut measure)?;
+                if c == ' ' {
+                    end = Some((idx, pen + advance));
+                }
+                pen += advance;
+                last = Some((c, advance));
+            }
+            lines.push(paragraph_start + start..paragraph_start + paragraph.len());
+        }
+        Self::from_lines(text, lines, align, metrics, advances, measure)
+    }
+
+    /// Lay out text that has already been broken into lines given as byte ranges of `text`. Each
+    /// line is aligned within the width of the widest line.
+    pub(crate) fn from_lines<F>(
+        text: &str,
+        lines: Vec<Range<usize>>,
+        align: TextAlign,
+        metrics: FontMetrics,
+        advances: &mut GlyphAdvances,
+        mut measure: F,
+    ) -> Result<Self>
+    where
+        F: FnMut(&str) -> Result<u32>,
+    {
+        let FontMetrics {
+            height,
+            line_skip,
+            ascent,
+        } = metrics;
+        let line_widths = lines
+            .iter()
+            .map(|range| measure(&text[range.clone()]))
+            .collect::<Result<Vec<u32>>>()?;
+        let width = line_widths.iter().copied().max().unwrap_or(0);
+        let mut text_lines = Vec::with_capacity(lines.len());
+        let mut glyphs = Vec::new();
+        for (row, (range, line_width)) in lines.into_iter().zip(line_widths).enumerate() {
+            let y = row as i32 * line_skip;
+            let start_x = align.offset(width - line_width);
+            let mut x = start_x;
+            let first_glyph = glyphs.len();
+            let mut chars = text[range.clone()].char_indices().peekable();
+            while let Some((idx, c)) = chars.next() {
+                let advance = match chars.peek() {
+                    Some(&(_, next)) => advances.get(c, Some(next), &mut measure)?,
+                    // The last glyph spans the rest of the line
+                    None => start_x + line_width as i32 - x,
+                };
+                glyphs.push(TextGlyph {
+                    index: range.start + idx,
+                    c,
+                    line: row,
+                    rect: rect![x, y, advance.max(0), height as i32],
+                });
+                x += advance;
+            }
+            text_lines.push(TextLine {
+                range,
+                glyphs: first_glyph..glyphs.len(),
+                rect: rect![start_x, y, line_width as i32, height as i32],
+                baseline: y + ascent as i32,
+            });
+        }
+        let total_height = height as i32 + (text_lines.len() as i32 - 1) * line_skip;
+        Ok(Self {
+            size: (width, total_height.max(0) as u32),
+            ascent,
+            descent: height.saturating_sub(ascent),
+            lines: text_lines,
+            glyphs,
+        })
+    }
+
+    /// Returns the rendered dimensions of the text as `(width, height)`.
+    #[inline]
+    #[must_use]
+    pub const fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Returns the rendered width of the text.
+    #[inline]
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.size.0
+    }
+
+    /// Returns the rendered height of the text.
+    #[inline]
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.size.1
+    }
+
+    /// Returns the distance from the top of a line to its baseline.
+    #[inline]
+    #[must_use]
+    pub const fn ascent(&self) -> u32 {
+        self.ascent
+    }
+
+    /// Returns the distance from the baseline of a line to its bottom.
+    #[inline]
+    #[must_use]
+    pub const fn descent(&self) -> u32 {
+        self.descent
+    }
+
+    /// Returns the y-coordinate of the baseline of the first line.
+    #[inline]
+    #[must_use]
+    pub fn baseline(&self) -> i32 {
+        self.lines
+            .first()
+            .map_or(self.ascent as i32, TextLine::baseline)
+    }
+
+    /// Returns the laid out lines, including empty lines.
+    #[inline]
+    #[must_use]
+    pub fn lines(&self) -> &[TextLine] {
+        &self.lines
+    }
+
+    /// Returns the laid out characters of every line.
+    #[inline]
+    #[must_use]
+    pub fn glyphs(&self) -> &[TextGlyph] {
+        &self.glyphs
+    }
+
+    /// Returns the byte index of the caret position closest to a point relative to the top-left
+    /// of the text. Points above or below the text select the first or last line and points past
+    /// the end of a line select the end of that line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { caret: usize };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let pos = s.cursor_pos();
+    ///     let layout = s.layout_text("Some text", None)?;
+    ///     if s.mouse_clicked(Mouse::Left) {
+    ///         self.caret = layout.hit_test(s.mouse_pos() - pos);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn hit_test<P: Into<Point<i32>>>(&self, p: P) -> usize {
+        let p = p.into();
+        let line = match self
+            .lines
+            .iter()
+            .find(|line| p.y() < line.rect.bottom())
+            .or_else(|| self.lines.last())
+        {
+            Some(line) => line,
+            None => return 0,
+        };
+        self.glyphs[line.glyphs()]
+            .iter()
+            .find(|glyph| p.x() < glyph.rect.center().x())
+            .map_or(line.range.end, TextGlyph::index)
+    }
+
+    /// Returns the top-left position of a caret placed before the character at the byte `index`
+    /// relative to the top-left of the text. Indexes at or past the end of a line are placed at
+    /// the end of that line.
+    #[must_use]
+    pub fn caret_position(&self, index: usize) -> Point<i32> {
+        if let Some(glyph) = self.glyphs.iter().find(|glyph| glyph.index == index) {
+            return glyph.rect.top_left();
+        }
+        self.lines
+            .iter()
+            .find(|line| index <= line.range.end)
+            .or_else(|| self.lines.last())
+            .map_or_else(Point::default, |line| {
+                point![line.rect.right(), line.rect.top()]
+            })
+    }
+}
+
+impl PixState {
+    /// Lay out text using the current font, font size and [`PixState::text_align`], wrapping
+    /// lines to `wrap_width` if provided. The returned [`TextLayout`] provides line breaks,
+    /// per-glyph rectangles, baseline metrics and hit-testing relative to the top-left of the
+    /// text.
+    ///
+    /// # Errors
+    ///
+    /// If the renderer fails to load the current font, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let text = "Some selected text";
+    ///     let pos = s.cursor_pos();
+    ///     let layout = s.layout_text(text, 100)?;
+    ///     // Highlight "selected"
+    ///     s.fill(Color::BLUE);
+    ///     for glyph in layout.glyphs().iter().filter(|g| (5..13).contains(&g.index())) {
+    ///         s.rect(glyph.rect() + pos)?;
+    ///     }
+    ///     s.wrap(100);
+    ///     s.text(text)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn layout_text<S, W>(&self, text: S, wrap_width: W) -> PixResult<TextLayout>
+    where
+        S: AsRef<str>,
+        W: Into<Option<u32>>,
+    {
+        self.renderer.layout_text(text.as_ref(), wrap_width.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: FontMetrics = FontMetrics {
+        height: 16,
+        line_skip: 20,
+        ascent: 12,
+    };
+
+    fn layout(text: &str, wrap_width: Option<u32>, align: TextAlign) -> Result<TextLayout> {
+        let mut advances = GlyphAdvances::default();
+        TextLayout::new(text, wrap_width, align, METRICS, &mut advances, |s| {
+            Ok(10 * s.chars().count() as u32)
+        })
+    }
+
+    #[test]
+    fn layout_lines_and_glyphs() -> Result<()> {
+        let layout = layout("ab cd\nefg", Some(25), TextAlign::Right)?;
+        assert_eq!(layout.size(), (30, 56));
+        let ranges: Vec<_> = layout.lines().iter().map(TextLine::range).collect();
+        assert_eq!(ranges, vec![0..2, 3..5, 6..9]);
+        assert_eq!(layout.lines()[1].rect(), rect![10, 20, 20, 16]);
+        assert_eq!(layout.lines()[1].baseline(), 32);
+        assert_eq!(layout.descent(), 4);
+        let glyph = layout.glyphs()[layout.lines()[1].glyphs()][1];
+        assert_eq!((glyph.index(), glyph.char()), (4, 'd'));
+        assert_eq!(glyph.rect(), rect![20, 20, 10, 16]);
+        Ok(())
+    }
+
+    #[test]
+    fn advances_are_cached() -> Result<()> {
+        let text = "ab ab ab ab";
+        let mut advances = GlyphAdvances::default();
+        let mut calls = 0;
+        let mut measure = |s: &str| {
+            calls += 1;
+            Ok(10 * s.chars().count() as u32)
+        };
+        let layout = TextLayout::new(
+            text,
+            Some(55),
+            TextAlign::Left,
+            METRICS,
+            &mut advances,
+            &mut measure,
+        )?;
+        assert_eq!(layout.lines().len(), 2);
+        // Only the 3 character pairs and 3 characters are measured, along with each line
+        assert_eq!(calls, 8);
+
+        calls = 0;
+        let mut measure = |s: &str| {
+            calls += 1;
+            Ok(10 * s.chars().count() as u32)
+        };
+        let cached = TextLayout::new(
+            text,
+            Some(55),
+            TextAlign::Left,
+            METRICS,
+            &mut advances,
+            &mut measure,
+        )?;
+        assert_eq!(cached, layout);
+        assert_eq!(calls, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn hit_test_and_caret() -> Result<()> {
+        let layout = layout("ab\ncd", None, TextAlign::Left)?;
+        assert_eq!(layout.hit_test([-5, -5]), 0);
+        assert_eq!(layout.hit_test([6, 5]), 1);
+        assert_eq!(layout.hit_test([50, 5]), 2);
+        assert_eq!(layout.hit_test([14, 25]), 4);
+        assert_eq!(layout.hit_test([0, 100]), 3);
+        assert_eq!(layout.caret_position(1), point![10, 0]);
+        assert_eq!(layout.caret_position(2), point![20, 0]);
+        assert_eq!(layout.caret_position(4), point![10, 20]);
+        assert_eq!(layout.caret_position(99), point![20, 20]);
+        Ok(())
+    }
+}
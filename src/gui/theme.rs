@@ -31,8 +31,15 @@ use std::{
     borrow::Cow,
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
+    iter, mem,
 };
 
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use bitmap::{BitmapFont, BitmapSrc};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod bitmap;
+
 /// A hashed identifier for internal state management.
 pub(crate) type FontId = u64;
 
@@ -220,7 +227,8 @@ impl ThemeBuilder {
     }
 }
 
-/// Represents a font family name along with the font glyph source.
+/// Represents a font family name along with the font glyph source and an ordered list of fallback
+/// fonts used to draw characters missing from this font.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[must_use]
 pub struct Font {
@@ -229,12 +237,14 @@ pub struct Font {
     #[cfg(not(target_arch = "wasm32"))]
     /// Data source for the font.
     pub(crate) source: FontSrc,
+    /// Fonts used, in order, to draw characters missing from this font.
+    pub(crate) fallbacks: Vec<Font>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl Default for Font {
     fn default() -> Self {
-        Self::EMULOGIC
+        Self::EMULOGIC.with_fallback(Self::NOTO)
     }
 }
 
@@ -274,6 +284,7 @@ impl Font {
             name: Cow::Borrowed(name),
             #[cfg(not(target_arch = "wasm32"))]
             source: FontSrc::None,
+            fallbacks: Vec::new(),
         }
     }
 
@@ -284,6 +295,7 @@ impl Font {
         Self {
             name: Cow::Borrowed(name),
             source: FontSrc::from_bytes(bytes),
+            fallbacks: Vec::new(),
         }
     }
 
@@ -298,9 +310,156 @@ impl Font {
         Self {
             name: name.into(),
             source: FontSrc::from_file(path),
+            fallbacks: Vec::new(),
+        }
+    }
+
+    /// Constructs a new bitmap `Font` instance from an [AngelCode
+    /// BMFont](https://www.angelcode.com/products/bmfont/) `.fnt` descriptor in text format. Page
+    /// images must be `.png` files and are loaded relative to the descriptor.
+    ///
+    /// Bitmap fonts are drawn at the largest integer multiple of their line height that fits
+    /// within the current font size, and ignore [`FontStyle`]s.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.font_family(Font::from_bmfont("Pixel", "./pixel.fnt"))?;
+    ///     s.text("Crisp pixel text")?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    pub fn from_bmfont<S, P>(name: S, path: P) -> Self
+    where
+        S: Into<Cow<'static, str>>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            name: name.into(),
+            source: FontSrc::Bitmap(BitmapSrc::BmFont(path.into())),
+            fallbacks: Vec::new(),
         }
     }
 
+    /// Constructs a new bitmap `Font` instance from a static PSF1 or PSF2 console font byte array.
+    ///
+    /// See [`Font::from_bmfont`] for how bitmap fonts are scaled.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    pub const fn from_psf_bytes(name: &'static str, bytes: &'static [u8]) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            source: FontSrc::Bitmap(BitmapSrc::PsfBytes(bytes)),
+            fallbacks: Vec::new(),
+        }
+    }
+
+    /// Constructs a new bitmap `Font` instance from a PSF1 or PSF2 console font file.
+    ///
+    /// See [`Font::from_bmfont`] for how bitmap fonts are scaled.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    pub fn from_psf<S, P>(name: S, path: P) -> Self
+    where
+        S: Into<Cow<'static, str>>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            name: name.into(),
+            source: FontSrc::Bitmap(BitmapSrc::PsfPath(path.into())),
+            fallbacks: Vec::new(),
+        }
+    }
+
+    /// Constructs a new bitmap `Font` instance from a `.png` glyph sheet laid out in a fixed grid
+    /// of `cell_size` `(width, height)` cells. `chars` lists the glyphs in the sheet from left to
+    /// right, top to bottom.
+    ///
+    /// See [`Font::from_bmfont`] for how bitmap fonts are scaled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.font_family(Font::from_glyph_sheet(
+    ///         "Sheet",
+    ///         "./glyphs.png",
+    ///         (8, 8),
+    ///         " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    ///     ))?;
+    ///     s.text("HELLO WORLD")?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    pub fn from_glyph_sheet<S, P, C>(name: S, path: P, cell_size: (u32, u32), chars: C) -> Self
+    where
+        S: Into<Cow<'static, str>>,
+        P: Into<PathBuf>,
+        C: Into<Cow<'static, str>>,
+    {
+        Self {
+            name: name.into(),
+            source: FontSrc::Bitmap(BitmapSrc::GlyphSheet {
+                path: path.into(),
+                cell_size,
+                chars: chars.into(),
+            }),
+            fallbacks: Vec::new(),
+        }
+    }
+
+    /// Add a fallback font used to draw characters missing from this font and any previously added
+    /// fallbacks. Fallbacks of `font` are added after it.
+    ///
+    /// Fallbacks are not supported by the terminal renderer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.font_family(
+    ///         Font::EMULOGIC
+    ///             .with_fallback(Font::NOTO)
+    ///             .with_fallback(Font::from_file("CJK", "./noto_sans_cjk.ttf")),
+    ///     )?;
+    ///     s.text("Héllo, 世界")?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn with_fallback(mut self, mut font: Font) -> Self {
+        let fallbacks = mem::take(&mut font.fallbacks);
+        self.fallbacks.push(font);
+        self.fallbacks.extend(fallbacks);
+        self
+    }
+
+    /// Add an ordered list of fallback fonts. See [`Font::with_fallback`].
+    #[inline]
+    pub fn with_fallbacks<I>(self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = Font>,
+    {
+        fonts.into_iter().fold(self, Self::with_fallback)
+    }
+
     /// Returns the name of the font family.
     #[inline]
     #[must_use]
@@ -308,6 +467,18 @@ impl Font {
         self.name.as_ref()
     }
 
+    /// Returns the ordered list of fallback fonts.
+    #[inline]
+    pub fn fallbacks(&self) -> &[Font] {
+        &self.fallbacks
+    }
+
+    /// Returns this font followed by its fallbacks.
+    #[inline]
+    pub(crate) fn faces(&self) -> impl Iterator<Item = &Font> {
+        iter::once(self).chain(&self.fallbacks)
+    }
+
     /// Returns the source data of the font family.
     #[cfg(not(target_arch = "wasm32"))]
     #[inline]
@@ -316,10 +487,22 @@ impl Font {
         &self.source
     }
 
-    /// Returns the hashed identifier for this font family.
+    /// Returns the hashed identifier for this font family, including its fallbacks.
     #[inline]
     #[must_use]
     pub fn id(&self) -> FontId {
+        let mut hasher = DefaultHasher::new();
+        for face in self.faces() {
+            face.name.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Returns the hashed identifier for this font face, excluding its fallbacks.
+    #[inline]
+    #[must_use]
+    #[cfg_attr(feature = "terminal", allow(dead_code))]
+    pub(crate) fn face_id(&self) -> FontId {
         let mut hasher = DefaultHasher::new();
         self.name.hash(&mut hasher);
         hasher.finish()
@@ -336,6 +519,8 @@ pub(crate) enum FontSrc {
     Bytes(&'static [u8]),
     /// A path to a `.ttf` font file.
     Path(PathBuf),
+    /// A bitmap font.
+    Bitmap(BitmapSrc),
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -346,6 +531,7 @@ impl fmt::Debug for FontSrc {
             Self::Bytes(bytes) => write!(f, "Bytes([u8; {}])", bytes.len()),
             #[cfg(not(target_arch = "wasm32"))]
             Self::Path(path) => write!(f, "Path({})", path.display()),
+            Self::Bitmap(src) => write!(f, "Bitmap({src:?})"),
         }
     }
 }
@@ -0,0 +1,727 @@
+//! Bitmap font loading and layout.
+//!
+//! Supports [AngelCode BMFont](https://www.angelcode.com/products/bmfont/) text descriptors with
+//! `.png` pages, PSF1/PSF2 console fonts and fixed-grid `.png` glyph sheets. Glyphs are drawn at
+//! integer multiples of their native size so that they stay crisp.
+
+use crate::prelude::*;
+use anyhow::{anyhow, Context};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt, fs, iter,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE_512: u8 = 0x01;
+const PSF1_MODE_UNICODE: u8 = 0x06;
+const PSF1_SEPARATOR: u16 = 0xFFFF;
+const PSF1_SEQUENCE: u16 = 0xFFFE;
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xB5, 0x4A, 0x86];
+const PSF2_FLAG_UNICODE: u32 = 0x01;
+const PSF2_SEPARATOR: u8 = 0xFF;
+const PSF2_SEQUENCE: u8 = 0xFE;
+
+/// Number of glyph columns used when building a page from PSF glyph data.
+const PSF_COLUMNS: u32 = 16;
+
+/// Represents a source of bitmap font glyph data.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(crate) enum BitmapSrc {
+    /// A path to an `AngelCode` `BMFont` `.fnt` descriptor in text format.
+    BmFont(PathBuf),
+    /// PSF1 or PSF2 console font byte data.
+    PsfBytes(&'static [u8]),
+    /// A path to a PSF1 or PSF2 console font file.
+    PsfPath(PathBuf),
+    /// A path to a `.png` glyph sheet with glyphs laid out left to right, top to bottom in a
+    /// fixed-size grid.
+    GlyphSheet {
+        path: PathBuf,
+        cell_size: (u32, u32),
+        chars: Cow<'static, str>,
+    },
+}
+
+impl fmt::Debug for BitmapSrc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BmFont(path) => write!(f, "BmFont({})", path.display()),
+            Self::PsfBytes(bytes) => write!(f, "PsfBytes([u8; {}])", bytes.len()),
+            Self::PsfPath(path) => write!(f, "PsfPath({})", path.display()),
+            Self::GlyphSheet {
+                path, cell_size, ..
+            } => write!(f, "GlyphSheet({}, {cell_size:?})", path.display()),
+        }
+    }
+}
+
+/// A single glyph located on a font page.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Glyph {
+    page: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    offset_x: i32,
+    offset_y: i32,
+    advance: i32,
+}
+
+/// A font page storing glyph coverage with one byte per pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Page {
+    width: u32,
+    height: u32,
+    coverage: Vec<u8>,
+}
+
+impl Page {
+    /// Convert an image into glyph coverage. Coverage is taken from the brightest color channel
+    /// scaled by alpha, so both white-on-transparent and white-on-black sheets are supported.
+    fn from_image(image: &Image) -> Self {
+        let coverage = image
+            .pixels()
+            .map(|color| {
+                let [red, green, blue, alpha] = color.channels();
+                let value = red.max(green).max(blue);
+                (u16::from(value) * u16::from(alpha) / 255) as u8
+            })
+            .collect();
+        Self {
+            width: image.width(),
+            height: image.height(),
+            coverage,
+        }
+    }
+
+    /// Returns the glyph coverage at the given `(x, y)` position, or `0` if out of bounds.
+    fn get(&self, x: u32, y: u32) -> u8 {
+        if x < self.width && y < self.height {
+            self.coverage[(y * self.width + x) as usize]
+        } else {
+            0
+        }
+    }
+}
+
+/// A loaded bitmap font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BitmapFont {
+    line_height: u32,
+    base: u32,
+    pages: Vec<Page>,
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), i32>,
+}
+
+impl BitmapFont {
+    /// Load a bitmap font from the given source.
+    ///
+    /// # Errors
+    ///
+    /// If the font or any of its page images fail to load or are invalid, then an error is
+    /// returned.
+    pub(crate) fn load(src: &BitmapSrc) -> PixResult<Self> {
+        match src {
+            BitmapSrc::BmFont(path) => Self::from_bmfont(path),
+            BitmapSrc::PsfBytes(bytes) => Self::from_psf(bytes),
+            BitmapSrc::PsfPath(path) => {
+                let bytes = fs::read(path)
+                    .with_context(|| format!("failed to read PSF font: {}", path.display()))?;
+                Self::from_psf(&bytes)
+            }
+            BitmapSrc::GlyphSheet {
+                path,
+                cell_size,
+                chars,
+            } => {
+                let image = Image::from_file(path)
+                    .with_context(|| format!("failed to load glyph sheet: {}", path.display()))?;
+                Self::from_glyph_sheet(&image, *cell_size, chars)
+            }
+        }
+    }
+
+    /// Load a `BMFont` text descriptor, loading pages relative to the descriptor path.
+    fn from_bmfont(path: &Path) -> PixResult<Self> {
+        let descriptor =
+            fs::read(path).with_context(|| format!("failed to read BMFont: {}", path.display()))?;
+        if descriptor.starts_with(b"BMF") {
+            return Err(anyhow!(
+                "binary BMFont descriptors are not supported: {}",
+                path.display()
+            ));
+        }
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        Self::parse_bmfont(&String::from_utf8_lossy(&descriptor), |file| {
+            Image::from_file(dir.join(file))
+                .with_context(|| format!("failed to load BMFont page: {file}"))
+        })
+    }
+
+    /// Parse a `BMFont` text descriptor, loading pages using `load_page`.
+    fn parse_bmfont<F>(descriptor: &str, mut load_page: F) -> PixResult<Self>
+    where
+        F: FnMut(&str) -> PixResult<Image>,
+    {
+        let mut common = None;
+        let mut pages = Vec::new();
+        let mut glyphs = HashMap::new();
+        let mut kerning = HashMap::new();
+        for line in descriptor.lines() {
+            let (tag, attrs) = parse_tag(line);
+            match tag {
+                "common" => common = Some((attr(&attrs, "lineHeight")?, attr(&attrs, "base")?)),
+                "page" => {
+                    let id: usize = attr(&attrs, "id")?;
+                    let file = attrs
+                        .get("file")
+                        .ok_or_else(|| anyhow!("BMFont page {id} is missing a file"))?;
+                    if pages.len() <= id {
+                        pages.resize(id + 1, None);
+                    }
+                    pages[id] = Some(Page::from_image(&load_page(file)?));
+                }
+                "char" => {
+                    let id: i64 = attr(&attrs, "id")?;
+                    let glyph = Glyph {
+                        page: attr(&attrs, "page")?,
+                        x: attr(&attrs, "x")?,
+                        y: attr(&attrs, "y")?,
+                        width: attr(&attrs, "width")?,
+                        height: attr(&attrs, "height")?,
+                        offset_x: attr(&attrs, "xoffset")?,
+                        offset_y: attr(&attrs, "yoffset")?,
+                        advance: attr(&attrs, "xadvance")?,
+                    };
+                    // Some exporters emit an `id=-1` glyph for invalid characters
+                    if let Some(c) = u32::try_from(id).ok().and_then(char::from_u32) {
+                        glyphs.insert(c, glyph);
+                    }
+                }
+                "kerning" => {
+                    let first: u32 = attr(&attrs, "first")?;
+                    let second: u32 = attr(&attrs, "second")?;
+                    let amount: i32 = attr(&attrs, "amount")?;
+                    if let (Some(first), Some(second)) =
+                        (char::from_u32(first), char::from_u32(second))
+                    {
+                        kerning.insert((first, second), amount);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let (line_height, base) =
+            common.ok_or_else(|| anyhow!("BMFont descriptor is missing `common` line"))?;
+        let pages = pages
+            .into_iter()
+            .enumerate()
+            .map(|(id, page)| page.ok_or_else(|| anyhow!("BMFont page {id} is missing")))
+            .collect::<PixResult<Vec<_>>>()?;
+        if let Some((c, glyph)) = glyphs.iter().find(|(_, glyph)| glyph.page >= pages.len()) {
+            return Err(anyhow!(
+                "BMFont glyph {c:?} references missing page {}",
+                glyph.page
+            ));
+        }
+        Ok(Self {
+            line_height,
+            base,
+            pages,
+            glyphs,
+            kerning,
+        })
+    }
+
+    /// Parse PSF1 or PSF2 console font data.
+    fn from_psf(bytes: &[u8]) -> PixResult<Self> {
+        let psf1 = bytes.starts_with(&PSF1_MAGIC);
+        let (width, height, glyph_count, glyph_size, header_size, unicode) = if psf1 {
+            let mode = read_u8(bytes, 2)?;
+            let charsize = u32::from(read_u8(bytes, 3)?);
+            let glyph_count = if mode & PSF1_MODE_512 == 0 { 256 } else { 512 };
+            (
+                8,
+                charsize,
+                glyph_count,
+                charsize,
+                4,
+                mode & PSF1_MODE_UNICODE != 0,
+            )
+        } else if bytes.starts_with(&PSF2_MAGIC) {
+            let header_size = read_u32(bytes, 8)?;
+            let flags = read_u32(bytes, 12)?;
+            let glyph_count = read_u32(bytes, 16)?;
+            let glyph_size = read_u32(bytes, 20)?;
+            let height = read_u32(bytes, 24)?;
+            let width = read_u32(bytes, 28)?;
+            let unicode = flags & PSF2_FLAG_UNICODE != 0;
+            (width, height, glyph_count, glyph_size, header_size, unicode)
+        } else {
+            return Err(anyhow!("invalid PSF font header"));
+        };
+
+        let row_size = (width as usize + 7) / 8;
+        if width == 0 || height == 0 || (glyph_size as usize) < row_size * height as usize {
+            return Err(anyhow!(
+                "invalid PSF glyph size {glyph_size} for {width}x{height} glyphs"
+            ));
+        }
+        let data_start = header_size as usize;
+        let data_end = data_start + glyph_count as usize * glyph_size as usize;
+        let data = bytes
+            .get(data_start..data_end)
+            .ok_or_else(|| anyhow!("PSF font is missing glyph data"))?;
+
+        let mut chars = HashMap::new();
+        let table = &bytes[data_end..];
+        if !unicode {
+            for index in 0..glyph_count {
+                if let Some(c) = char::from_u32(index) {
+                    chars.insert(c, index);
+                }
+            }
+        } else if psf1 {
+            let mut index = 0;
+            let mut sequence = false;
+            for entry in table.chunks_exact(2) {
+                match u16::from_le_bytes([entry[0], entry[1]]) {
+                    PSF1_SEPARATOR => {
+                        index += 1;
+                        sequence = false;
+                    }
+                    PSF1_SEQUENCE => sequence = true,
+                    value if !sequence => {
+                        if let Some(c) = char::from_u32(value.into()) {
+                            chars.entry(c).or_insert(index);
+                        }
+                    }
+                    _ => (),
+                }
+                if index >= glyph_count {
+                    break;
+                }
+            }
+        } else {
+            let entries = table.split(|&b| b == PSF2_SEPARATOR);
+            for (index, entry) in (0..glyph_count).zip(entries) {
+                // Multi-character sequences follow the single characters and are not supported
+                let singles = entry.split(|&b| b == PSF2_SEQUENCE).next().unwrap_or(&[]);
+                for c in String::from_utf8_lossy(singles).chars() {
+                    chars.entry(c).or_insert(index);
+                }
+            }
+        }
+
+        let rows = (glyph_count + PSF_COLUMNS - 1) / PSF_COLUMNS;
+        let page_width = PSF_COLUMNS * width;
+        let page_height = rows * height;
+        let mut coverage = vec![0x00; (page_width * page_height) as usize];
+        for (index, glyph) in (0..glyph_count).zip(data.chunks_exact(glyph_size as usize)) {
+            let left = (index % PSF_COLUMNS) * width;
+            let top = (index / PSF_COLUMNS) * height;
+            for y in 0..height {
+                let row = &glyph[y as usize * row_size..(y as usize + 1) * row_size];
+                for x in 0..width {
+                    if row[x as usize / 8] & (0x80 >> (x % 8)) != 0 {
+                        coverage[((top + y) * page_width + left + x) as usize] = 0xFF;
+                    }
+                }
+            }
+        }
+
+        let glyphs = chars
+            .into_iter()
+            .map(|(c, index)| {
+                let glyph = Glyph {
+                    page: 0,
+                    x: (index % PSF_COLUMNS) * width,
+                    y: (index / PSF_COLUMNS) * height,
+                    width,
+                    height,
+                    offset_x: 0,
+                    offset_y: 0,
+                    advance: width as i32,
+                };
+                (c, glyph)
+            })
+            .collect();
+        Ok(Self {
+            line_height: height,
+            base: height,
+            pages: vec![Page {
+                width: page_width,
+                height: page_height,
+                coverage,
+            }],
+            glyphs,
+            kerning: HashMap::new(),
+        })
+    }
+
+    /// Slice a glyph sheet into fixed-size cells, assigning `chars` left to right, top to bottom.
+    fn from_glyph_sheet(
+        image: &Image,
+        (cell_width, cell_height): (u32, u32),
+        chars: &str,
+    ) -> PixResult<Self> {
+        if cell_width == 0
+            || cell_height == 0
+            || cell_width > image.width()
+            || cell_height > image.height()
+        {
+            return Err(anyhow!(
+                "invalid glyph sheet cell size {cell_width}x{cell_height} for {}x{} image",
+                image.width(),
+                image.height()
+            ));
+        }
+        let columns = image.width() / cell_width;
+        let rows = image.height() / cell_height;
+        let glyphs = (0..columns * rows)
+            .zip(chars.chars())
+            .map(|(index, c)| {
+                let glyph = Glyph {
+                    page: 0,
+                    x: (index % columns) * cell_width,
+                    y: (index / columns) * cell_height,
+                    width: cell_width,
+                    height: cell_height,
+                    offset_x: 0,
+                    offset_y: 0,
+                    advance: cell_width as i32,
+                };
+                (c, glyph)
+            })
+            .collect();
+        Ok(Self {
+            line_height: cell_height,
+            base: cell_height,
+            pages: vec![Page::from_image(image)],
+            glyphs,
+            kerning: HashMap::new(),
+        })
+    }
+
+    /// Returns the integer scale used to draw text at the given font `size`. This is the largest
+    /// multiple of the native line height that fits within `size`, with a minimum of `1`.
+    #[must_use]
+    pub(crate) fn scale(&self, size: u32) -> u32 {
+        (size / self.line_height.max(1)).max(1)
+    }
+
+    /// Returns the scaled height of a line at the given font `size`.
+    #[must_use]
+    pub(crate) fn height(&self, size: u32) -> u32 {
+        self.line_height * self.scale(size)
+    }
+
+    /// Returns whether the font has a glyph for a character.
+    #[must_use]
+    #[cfg_attr(feature = "terminal", allow(dead_code))]
+    pub(crate) fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    /// Returns the scaled offset of the glyph image for a character from its pen position.
+    #[cfg_attr(feature = "terminal", allow(dead_code))]
+    pub(crate) fn glyph_offset(&self, c: char, size: u32) -> Point<i32> {
+        let scale = self.scale(size) as i32;
+        self.glyph(c).map_or_else(Point::default, |glyph| {
+            point![glyph.offset_x * scale, glyph.offset_y * scale]
+        })
+    }
+
+    /// Returns the scaled distance from the top of a line to its baseline at the given font `size`.
+    #[must_use]
+    pub(crate) fn ascent(&self, size: u32) -> u32 {
+        self.base * self.scale(size)
+    }
+
+    /// Returns the rendered dimensions of the given text at the given font `size` as `(width,
+    /// height)`.
+    #[must_use]
+    pub(crate) fn size_of(&self, text: &str, wrap_width: Option<u32>, size: u32) -> (u32, u32) {
+        let scale = self.scale(size);
+        let lines = self.lines(text, wrap_width, scale);
+        let width = lines
+            .iter()
+            .map(|line| self.layout_line(line).1)
+            .max()
+            .unwrap_or(0);
+        (width * scale, lines.len() as u32 * self.line_height * scale)
+    }
+
+    /// Returns the scaled position of each glyph in the given text at the given font `size`,
+    /// relative to the top-left of the rendered text. Each line is aligned within the width of the
+    /// widest line.
+    #[must_use]
+    #[cfg_attr(not(feature = "terminal"), allow(dead_code))]
+    pub(crate) fn layout(
+        &self,
+        text: &str,
+        wrap_width: Option<u32>,
+        size: u32,
+        align: TextAlign,
+    ) -> Vec<(char, Point<i32>)> {
+        let scale = self.scale(size) as i32;
+        let lines: Vec<_> = self
+            .lines(text, wrap_width, scale as u32)
+            .iter()
+            .map(|line| self.layout_line(line))
+            .collect();
+        let width = lines.iter().map(|&(_, width)| width).max().unwrap_or(0);
+        let mut glyphs = Vec::new();
+        for (row, (line, line_width)) in lines.into_iter().enumerate() {
+            let top = row as i32 * self.line_height as i32;
+            let left = align.offset(width - line_width);
+            for (pen, c, glyph) in line {
+                let x = (left + pen + glyph.offset_x) * scale;
+                let y = (top + glyph.offset_y) * scale;
+                glyphs.push((c, point![x, y]));
+            }
+        }
+        glyphs
+    }
+
+    /// Returns a white RGBA [Image] of the glyph for a character at the given font `size`, using
+    /// glyph coverage as alpha.
+    #[must_use]
+    pub(crate) fn glyph_image(&self, c: char, size: u32) -> Option<Image> {
+        let scale = self.scale(size);
+        let glyph = self.glyph(c)?;
+        let page = &self.pages[glyph.page];
+        let (width, height) = (glyph.width * scale, glyph.height * scale);
+        let mut image = Image::rgba(width, height);
+        for (idx, pixel) in image.as_mut_bytes().chunks_exact_mut(4).enumerate() {
+            let (x, y) = (idx as u32 % width, idx as u32 / width);
+            let coverage = page.get(glyph.x + x / scale, glyph.y + y / scale);
+            pixel.copy_from_slice(&[0xFF, 0xFF, 0xFF, coverage]);
+        }
+        Some(image)
+    }
+
+    /// Render the given text at the given font `size` into an RGBA [Image] using `color`.
+    #[cfg_attr(not(feature = "terminal"), allow(dead_code))]
+    pub(crate) fn render(
+        &self,
+        text: &str,
+        wrap_width: Option<u32>,
+        size: u32,
+        align: TextAlign,
+        color: Color,
+    ) -> Image {
+        let (width, height) = self.size_of(text, wrap_width, size);
+        let [red, green, blue, alpha] = color.channels();
+        let mut image = Image::rgba(width, height);
+        let bytes = image.as_mut_bytes();
+        for (c, pos) in self.layout(text, wrap_width, size, align) {
+            let glyph = match self.glyph_image(c, size) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            for (idx, pixel) in glyph.as_bytes().chunks_exact(4).enumerate() {
+                let x = pos.x() + (idx as u32 % glyph.width()) as i32;
+                let y = pos.y() + (idx as u32 / glyph.width()) as i32;
+                if pixel[3] == 0 || x < 0 || y < 0 || x as u32 >= width || y as u32 >= height {
+                    continue;
+                }
+                let alpha = (u16::from(pixel[3]) * u16::from(alpha) / 255) as u8;
+                let idx = 4 * (y as u32 * width + x as u32) as usize;
+                if alpha > bytes[idx + 3] {
+                    bytes[idx..idx + 4].copy_from_slice(&[red, green, blue, alpha]);
+                }
+            }
+        }
+        image
+    }
+
+    /// Returns the glyph for a character, falling back to `?` for missing glyphs.
+    fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    /// Returns the unscaled pen position of each glyph in a line along with the line width.
+    fn layout_line(&self, line: &str) -> (Vec<(i32, char, &Glyph)>, u32) {
+        let mut glyphs = Vec::new();
+        let mut pen = 0;
+        let mut width = 0;
+        let mut prev = None;
+        for c in line.chars() {
+            if let Some(glyph) = self.glyph(c) {
+                if let Some(prev) = prev {
+                    pen += self.kerning.get(&(prev, c)).copied().unwrap_or(0);
+                }
+                glyphs.push((pen, c, glyph));
+                width = width.max(pen + glyph.offset_x + glyph.width as i32);
+                pen += glyph.advance;
+                width = width.max(pen);
+            }
+            prev = Some(c);
+        }
+        (glyphs, width.max(0) as u32)
+    }
+
+    /// Split text into lines on newlines, wrapping on spaces when `wrap_width` is exceeded.
+    fn lines<'a>(&self, text: &'a str, wrap_width: Option<u32>, scale: u32) -> Vec<&'a str> {
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let paragraph = paragraph.strip_suffix('\r').unwrap_or(paragraph);
+            let wrap_width = match wrap_width {
+                Some(wrap_width) => wrap_width / scale,
+                None => {
+                    lines.push(paragraph);
+                    continue;
+                }
+            };
+            let mut start = 0;
+            let mut end = None;
+            let breaks = paragraph.match_indices(' ').map(|(idx, _)| idx);
+            for idx in breaks.chain(iter::once(paragraph.len())) {
+                if let Some(end) = end.filter(|&end| end > start) {
+                    if self.layout_line(&paragraph[start..idx]).1 > wrap_width {
+                        lines.push(&paragraph[start..end]);
+                        start = end + 1;
+                    }
+                }
+                end = Some(idx);
+            }
+            lines.push(&paragraph[start..]);
+        }
+        lines
+    }
+}
+
+/// Split a `BMFont` descriptor line into its tag and `key=value` attributes.
+fn parse_tag(line: &str) -> (&str, HashMap<&str, &str>) {
+    let line = line.trim();
+    let (tag, mut rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let mut attrs = HashMap::new();
+    while let Some((key, value)) = rest.split_once('=') {
+        let (value, remaining) = if let Some(quoted) = value.strip_prefix('"') {
+            quoted.split_once('"').unwrap_or((quoted, ""))
+        } else {
+            value.split_once(char::is_whitespace).unwrap_or((value, ""))
+        };
+        attrs.insert(key.trim(), value);
+        rest = remaining;
+    }
+    (tag, attrs)
+}
+
+/// Parse a required `BMFont` attribute.
+fn attr<T: FromStr>(attrs: &HashMap<&str, &str>, name: &str) -> PixResult<T> {
+    attrs
+        .get(name)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| anyhow!("missing or invalid BMFont attribute `{name}`"))
+}
+
+fn read_u8(bytes: &[u8], offset: usize) -> PixResult<u8> {
+    bytes
+        .get(offset)
+        .copied()
+        .ok_or_else(|| anyhow!("invalid PSF font header"))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> PixResult<u32> {
+    bytes
+        .get(offset..offset + 4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or_else(|| anyhow!("invalid PSF font header"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bmfont() -> PixResult<()> {
+        let descriptor = r#"info face="Test Font" size=8 bold=0
+common lineHeight=8 base=7 scaleW=16 scaleH=8 pages=1
+page id=0 file="test font.png"
+chars count=2
+char id=65 x=0 y=0 width=4 height=8 xoffset=0 yoffset=0 xadvance=5 page=0 chnl=15
+char id=66 x=4 y=0 width=4 height=8 xoffset=1 yoffset=0 xadvance=6 page=0 chnl=15
+char id=-1 x=0 y=0 width=0 height=0 xoffset=0 yoffset=0 xadvance=0 page=0 chnl=15
+kerning first=65 second=66 amount=-1"#;
+        let mut files = Vec::new();
+        let font = BitmapFont::parse_bmfont(descriptor, |file| {
+            files.push(file.to_owned());
+            Ok(Image::rgba(16, 8))
+        })?;
+        assert_eq!(files, ["test font.png"]);
+        assert_eq!(font.glyphs.len(), 2);
+        assert_eq!(font.kerning.get(&('A', 'B')), Some(&-1));
+        assert_eq!(font.size_of("AB", None, 8), (10, 8));
+        assert_eq!(font.size_of("AB", None, 20), (20, 16));
+        assert_eq!(font.ascent(20), 14);
+        assert!(
+            BitmapFont::parse_bmfont("page id=0 file=\"a.png\"", |_| Ok(Image::rgba(1, 1)))
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_psf() -> PixResult<()> {
+        let mut bytes = vec![PSF1_MAGIC[0], PSF1_MAGIC[1], 0x00, 2];
+        bytes.resize(4 + 256 * 2, 0x00);
+        bytes[4 + 2 * 'A' as usize] = 0b1000_0001;
+        let font = BitmapFont::from_psf(&bytes)?;
+        assert_eq!(font.line_height, 2);
+        assert_eq!(font.size_of("AA\nA", None, 2), (16, 4));
+
+        let image = font.render("A", None, 4, TextAlign::Left, Color::WHITE);
+        assert_eq!(image.dimensions(), (16, 4));
+        assert_eq!(image.get_pixel(0, 0), Color::WHITE);
+        assert_eq!(image.get_pixel(1, 1), Color::WHITE);
+        assert_eq!(image.get_pixel(2, 0), Color::TRANSPARENT);
+        assert_eq!(image.get_pixel(14, 0), Color::WHITE);
+        assert_eq!(image.get_pixel(0, 2), Color::TRANSPARENT);
+
+        assert!(BitmapFont::from_psf(&bytes[..100]).is_err());
+        assert!(BitmapFont::from_psf(b"not a font").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn glyph_sheet_wrapping() -> PixResult<()> {
+        let image = Image::rgba(8, 4);
+        let font = BitmapFont::from_glyph_sheet(&image, (4, 4), "ab ")?;
+        assert_eq!(font.glyphs.len(), 2);
+        assert_eq!(font.lines("ab ab", Some(8), 1), ["ab", "ab"]);
+        assert_eq!(font.lines("ab ab", Some(20), 1), ["ab ab"]);
+        assert_eq!(font.lines("ab ab", Some(16), 2), ["ab", "ab"]);
+        assert_eq!(font.size_of("ab ab", Some(8), 4), (8, 8));
+        assert_eq!(
+            font.layout("ab\nb", None, 8, TextAlign::Left),
+            [
+                ('a', point![0, 0]),
+                ('b', point![8, 0]),
+                ('b', point![0, 8])
+            ]
+        );
+        assert_eq!(
+            font.layout("ab\nb", None, 8, TextAlign::Right),
+            [
+                ('a', point![0, 0]),
+                ('b', point![8, 0]),
+                ('b', point![8, 8])
+            ]
+        );
+        assert_eq!(
+            font.layout("ab\nb", None, 4, TextAlign::Center)[2].1,
+            point![2, 4]
+        );
+        assert!(BitmapFont::from_glyph_sheet(&image, (16, 4), "a").is_err());
+        Ok(())
+    }
+}
@@ -28,9 +28,11 @@
 use crate::{gui::Direction, ops::clamp_size, prelude::*};
 
 pub mod field;
+pub mod rich_text;
 pub mod select;
 pub mod slider;
 pub mod text;
+pub mod text_path;
 pub mod tooltip;
 
 impl PixState {
@@ -98,12 +100,12 @@ impl PixState {
 
         // Button text
         s.rect_mode(RectMode::Center);
-        s.clip(button)?;
+        s.push_clip(button)?;
         s.set_cursor_pos(button.center());
         s.stroke(None);
         s.fill(fg);
         s.text(label)?;
-        s.clip(None)?;
+        s.pop_clip()?;
 
         s.ui.pop_cursor();
         s.pop();
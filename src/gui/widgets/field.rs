@@ -158,7 +158,7 @@ impl PixState {
 
         s.wrap(None);
         s.set_cursor_pos([x, y]);
-        s.clip(clip)?;
+        s.push_clip(clip)?;
         s.stroke(None);
         s.fill(fg);
         if value.is_empty() {
@@ -179,7 +179,7 @@ impl PixState {
             s.text(&value)?;
         }
 
-        s.clip(None)?;
+        s.pop_clip()?;
         s.ui.pop_cursor();
         s.pop();
 
@@ -321,7 +321,7 @@ impl PixState {
         text_pos.offset(ipad - scroll);
 
         s.set_cursor_pos(text_pos);
-        s.clip(clip)?;
+        s.push_clip(clip)?;
         s.stroke(None);
         s.fill(fg);
         let (_, text_height) = if value.is_empty() {
@@ -377,14 +377,15 @@ impl PixState {
             s.ui.set_scroll(id, scroll);
         }
 
-        s.clip(None)?;
+        s.pop_clip()?;
         s.ui.pop_cursor();
         s.pop();
 
         s.ui.handle_focus(id);
         // Scrollbars
         let rect = s.scroll(id, input, 0, text_height)?;
-        s.advance_cursor([rect.width().max(label_width), rect.bottom() - pos.y()]);
+        let label_rect = rect![pos, label_width, label_height];
+        s.advance_cursor(rect.union(label_rect).size());
 
         Ok(changed)
     }
@@ -0,0 +1,726 @@
+//! Rich text rendering methods.
+//!
+//! Rich text is a paragraph made up of [`TextSpan`]s which can each change the [Color],
+//! [`FontStyle`], [Font] family and size of their text. Spans are wrapped together as a single
+//! paragraph using the current [`PixState::wrap`] width and aligned using the current
+//! [`PixState::text_align`].
+//!
+//! [`RichText`] can be built from a list of spans or parsed from a small markup language:
+//!
+//! - `[color=#f00]red[/color]`: Changes the text [Color] to a hexadecimal color.
+//! - `[size=24]large[/size]`: Changes the font size.
+//! - `[b]bold[/b]` or `**bold**`: Adds [`FontStyle::BOLD`].
+//! - `[i]italic[/i]`: Adds [`FontStyle::ITALIC`].
+//! - `[u]underline[/u]`: Adds [`FontStyle::UNDERLINE`].
+//! - `[s]strikethrough[/s]`: Adds [`FontStyle::STRIKETHROUGH`].
+//!
+//! Tags can be nested and must be closed in order. A `\` escapes the next character, e.g. `\[` or
+//! `\*`.
+//!
+//! Provided types:
+//!
+//! - [`TextSpan`]: A run of text with optional color, style, font and size changes.
+//! - [`RichText`]: A paragraph of [`TextSpan`]s.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::rich_text`]
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App;
+//! # impl PixEngine for App {
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     s.rich_text(RichText::parse("Press [color=#f00]**Space**[/color] to jump")?)?;
+//!     s.rich_text([
+//!         TextSpan::new("Found "),
+//!         TextSpan::new("Excalibur").color(Color::GOLD).style(FontStyle::ITALIC),
+//!         TextSpan::new("!"),
+//!     ])?;
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::{ops::clamp_size, prelude::*, renderer::Rendering};
+use anyhow::anyhow;
+use std::{mem, ptr, str::FromStr};
+
+/// A run of text drawn with an optional [Color], [`FontStyle`], [Font] family and size. Any values
+/// not set use the current settings.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct TextSpan {
+    text: String,
+    color: Option<Color>,
+    style: Option<FontStyle>,
+    font: Option<Font>,
+    size: Option<u32>,
+}
+
+impl TextSpan {
+    /// Constructs a `TextSpan` drawn with the current settings.
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            color: None,
+            style: None,
+            font: None,
+            size: None,
+        }
+    }
+
+    /// Set the [Color] used to draw this span.
+    pub fn color<C: Into<Color>>(mut self, color: C) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Set the [`FontStyle`] used to draw this span.
+    pub fn style(mut self, style: FontStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Set the [Font] family used to draw this span.
+    pub fn font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Set the font size used to draw this span.
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Returns the text of this span.
+    #[inline]
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl From<&str> for TextSpan {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for TextSpan {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/// A paragraph of [`TextSpan`]s drawn with [`PixState::rich_text`].
+#[derive(Default, Debug, Clone, PartialEq)]
+#[must_use]
+pub struct RichText {
+    spans: Vec<TextSpan>,
+}
+
+impl RichText {
+    /// Constructs an empty `RichText`.
+    pub const fn new() -> Self {
+        Self { spans: Vec::new() }
+    }
+
+    /// Parse `RichText` from markup. See the [module-level documentation](self) for the supported
+    /// tags.
+    ///
+    /// # Errors
+    ///
+    /// If the markup contains an unknown or unclosed tag, a closing tag that doesn't match the
+    /// last opened tag or an invalid color or size, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # fn main() -> PixResult<()> {
+    /// let text = RichText::parse("[color=#f00]red[/color] **bold**")?;
+    /// assert_eq!(text.spans().len(), 3);
+    /// assert_eq!(text.spans()[2].text(), "bold");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse(markup: &str) -> PixResult<Self> {
+        let mut parser = MarkupParser::default();
+        let mut chars = markup.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some(c) = chars.next() {
+                        parser.text.push(c);
+                    }
+                }
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if parser.is_open(Tag::Strong) {
+                        parser.close(Tag::Strong)?;
+                    } else {
+                        parser.open(Tag::Strong);
+                        parser.state.style |= FontStyle::BOLD;
+                    }
+                }
+                '[' => {
+                    let mut tag = String::new();
+                    for c in chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                        tag.push(c);
+                    }
+                    parser.tag(&tag)?;
+                }
+                _ => parser.text.push(c),
+            }
+        }
+        parser.finish()
+    }
+
+    /// Append a [`TextSpan`].
+    pub fn push<S: Into<TextSpan>>(&mut self, span: S) {
+        self.spans.push(span.into());
+    }
+
+    /// Returns the list of [`TextSpan`]s.
+    #[inline]
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+}
+
+impl FromStr for RichText {
+    type Err = PixError;
+
+    fn from_str(markup: &str) -> Result<Self, Self::Err> {
+        Self::parse(markup).map_err(PixError::Other)
+    }
+}
+
+impl From<Vec<TextSpan>> for RichText {
+    fn from(spans: Vec<TextSpan>) -> Self {
+        Self { spans }
+    }
+}
+
+impl<const N: usize> From<[TextSpan; N]> for RichText {
+    fn from(spans: [TextSpan; N]) -> Self {
+        Self {
+            spans: spans.into(),
+        }
+    }
+}
+
+impl<S: Into<TextSpan>> FromIterator<S> for RichText {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self {
+            spans: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A markup tag which changes how text is drawn until it's closed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Tag {
+    Color,
+    Size,
+    Bold,
+    Italic,
+    Underline,
+    Strikethrough,
+    Strong,
+}
+
+impl Tag {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Color => "color",
+            Self::Size => "size",
+            Self::Bold => "b",
+            Self::Italic => "i",
+            Self::Underline => "u",
+            Self::Strikethrough => "s",
+            Self::Strong => "**",
+        }
+    }
+}
+
+/// Text settings changed by markup tags.
+#[derive(Default, Debug, Copy, Clone)]
+struct MarkupState {
+    color: Option<Color>,
+    size: Option<u32>,
+    style: FontStyle,
+}
+
+/// Builds a list of [`TextSpan`]s from markup, starting a new span whenever the text settings
+/// change.
+#[derive(Default, Debug)]
+struct MarkupParser {
+    spans: Vec<TextSpan>,
+    text: String,
+    state: MarkupState,
+    stack: Vec<(Tag, MarkupState)>,
+}
+
+impl MarkupParser {
+    /// Handle the contents of a `[tag]` or `[/tag]`.
+    fn tag(&mut self, tag: &str) -> PixResult<()> {
+        let (name, value) = match tag.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (tag.trim(), None),
+        };
+        if let Some(name) = name.strip_prefix('/') {
+            let tag = match name {
+                "color" => Tag::Color,
+                "size" => Tag::Size,
+                "b" => Tag::Bold,
+                "i" => Tag::Italic,
+                "u" => Tag::Underline,
+                "s" => Tag::Strikethrough,
+                _ => return Err(anyhow!("unknown closing rich text tag `[{tag}]`")),
+            };
+            return self.close(tag);
+        }
+        match (name, value) {
+            ("color", Some(value)) => {
+                let color = Color::from_str(value)
+                    .map_err(|_| anyhow!("invalid rich text color `{value}`"))?;
+                self.open(Tag::Color);
+                self.state.color = Some(color);
+            }
+            ("size", Some(value)) => {
+                let size = value
+                    .parse()
+                    .map_err(|_| anyhow!("invalid rich text size `{value}`"))?;
+                self.open(Tag::Size);
+                self.state.size = Some(size);
+            }
+            ("b", None) => {
+                self.open(Tag::Bold);
+                self.state.style |= FontStyle::BOLD;
+            }
+            ("i", None) => {
+                self.open(Tag::Italic);
+                self.state.style |= FontStyle::ITALIC;
+            }
+            ("u", None) => {
+                self.open(Tag::Underline);
+                self.state.style |= FontStyle::UNDERLINE;
+            }
+            ("s", None) => {
+                self.open(Tag::Strikethrough);
+                self.state.style |= FontStyle::STRIKETHROUGH;
+            }
+            _ => return Err(anyhow!("unknown rich text tag `[{tag}]`")),
+        }
+        Ok(())
+    }
+
+    /// Returns whether `tag` has been opened and not yet closed.
+    fn is_open(&self, tag: Tag) -> bool {
+        self.stack.iter().any(|&(open, _)| open == tag)
+    }
+
+    /// Open a tag, saving the current state to be restored when it's closed.
+    fn open(&mut self, tag: Tag) {
+        self.flush();
+        self.stack.push((tag, self.state));
+    }
+
+    /// Close the most recently opened tag, restoring the previous state.
+    fn close(&mut self, tag: Tag) -> PixResult<()> {
+        match self.stack.pop() {
+            Some((open, state)) if open == tag => {
+                self.flush();
+                self.state = state;
+                Ok(())
+            }
+            Some((open, _)) => Err(anyhow!(
+                "expected rich text tag `{}` to be closed before `{}`",
+                open.name(),
+                tag.name()
+            )),
+            None => Err(anyhow!("unopened rich text tag `{}`", tag.name())),
+        }
+    }
+
+    /// Push any pending text as a span using the current state.
+    fn flush(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+        let mut span = TextSpan::new(mem::take(&mut self.text));
+        span.color = self.state.color;
+        span.size = self.state.size;
+        if !self.state.style.is_empty() {
+            span.style = Some(self.state.style);
+        }
+        self.spans.push(span);
+    }
+
+    /// Finish parsing, returning the parsed [`RichText`].
+    fn finish(mut self) -> PixResult<RichText> {
+        if let Some(&(tag, _)) = self.stack.last() {
+            return Err(anyhow!("unclosed rich text tag `{}`", tag.name()));
+        }
+        self.flush();
+        Ok(RichText { spans: self.spans })
+    }
+}
+
+/// A run of text on a line drawn with a single [`TextSpan`].
+#[derive(Debug)]
+struct Run<'a> {
+    span: &'a TextSpan,
+    text: String,
+    x: i32,
+    width: u32,
+    ascent: u32,
+}
+
+/// A laid out line of [`Run`]s.
+#[derive(Default, Debug)]
+struct RichLine<'a> {
+    runs: Vec<Run<'a>>,
+    width: u32,
+    ascent: u32,
+    descent: u32,
+}
+
+impl RichLine<'_> {
+    /// Returns whether the line only contains whitespace.
+    fn is_blank(&self) -> bool {
+        self.runs.iter().all(|run| run.text.trim().is_empty())
+    }
+
+    /// Returns the end position of the last run on the line.
+    fn end(&self) -> u32 {
+        self.runs
+            .last()
+            .map_or(0, |run| run.x.max(0) as u32 + run.width)
+    }
+}
+
+/// The font settings spans fall back to.
+#[derive(Debug)]
+struct BaseFont {
+    family: Font,
+    size: u32,
+    style: FontStyle,
+}
+
+impl PixState {
+    /// Draw [`RichText`] to the current canvas, where each [`TextSpan`] can change the [Color],
+    /// [`FontStyle`], [Font] family and size of its text. Spans are wrapped as a single paragraph
+    /// using [`PixState::wrap`] and each line is aligned using [`PixState::text_align`]. Spans on
+    /// the same line share a baseline.
+    ///
+    /// Returns the rendered `(width, height)` of the text, including any newlines or text
+    /// wrapping.
+    ///
+    /// # Errors
+    ///
+    /// If the renderer fails to load a span font or draw to the current render target, then an
+    /// error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.wrap(200);
+    ///     s.rich_text(RichText::parse(
+    ///         "The [color=#f00]dragon[/color] breathes [size=20]**fire**[/size]!",
+    ///     )?)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn rich_text<R>(&mut self, text: R) -> PixResult<(u32, u32)>
+    where
+        R: Into<RichText>,
+    {
+        let text = text.into();
+        let s = &self.settings;
+        let wrap_width = s.wrap_width;
+        let (text_align, vertical_align) = (s.text_align, s.vertical_align);
+        let fill = s.fill.unwrap_or(Color::TRANSPARENT);
+        let shadow = s.text_shadow;
+        let rect_mode = s.rect_mode;
+        let base = BaseFont {
+            family: s.font_family.clone(),
+            size: s.font_size,
+            style: s.font_style,
+        };
+        let colors = self.theme.colors;
+        let outline = self.text_outline_style();
+
+        self.push();
+        // Runs are positioned here, so each is drawn aligned to its top-left
+        self.text_align(TextAlign::Left, VerticalAlign::Top);
+
+        // Settings are restored even if drawing fails partway through
+        let draw = |s: &mut Self| -> PixResult<(Point<i32>, u32, u32)> {
+            let lines = s.rich_text_lines(&text, &base, wrap_width)?;
+            let width = lines.iter().map(|line| line.width).max().unwrap_or(0);
+            let height: u32 = lines.iter().map(|line| line.ascent + line.descent).sum();
+
+            let mut pos = s.cursor_pos();
+            if rect_mode == RectMode::Center {
+                pos.offset([-(clamp_size(width) / 2), -(clamp_size(height) / 2)]);
+            }
+            let y_offset = match vertical_align {
+                VerticalAlign::Top => 0,
+                VerticalAlign::Middle => clamp_size(height) / 2,
+                VerticalAlign::Baseline => lines.first().map_or(0, |line| clamp_size(line.ascent)),
+                VerticalAlign::Bottom => clamp_size(height),
+            };
+            pos.offset([-text_align.offset(width), -y_offset]);
+
+            let mut runs = Vec::new();
+            let mut top = pos.y();
+            for line in &lines {
+                let left = pos.x() + text_align.offset(width - line.width);
+                for run in &line.runs {
+                    let position = point![
+                        left + run.x,
+                        top + clamp_size(line.ascent) - clamp_size(run.ascent)
+                    ];
+                    runs.push((position, run));
+                }
+                top += clamp_size(line.ascent + line.descent);
+            }
+
+            // Shadows are drawn behind every run
+            if let Some(shadow) = shadow {
+                let weight = outline.map_or(0, |(weight, _)| weight);
+                let copies = shadow.copies();
+                for &(position, run) in &runs {
+                    s.apply_span_font(run.span, &base)?;
+                    for &(offset, color) in &copies {
+                        let position = point![position.x() + offset.x(), position.y() + offset.y()];
+                        s.draw_run(position, &run.text, color, weight)?;
+                    }
+                }
+            }
+            for (mut position, run) in runs {
+                s.apply_span_font(run.span, &base)?;
+                if let Some((weight, stroke)) = outline {
+                    s.draw_run(position, &run.text, stroke, weight)?;
+                    position += i32::from(weight);
+                }
+                let color = run.span.color.unwrap_or(fill);
+                let color = if s.ui.disabled {
+                    color.blended(colors.background, 0.38)
+                } else {
+                    color
+                };
+                s.draw_run(position, &run.text, color, 0)?;
+            }
+            Ok((pos, width, height))
+        };
+        let result = draw(self);
+        self.pop();
+        let (pos, width, height) = result?;
+
+        // EXPL: Add some bottom/right padding
+        let rect = rect![pos, clamp_size(width), clamp_size(height)].offset_size([3, 3]);
+        self.advance_cursor(rect.size());
+        Ok((rect.width() as u32, rect.height() as u32))
+    }
+}
+
+impl PixState {
+    /// Set the font family, size and style used to draw a [`TextSpan`].
+    fn apply_span_font(&mut self, span: &TextSpan, base: &BaseFont) -> PixResult<()> {
+        let s = &mut self.settings;
+        s.font_family = span.font.clone().unwrap_or_else(|| base.family.clone());
+        s.font_size = span.size.unwrap_or(base.size);
+        s.font_style = span.style.unwrap_or(base.style);
+        self.renderer.font_family(&s.font_family)?;
+        self.renderer.font_size(s.font_size)?;
+        self.renderer.font_style(s.font_style);
+        Ok(())
+    }
+
+    /// Draw a single run of text at `position` with an `outline` width.
+    fn draw_run(
+        &mut self,
+        position: Point<i32>,
+        text: &str,
+        color: Color,
+        outline: u16,
+    ) -> PixResult<()> {
+        self.draw_text(position, text, None, None, None, None, color, outline, None)?;
+        Ok(())
+    }
+
+    /// Break spans into words and lay them out into lines, wrapping at `wrap_width` if provided.
+    /// Adjacent words from the same span are merged into a single [`Run`].
+    fn rich_text_lines<'a>(
+        &mut self,
+        text: &'a RichText,
+        base: &BaseFont,
+        wrap_width: Option<u32>,
+    ) -> PixResult<Vec<RichLine<'a>>> {
+        let mut lines = Vec::new();
+        let mut line = RichLine::default();
+        let mut wrapped = false;
+        for span in &text.spans {
+            self.apply_span_font(span, base)?;
+            let ascent = self.renderer.font_ascent()?;
+            let (_, height) = self.renderer.size_of("", None)?;
+            let descent = height.saturating_sub(ascent);
+            for word in split_words(&span.text) {
+                if word == "\n" {
+                    line.ascent = line.ascent.max(ascent);
+                    line.descent = line.descent.max(descent);
+                    lines.push(mem::take(&mut line));
+                    wrapped = false;
+                    continue;
+                }
+                let blank = word.trim().is_empty();
+                if blank && wrapped && line.runs.is_empty() {
+                    continue;
+                }
+
+                let run = line.runs.last().filter(|run| ptr::eq(run.span, span));
+                let (x, run_text) = match run {
+                    Some(run) => (run.x, format!("{}{word}", run.text)),
+                    None => (clamp_size(line.width), word.to_owned()),
+                };
+                let (run_width, _) = self.renderer.size_of(&run_text, None)?;
+                let exceeds =
+                    matches!(wrap_width, Some(wrap_width) if x as u32 + run_width > wrap_width);
+                if exceeds && !blank && !line.is_blank() {
+                    self.trim_line_end(&mut line, base)?;
+                    self.apply_span_font(span, base)?;
+                    lines.push(mem::take(&mut line));
+                    wrapped = true;
+                    let (width, _) = self.renderer.size_of(word, None)?;
+                    line.runs.push(Run {
+                        span,
+                        text: word.to_owned(),
+                        x: 0,
+                        width,
+                        ascent,
+                    });
+                } else if let Some(run) = line.runs.last_mut().filter(|run| ptr::eq(run.span, span))
+                {
+                    run.text = run_text;
+                    run.width = run_width;
+                } else {
+                    line.runs.push(Run {
+                        span,
+                        text: run_text,
+                        x,
+                        width: run_width,
+                        ascent,
+                    });
+                }
+                line.width = line.end();
+                line.ascent = line.ascent.max(ascent);
+                line.descent = line.descent.max(descent);
+            }
+        }
+        lines.push(line);
+        Ok(lines)
+    }
+
+    /// Remove trailing whitespace from a wrapped line, as it doesn't count towards the line width.
+    fn trim_line_end(&mut self, line: &mut RichLine<'_>, base: &BaseFont) -> PixResult<()> {
+        while matches!(line.runs.last(), Some(run) if run.text.trim().is_empty()) {
+            line.runs.pop();
+        }
+        if let Some(run) = line.runs.last_mut() {
+            let trimmed = run.text.trim_end().len();
+            if trimmed < run.text.len() {
+                run.text.truncate(trimmed);
+                self.apply_span_font(run.span, base)?;
+                run.width = self.renderer.size_of(&run.text, None)?.0;
+            }
+        }
+        line.width = line.end();
+        Ok(())
+    }
+}
+
+/// Split text into words, runs of whitespace and newlines.
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut prev_space = None;
+    for (idx, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if c == '\n' || matches!(prev_space, Some(prev) if prev != space) {
+            if start < idx {
+                words.push(&text[start..idx]);
+            }
+            start = idx;
+        }
+        if c == '\n' {
+            words.push("\n");
+            start = idx + 1;
+            prev_space = None;
+        } else {
+            prev_space = Some(space);
+        }
+    }
+    if start < text.len() {
+        words.push(&text[start..]);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_markup() -> PixResult<()> {
+        let text = RichText::parse("[color=#f00]red [b]bold[/b][/color] **strong** \\[x\\]")?;
+        let spans = text.spans();
+        assert_eq!(spans.len(), 5);
+        assert_eq!(spans[0], TextSpan::new("red ").color(Color::RED));
+        assert_eq!(
+            spans[1],
+            TextSpan::new("bold")
+                .color(Color::RED)
+                .style(FontStyle::BOLD)
+        );
+        assert_eq!(spans[2], TextSpan::new(" "));
+        assert_eq!(spans[3], TextSpan::new("strong").style(FontStyle::BOLD));
+        assert_eq!(spans[4], TextSpan::new(" [x]"));
+
+        let text = RichText::parse("[size=20][i]big[/i][/size]")?;
+        assert_eq!(
+            text.spans(),
+            [TextSpan::new("big").size(20).style(FontStyle::ITALIC)]
+        );
+
+        assert!(RichText::parse("[b]unclosed").is_err());
+        assert!(RichText::parse("[b][i]crossed[/b][/i]").is_err());
+        assert!(RichText::parse("[color=red]named[/color]").is_err());
+        assert!(RichText::parse("[unknown]tag").is_err());
+        assert!(RichText::parse("[/b]").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn split_text_words() {
+        assert_eq!(
+            split_words("a  bc\n\nd "),
+            ["a", "  ", "bc", "\n", "\n", "d", " "]
+        );
+        assert!(split_words("").is_empty());
+    }
+}
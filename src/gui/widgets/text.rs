@@ -26,7 +26,14 @@
 //! # }
 //! ```
 
-use crate::{gui::Direction, ops::clamp_size, prelude::*, renderer::Rendering};
+use crate::{
+    gui::Direction, ops::clamp_size, prelude::*, renderer::Rendering, state::settings::Settings,
+};
+use std::borrow::Cow;
+
+/// Character appended to text truncated by [`TextOverflow::Ellipsis`] or
+/// [`TextOverflow::WordEllipsis`].
+const ELLIPSIS: char = '…';
 
 impl PixState {
     /// Return the dimensions of given text for drawing to the current canvas.
@@ -110,14 +117,10 @@ impl PixState {
     where
         S: AsRef<str>,
     {
-        let s = self;
-        s.push();
-        s.renderer.font_family(&s.theme.fonts.heading)?;
-        s.renderer.font_size(s.theme.font_size + 6)?;
-        s.renderer.font_style(s.theme.styles.heading);
-        let size = s.text_transformed(text, None, None, None);
-        s.pop();
-        size
+        let font = self.theme.fonts.heading.clone();
+        let size = self.theme.font_size + 6;
+        let style = self.theme.styles.heading;
+        self.text_with_font(text.as_ref(), font, size, style)
     }
 
     /// Draw monospace text to the current canvas.
@@ -145,14 +148,10 @@ impl PixState {
     where
         S: AsRef<str>,
     {
-        let s = self;
-        s.push();
-        s.renderer.font_family(&s.theme.fonts.monospace)?;
-        s.renderer.font_size(s.theme.font_size + 2)?;
-        s.renderer.font_style(s.theme.styles.monospace);
-        let size = s.text_transformed(text, None, None, None);
-        s.pop();
-        size
+        let font = self.theme.fonts.monospace.clone();
+        let size = self.theme.font_size + 2;
+        let style = self.theme.styles.monospace;
+        self.text_with_font(text.as_ref(), font, size, style)
     }
 
     /// Draw transformed text to the current canvas, optionally rotated about a `center` by `angle`
@@ -198,19 +197,40 @@ impl PixState {
         let center = center.into();
         let flipped = flipped.into();
 
-        let s = &self.settings;
-        let fill = s.fill.unwrap_or(Color::TRANSPARENT);
-
-        let rect = {
-            let stroke_size = match (s.stroke, s.stroke_weight) {
-                (Some(stroke), weight) if weight > 0 => {
-                    Some(self.render_text(text, stroke, weight, angle, center, flipped)?)
+        let fill = self.settings.fill.unwrap_or(Color::TRANSPARENT);
+        let outline = self.text_outline_style();
+        let shadow = self.settings.text_shadow;
+
+        // Fitting text can disable wrapping and shrink the font size, so restore them afterwards
+        let (wrap_width, font_size) = (self.settings.wrap_width, self.settings.font_size);
+        let rect = self.fit_text(text).and_then(|text| {
+            if let Some(shadow) = shadow {
+                let weight = outline.map_or(0, |(weight, _)| weight);
+                let pos = self.cursor_pos();
+                self.ui.push_cursor();
+                let result = shadow.copies().into_iter().try_for_each(|(offset, color)| {
+                    self.set_cursor_pos([pos.x() + offset.x(), pos.y() + offset.y()]);
+                    self.render_text(&text, color, weight, angle, center, flipped)
+                        .map(|_| ())
+                });
+                self.ui.pop_cursor();
+                result?;
+            }
+            let stroke_size = match outline {
+                Some((weight, stroke)) => {
+                    Some(self.render_text(&text, stroke, weight, angle, center, flipped)?)
                 }
-                _ => None,
+                None => None,
             };
-            let text_size = self.render_text(text, fill, 0, angle, center, flipped)?;
-            stroke_size.unwrap_or(text_size)
-        };
+            let text_size = self.render_text(&text, fill, 0, angle, center, flipped)?;
+            Ok(stroke_size.unwrap_or(text_size))
+        });
+        self.settings.wrap_width = wrap_width;
+        if self.settings.font_size != font_size {
+            self.settings.font_size = font_size;
+            self.renderer.font_size(font_size)?;
+        }
+        let rect = rect?;
         // EXPL: Add some bottom/right padding
         let rect = rect.offset_size([3, 3]);
         self.advance_cursor(rect.size());
@@ -513,22 +533,26 @@ impl PixState {
         flipped: Option<Flipped>,
     ) -> PixResult<Rect<i32>> {
         let s = &self.settings;
-        let wrap_width = s.wrap_width;
+        let (wrap_width, clip_width) = match (s.text_overflow, s.wrap_width) {
+            (TextOverflow::Clip, Some(width)) => (None, Some(width)),
+            _ => (s.wrap_width, None),
+        };
+        let (text_align, vertical_align) = (s.text_align, s.vertical_align);
         let angle_mode = s.angle_mode;
         let colors = self.theme.colors;
         let ipad = self.theme.spacing.item_pad;
+        let outline_weight = self.text_outline_style().map_or(0, |(weight, _)| weight);
 
         let mut pos = self.cursor_pos();
         if s.rect_mode == RectMode::Center {
-            let (width, height) = self.size_of(text)?;
+            let (width, height) = self.renderer.size_of(text, wrap_width)?;
+            let width = clip_width.map_or(width, |clip_width| width.min(clip_width));
             pos.offset([-(clamp_size(width) / 2), -(clamp_size(height) / 2)]);
         };
-        if outline == 0 && s.stroke_weight > 0 {
-            pos += i32::from(s.stroke_weight);
+        if outline == 0 && outline_weight > 0 {
+            pos += i32::from(outline_weight);
         }
 
-        self.push();
-
         let color = if self.ui.disabled {
             color.blended(colors.background, 0.38)
         } else {
@@ -545,17 +569,259 @@ impl PixState {
         } else {
             wrap_width
         };
-        let rect = if matches!(angle, Some(angle) if angle != 0.0) {
-            let angle = if angle_mode == AngleMode::Radians {
-                angle.map(f64::to_degrees)
-            } else {
-                angle
-            };
+        if text_align != TextAlign::Left || vertical_align != VerticalAlign::Top {
             let (width, height) = self.renderer.size_of(text, wrap_width)?;
-            let rect = rect![0, 0, clamp_size(width), clamp_size(height)];
-            let rect = angle.map_or(rect, |angle| rect.rotated(angle.to_radians(), center));
+            let width = clip_width.map_or(width, |clip_width| width.min(clip_width));
+            let y_offset = match vertical_align {
+                VerticalAlign::Top => 0,
+                VerticalAlign::Middle => clamp_size(height) / 2,
+                VerticalAlign::Baseline => clamp_size(self.renderer.font_ascent()?),
+                VerticalAlign::Bottom => clamp_size(height),
+            };
+            pos.offset([-text_align.offset(width), -y_offset]);
+        }
+        let clip = match clip_width {
+            Some(clip_width) => {
+                let (_, height) = self.renderer.size_of(text, wrap_width)?;
+                Some(rect![pos, clamp_size(clip_width), clamp_size(height)])
+            }
+            None => None,
+        };
+        // Clips are in screen coordinates, unlike the text position
+        let camera = self.settings.camera;
+        let screen_clip =
+            clip.map(|clip| camera.map_or(clip, |camera| camera.transform_rect(clip)));
+        let draw = |s: &mut Self| -> PixResult<Rect<i32>> {
+            if matches!(angle, Some(angle) if angle != 0.0) {
+                let angle = if angle_mode == AngleMode::Radians {
+                    angle.map(f64::to_degrees)
+                } else {
+                    angle
+                };
+                let (width, height) = s.renderer.size_of(text, wrap_width)?;
+                let rect = rect![0, 0, clamp_size(width), clamp_size(height)];
+                let rect = angle.map_or(rect, |angle| rect.rotated(angle.to_radians(), center));
+                let position: Point<i32> = (pos - rect.top_left()).into();
+                s.draw_text(
+                    position,
+                    text,
+                    wrap_width,
+                    angle,
+                    center,
+                    flipped,
+                    color,
+                    outline,
+                    screen_clip,
+                )?;
+                Ok(rect![
+                    pos,
+                    rect.width() + rect.left(),
+                    rect.height() + rect.top()
+                ])
+            } else {
+                let (width, height) = s.draw_text(
+                    pos,
+                    text,
+                    wrap_width,
+                    None,
+                    center,
+                    flipped,
+                    color,
+                    outline,
+                    screen_clip,
+                )?;
+                Ok(rect![pos, clamp_size(width), clamp_size(height)])
+            }
+        };
+        match clip.zip(screen_clip) {
+            Some((clip, screen_clip)) => {
+                self.push_clip(screen_clip)?;
+                let rect = draw(self);
+                self.pop_clip()?;
+                let rect = rect?;
+                Ok(rect![
+                    rect.top_left(),
+                    rect.width().min(clip.width()),
+                    rect.height()
+                ])
+            }
+            None => draw(self),
+        }
+    }
+
+    /// Returns the outline width and color used to draw text, preferring the
+    /// [`PixState::text_outline`] over the current stroke settings.
+    pub(super) fn text_outline_style(&self) -> Option<(u16, Color)> {
+        let s = &self.settings;
+        s.text_outline.or_else(|| {
+            s.stroke
+                .filter(|_| s.stroke_weight > 0)
+                .map(|stroke| (s.stroke_weight, stroke))
+        })
+    }
+
+    /// Fit text to the [`PixState::wrap`] width based on the current [`TextOverflow`], disabling
+    /// wrapping. Returns the text truncated with an ellipsis if required.
+    /// [`TextOverflow::ShrinkToFit`] reduces the current font size instead. Callers are responsible
+    /// for restoring the wrap width and font size, even if an error is returned.
+    fn fit_text<'a>(&mut self, text: &'a str) -> PixResult<Cow<'a, str>> {
+        let s = &self.settings;
+        let (overflow, width) = match (s.text_overflow, s.wrap_width) {
+            (TextOverflow::Wrap | TextOverflow::Clip, _) | (_, None) => {
+                return Ok(Cow::Borrowed(text))
+            }
+            (overflow, Some(width)) => (overflow, width),
+        };
+        self.settings.wrap_width = None;
+        match overflow {
+            TextOverflow::Ellipsis | TextOverflow::WordEllipsis => {
+                let word = overflow == TextOverflow::WordEllipsis;
+                let measure =
+                    |line: &str| -> PixResult<u32> { Ok(self.renderer.size_of(line, None)?.0) };
+                if !text.contains('\n') {
+                    return truncate_line(text, width, word, measure);
+                }
+                let lines = text
+                    .lines()
+                    .map(|line| truncate_line(line, width, word, measure))
+                    .collect::<PixResult<Vec<_>>>()?;
+                Ok(Cow::Owned(lines.join("\n")))
+            }
+            TextOverflow::ShrinkToFit => {
+                let (text_width, _) = self.renderer.size_of(text, None)?;
+                if text_width > width {
+                    // Start from the proportionally scaled size and shrink until the text fits
+                    let size = u64::from(self.settings.font_size) * u64::from(width)
+                        / u64::from(text_width);
+                    let mut size = (size as u32).max(1);
+                    loop {
+                        self.settings.font_size = size;
+                        self.renderer.font_size(size)?;
+                        if size == 1 || self.renderer.size_of(text, None)?.0 <= width {
+                            break;
+                        }
+                        size -= 1;
+                    }
+                }
+                Ok(Cow::Borrowed(text))
+            }
+            TextOverflow::Wrap | TextOverflow::Clip => Ok(Cow::Borrowed(text)),
+        }
+    }
+
+    /// Draw text with the given font, restoring the current font afterwards.
+    fn text_with_font(
+        &mut self,
+        text: &str,
+        font: Font,
+        size: u32,
+        style: FontStyle,
+    ) -> PixResult<(u32, u32)> {
+        self.push();
+        // Fonts are set on the settings as well so recorded text replays with the same font
+        let s = &mut self.settings;
+        s.font_family = font;
+        s.font_size = size;
+        s.font_style = style;
+        let result = self
+            .renderer
+            .font_family(&s.font_family)
+            .and_then(|()| self.renderer.font_size(size))
+            .and_then(|()| {
+                self.renderer.font_style(style);
+                self.text_transformed(text, None, None, None)
+            });
+        self.pop();
+        result
+    }
+
+    /// Draw text to the current canvas or layer, recording it if a [`DisplayList`] is being
+    /// recorded. Text is transformed by the current [`Camera2D`], if set. `clip` is the area in
+    /// screen coordinates the text is clipped to by [`TextOverflow::Clip`], which the caller is
+    /// responsible for applying. Returns the size of the drawn text in world coordinates.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub(super) fn draw_text(
+        &mut self,
+        position: Point<i32>,
+        text: &str,
+        wrap_width: Option<u32>,
+        angle: Option<f64>,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        color: Color,
+        outline: u16,
+        clip: Option<Rect<i32>>,
+    ) -> PixResult<(u32, u32)> {
+        let camera = match self.settings.camera {
+            Some(camera) => camera,
+            None => {
+                return self.draw_screen_text(
+                    position, text, wrap_width, angle, center, flipped, color, outline, clip,
+                )
+            }
+        };
+        let size = self.renderer.size_of(text, wrap_width)?;
+        let (position, angle, center) = camera.transform_text(position, size, angle, center);
+        let scale = |length: u32| camera.transform_length(clamp_size(length)).max(1) as u32;
+        let wrap_width = wrap_width.map(scale);
+        let outline = camera
+            .transform_length(i32::from(outline))
+            .clamp(0, i32::from(u16::MAX)) as u16;
+        let font_size = self.settings.font_size;
+        self.settings.font_size = scale(font_size);
+        let result = self
+            .renderer
+            .font_size(self.settings.font_size)
+            .and_then(|()| {
+                self.draw_screen_text(
+                    position, text, wrap_width, angle, center, flipped, color, outline, clip,
+                )
+            });
+        self.settings.font_size = font_size;
+        self.renderer.font_size(font_size)?;
+        result.map(|_| size)
+    }
+
+    /// Draw text in screen coordinates to the current canvas or layer, recording it if a
+    /// [`DisplayList`] is being recorded. Returns the size of the drawn text.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_screen_text(
+        &mut self,
+        position: Point<i32>,
+        text: &str,
+        wrap_width: Option<u32>,
+        angle: Option<f64>,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        color: Color,
+        outline: u16,
+        clip: Option<Rect<i32>>,
+    ) -> PixResult<(u32, u32)> {
+        let command = |s: &Settings| DrawCommand::Text {
+            position,
+            text: text.to_owned(),
+            wrap_width,
+            angle,
+            center,
+            flipped,
+            fill: Some(color),
+            outline,
+            font_family: s.font_family.clone(),
+            font_size: s.font_size,
+            font_style: s.font_style,
+            align: s.text_align,
+            clip,
+        };
+        let deferred = self.active_layer.is_some() && {
+            let command = command(&self.settings);
+            self.defer(|| command)
+        };
+        let size = if deferred {
+            self.renderer.size_of(text, wrap_width)?
+        } else {
             self.renderer.text(
-                (pos - rect.top_left()).into(),
+                position,
                 text,
                 wrap_width,
                 angle,
@@ -563,23 +829,80 @@ impl PixState {
                 flipped,
                 Some(color),
                 outline,
-            )?;
-            rect![pos, rect.width() + rect.left(), rect.height() + rect.top()]
-        } else {
-            let (width, height) = self.renderer.text(
-                pos,
-                text,
-                wrap_width,
-                None,
-                center,
-                flipped,
-                Some(color),
-                outline,
-            )?;
-            rect![pos, clamp_size(width), clamp_size(height)]
+            )?
         };
+        if let Some(list) = &mut self.display_list {
+            list.push(command(&self.settings));
+        }
+        Ok(size)
+    }
+}
 
-        self.pop();
-        Ok(rect)
+/// Truncate a single line of text with an ellipsis so that it fits within `width` as measured by
+/// `measure`, either after the last character or the last word that fits. Returns an empty string
+/// if not even the ellipsis fits.
+fn truncate_line<F>(line: &str, width: u32, word: bool, measure: F) -> PixResult<Cow<'_, str>>
+where
+    F: Fn(&str) -> PixResult<u32>,
+{
+    if measure(line)? <= width {
+        return Ok(Cow::Borrowed(line));
+    }
+    let truncated = |end: usize| {
+        let mut truncated = line[..end].trim_end().to_owned();
+        truncated.push(ELLIPSIS);
+        truncated
+    };
+    let char_ends = || line.char_indices().map(|(idx, _)| idx);
+    let mut ends: Vec<usize> = if word {
+        char_ends()
+            .filter(|&idx| line[idx..].starts_with(char::is_whitespace))
+            .collect()
+    } else {
+        char_ends().collect()
+    };
+    if ends.first() != Some(&0) {
+        ends.insert(0, 0);
+    }
+    // Binary search for the number of truncation points that fit
+    let (mut low, mut high) = (0, ends.len());
+    while low < high {
+        let mid = (low + high) / 2;
+        if measure(&truncated(ends[mid]))? <= width {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    match low {
+        0 => Ok(Cow::Borrowed("")),
+        1 if word => truncate_line(line, width, false, measure),
+        _ => Ok(Cow::Owned(truncated(ends[low - 1]))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measure(text: &str) -> PixResult<u32> {
+        Ok(10 * text.chars().count() as u32)
+    }
+
+    #[test]
+    fn truncate_line_with_ellipsis() -> PixResult<()> {
+        assert_eq!(
+            truncate_line("Lorem ipsum", 110, false, measure)?,
+            "Lorem ipsum"
+        );
+        assert_eq!(
+            truncate_line("Lorem ipsum", 80, false, measure)?,
+            "Lorem i…"
+        );
+        assert_eq!(truncate_line("Lorem ipsum", 70, false, measure)?, "Lorem…");
+        assert_eq!(truncate_line("Lorem ipsum", 100, true, measure)?, "Lorem…");
+        assert_eq!(truncate_line("Lorem ipsum", 40, true, measure)?, "Lor…");
+        assert_eq!(truncate_line("Lorem ipsum", 5, false, measure)?, "");
+        Ok(())
     }
 }
@@ -0,0 +1,348 @@
+//! Text along a path rendering methods.
+//!
+//! Glyphs are placed one at a time along a polyline or Bezier curve and rotated to match the
+//! direction of the path where they are placed, such as for curved map labels or circular
+//! badges. [`PixState::text_align`] positions the text along the path and relative to it.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::text_on_path`]
+//! - [`PixState::text_on_bezier`]
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App;
+//! # impl PixEngine for App {
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     s.text_align(TextAlign::Center, VerticalAlign::Baseline);
+//!     s.text_on_bezier("Curved label", [[20, 120], [80, 20], [160, 20], [220, 120]])?;
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::{ops::clamp_size, prelude::*, renderer::Rendering};
+
+impl PixState {
+    /// Draw text along a polyline through a list of points to the current canvas, rotating each
+    /// glyph to match the direction of the line segment it is drawn on. Text is drawn on a single
+    /// line and glyphs past the end of the path are not drawn.
+    ///
+    /// [`TextAlign`] positions the text at the start, middle or end of the path and
+    /// [`VerticalAlign`] positions the text relative to the path, e.g.
+    /// [`VerticalAlign::Baseline`] draws the baseline of the text along the path.
+    ///
+    /// # Errors
+    ///
+    /// If the renderer fails to draw to the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Draw a circular badge label
+    ///     let points: Vec<Point<i32>> = (0..=36)
+    ///         .map(|i| {
+    ///             let angle = 180.0f64.to_radians() + f64::from(i) * 5.0f64.to_radians();
+    ///             point![100 + (80.0 * angle.cos()) as i32, 100 + (80.0 * angle.sin()) as i32]
+    ///         })
+    ///         .collect();
+    ///     s.text_align(TextAlign::Center, VerticalAlign::Bottom);
+    ///     s.text_on_path("Employee of the month", points)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn text_on_path<S, P, I>(&mut self, text: S, points: I) -> PixResult<()>
+    where
+        S: AsRef<str>,
+        P: Into<Point<i32>>,
+        I: IntoIterator<Item = P>,
+    {
+        let points: Vec<Point<f64>> = points
+            .into_iter()
+            .map(|p| Into::<Point<i32>>::into(p).as_())
+            .collect();
+        self.render_text_on_path(text.as_ref(), &points)
+    }
+
+    /// Draw text along a Bezier curve through a list of anchor and control points to the current
+    /// canvas, rotating each glyph to match the direction of the curve.
+    /// [`PixState::bezier_detail`] controls the resolution of the curve. See
+    /// [`PixState::text_on_path`] for how text is positioned along the curve.
+    ///
+    /// # Errors
+    ///
+    /// If the renderer fails to draw to the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.text_align(TextAlign::Center, VerticalAlign::Baseline);
+    ///     s.text_on_bezier("River Styx", [[20, 80], [60, 20], [140, 140], [180, 80]])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn text_on_bezier<S, P, I>(&mut self, text: S, points: I) -> PixResult<()>
+    where
+        S: AsRef<str>,
+        P: Into<Point<i32>>,
+        I: IntoIterator<Item = P>,
+    {
+        let points: Vec<Point<f64>> = points
+            .into_iter()
+            .map(|p| Into::<Point<i32>>::into(p).as_())
+            .collect();
+        let steps = self.settings.bezier_detail.max(2);
+        let curve: Vec<Point<f64>> = if points.is_empty() {
+            points
+        } else {
+            (0..=steps)
+                .map(|step| bezier_point(&points, f64::from(step) / f64::from(steps)))
+                .collect()
+        };
+        self.render_text_on_path(text.as_ref(), &curve)
+    }
+}
+
+impl PixState {
+    /// Draw each glyph of text along a polyline.
+    fn render_text_on_path(&mut self, text: &str, points: &[Point<f64>]) -> PixResult<()> {
+        if points.len() < 2 || text.is_empty() {
+            return Ok(());
+        }
+        let s = &self.settings;
+        let fill = s.fill.unwrap_or(Color::TRANSPARENT);
+        let (text_align, vertical_align) = (s.text_align, s.vertical_align);
+        let shadow = s.text_shadow;
+        let colors = self.theme.colors;
+        let outline = self.text_outline_style();
+        let fill = if self.ui.disabled {
+            fill.blended(colors.background, 0.38)
+        } else {
+            fill
+        };
+
+        let text = text.replace('\n', " ");
+        let layout = self.renderer.layout_text(&text, None)?;
+        let y_offset = match vertical_align {
+            VerticalAlign::Top => 0,
+            VerticalAlign::Middle => clamp_size(layout.height()) / 2,
+            VerticalAlign::Baseline => clamp_size(layout.ascent()),
+            VerticalAlign::Bottom => clamp_size(layout.height()),
+        };
+        let glyphs = place_glyphs(&layout, points, text_align, y_offset);
+
+        // Outlined glyphs are larger by the outline width on each side
+        let weight = outline.map_or(0, |(weight, _)| weight);
+        let offset = i32::from(weight);
+        if let Some(shadow) = shadow {
+            for (shadow_offset, color) in shadow.copies() {
+                for &(c, position, center, angle) in &glyphs {
+                    let position = point![
+                        position.x() + shadow_offset.x() - offset,
+                        position.y() + shadow_offset.y() - offset
+                    ];
+                    let center = point![center.x() + offset, center.y() + offset];
+                    self.draw_path_glyph(c, position, center, angle, color, weight)?;
+                }
+            }
+        }
+        if let Some((weight, stroke)) = outline {
+            for &(c, position, center, angle) in &glyphs {
+                let position = point![position.x() - offset, position.y() - offset];
+                let center = point![center.x() + offset, center.y() + offset];
+                self.draw_path_glyph(c, position, center, angle, stroke, weight)?;
+            }
+        }
+        for &(c, position, center, angle) in &glyphs {
+            self.draw_path_glyph(c, position, center, angle, fill, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Draw a single glyph rotated by `angle` degrees about `center`, relative to `position`.
+    fn draw_path_glyph(
+        &mut self,
+        c: char,
+        position: Point<i32>,
+        center: Point<i32>,
+        angle: f64,
+        color: Color,
+        outline: u16,
+    ) -> PixResult<()> {
+        let glyph = c.encode_utf8(&mut [0; 4]).to_owned();
+        let (angle, center) = (Some(angle), Some(center));
+        self.draw_text(
+            position, &glyph, None, angle, center, None, color, outline, None,
+        )?;
+        Ok(())
+    }
+}
+
+/// A glyph placed along a path as `(char, position, center, angle)`, drawn rotated by `angle`
+/// degrees about `center`, relative to `position`.
+type PathGlyph = (char, Point<i32>, Point<i32>, f64);
+
+/// Places each glyph of a text layout along a polyline, aligned along the path with `text_align`
+/// and offset above the path by `y_offset`. Whitespace and glyphs past either end of the path are
+/// skipped.
+fn place_glyphs(
+    layout: &TextLayout,
+    points: &[Point<f64>],
+    text_align: TextAlign,
+    y_offset: i32,
+) -> Vec<PathGlyph> {
+    let distances: Vec<f64> = points
+        .windows(2)
+        .scan(0.0, |distance, segment| {
+            *distance += segment[0].dist(segment[1]);
+            Some(*distance)
+        })
+        .collect();
+    let length = distances.last().copied().unwrap_or(0.0);
+    let start = f64::from(text_align.offset((length as u32).saturating_sub(layout.width())));
+
+    let mut glyphs = Vec::new();
+    let mut segment = 0;
+    for glyph in layout.glyphs() {
+        let rect = glyph.rect();
+        let distance = start + f64::from(rect.x()) + f64::from(rect.width()) / 2.0;
+        if glyph.char().is_whitespace() || distance < 0.0 {
+            continue;
+        } else if distance > length {
+            break;
+        }
+        // Glyphs are laid out in order, so the segment only ever moves forward
+        while distances[segment] < distance && segment + 1 < distances.len() {
+            segment += 1;
+        }
+        let (p1, p2) = (points[segment], points[segment + 1]);
+        let segment_start = if segment == 0 {
+            0.0
+        } else {
+            distances[segment - 1]
+        };
+        let segment_length = distances[segment] - segment_start;
+        let t = if segment_length > 0.0 {
+            (distance - segment_start) / segment_length
+        } else {
+            0.0
+        };
+        let anchor = point![
+            p1.x() + (p2.x() - p1.x()) * t,
+            p1.y() + (p2.y() - p1.y()) * t
+        ];
+        let angle = (p2.y() - p1.y()).atan2(p2.x() - p1.x()).to_degrees();
+        // Each glyph is rotated about the point on the path it is anchored to
+        let center = point![rect.width() / 2, y_offset];
+        let position = point![
+            anchor.x().round() as i32 - center.x(),
+            anchor.y().round() as i32 - center.y()
+        ];
+        glyphs.push((glyph.char(), position, center, angle));
+    }
+    glyphs
+}
+
+/// Returns the point at `t` between `0.0` and `1.0` along a Bezier curve through a list of anchor
+/// and control points using De Casteljau's algorithm.
+fn bezier_point(points: &[Point<f64>], t: f64) -> Point<f64> {
+    let mut ps = points.to_vec();
+    while ps.len() > 1 {
+        ps = ps
+            .windows(2)
+            .map(|w| {
+                point![
+                    w[0].x() + (w[1].x() - w[0].x()) * t,
+                    w[0].y() + (w[1].y() - w[0].y()) * t
+                ]
+            })
+            .collect();
+    }
+    ps[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::text_layout::{FontMetrics, GlyphAdvances};
+
+    const METRICS: FontMetrics = FontMetrics {
+        height: 16,
+        line_skip: 20,
+        ascent: 12,
+    };
+
+    /// Returns the point on the path each glyph is anchored to.
+    fn anchor(position: Point<i32>, center: Point<i32>) -> Point<i32> {
+        point![position.x() + center.x(), position.y() + center.y()]
+    }
+
+    fn layout(text: &str) -> PixResult<TextLayout> {
+        let mut advances = GlyphAdvances::default();
+        TextLayout::new(text, None, TextAlign::Left, METRICS, &mut advances, |s| {
+            Ok(10 * s.chars().count() as u32)
+        })
+    }
+
+    #[test]
+    fn bezier_point_interpolates_curve() {
+        let points = [point![0.0, 0.0], point![10.0, 20.0], point![20.0, 0.0]];
+        assert_eq!(bezier_point(&points, 0.0), point![0.0, 0.0]);
+        assert_eq!(bezier_point(&points, 0.5), point![10.0, 10.0]);
+        assert_eq!(bezier_point(&points, 1.0), point![20.0, 0.0]);
+        // A line is its own curve
+        let line = [point![0.0, 0.0], point![10.0, 20.0]];
+        assert_eq!(bezier_point(&line, 0.25), point![2.5, 5.0]);
+    }
+
+    #[test]
+    fn place_glyphs_follows_segments() -> PixResult<()> {
+        let points = [point![0.0, 0.0], point![20.0, 0.0], point![20.0, 20.0]];
+        let glyphs = place_glyphs(&layout("abc d")?, &points, TextAlign::Left, 12);
+        let placed: Vec<_> = glyphs
+            .iter()
+            .map(|&(c, position, center, angle)| (c, anchor(position, center), angle))
+            .collect();
+        // Whitespace is skipped and glyphs past the end of the path are dropped
+        assert_eq!(
+            placed,
+            [
+                ('a', point![5, 0], 0.0),
+                ('b', point![15, 0], 0.0),
+                ('c', point![20, 5], 90.0),
+            ]
+        );
+        assert!(glyphs
+            .iter()
+            .all(|&(_, _, center, _)| center == point![5, 12]));
+        Ok(())
+    }
+
+    #[test]
+    fn place_glyphs_aligns_along_path() -> PixResult<()> {
+        let points = [point![0.0, 0.0], point![100.0, 0.0]];
+        let layout = layout("ab")?;
+        let first_anchor = |align| {
+            let glyphs = place_glyphs(&layout, &points, align, 0);
+            glyphs
+                .first()
+                .map(|&(_, position, center, _)| anchor(position, center))
+        };
+        assert_eq!(first_anchor(TextAlign::Left), Some(point![5, 0]));
+        assert_eq!(first_anchor(TextAlign::Center), Some(point![45, 0]));
+        assert_eq!(first_anchor(TextAlign::Right), Some(point![85, 0]));
+        Ok(())
+    }
+}
@@ -1,12 +1,19 @@
 //! [Image] and [`PixelFormat`] functions.
 
-use crate::{ops::clamp_dimensions, prelude::*, renderer::Rendering};
+use crate::{
+    ops::clamp_dimensions,
+    prelude::*,
+    renderer::{Rendering, WindowRenderer},
+    texture::tile_rects,
+};
 #[cfg(not(target_arch = "wasm32"))]
 use anyhow::Context;
 #[cfg(not(target_arch = "wasm32"))]
 use png::{BitDepth, ColorType, Decoder};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "sdl")]
+use std::sync::atomic::{AtomicU64, Ordering};
 #[cfg(not(target_arch = "wasm32"))]
 use std::{
     ffi::OsStr,
@@ -73,10 +80,29 @@ impl Default for PixelFormat {
     }
 }
 
+/// Identifier shared by an [Image] and its clones, used to cache renderer textures.
+#[cfg(feature = "sdl")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ImageId(u64);
+
+#[cfg(feature = "sdl")]
+impl Default for ImageId {
+    fn default() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 /// An `Image` representing a buffer of pixel color values.
 #[derive(Default, Clone)]
 #[must_use]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Image {
+    /// `Image` identifier. Clones share the same identifier so that deferred or recorded draws
+    /// reuse the same renderer texture.
+    #[cfg(feature = "sdl")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    id: ImageId,
     /// `Image` width.
     width: u32,
     /// `Image` height.
@@ -176,6 +202,8 @@ impl Image {
     #[inline]
     pub fn from_vec(width: u32, height: u32, data: Vec<u8>, format: PixelFormat) -> Self {
         Self {
+            #[cfg(feature = "sdl")]
+            id: ImageId::default(),
             width,
             height,
             data,
@@ -240,6 +268,13 @@ impl Image {
         Self::from_bytes(info.width, info.height, data, format)
     }
 
+    /// Returns the `Image` identifier, shared by its clones.
+    #[inline]
+    #[cfg(feature = "sdl")]
+    pub(crate) const fn id(&self) -> ImageId {
+        self.id
+    }
+
     /// Returns the `Image` width.
     #[inline]
     #[must_use]
@@ -521,18 +556,110 @@ impl PixState {
         if s.angle_mode == AngleMode::Radians {
             angle = angle.to_degrees();
         };
-        self.renderer.image(
-            img,
-            src.into(),
+        let mut center = center.into();
+        if let (Some(camera), Some(rect)) = (s.camera, dst) {
+            let transformed = camera.transform_dst(rect, angle, center);
+            (dst, angle, center) = (Some(transformed.0), transformed.1, transformed.2);
+        }
+        let (src, flipped, tint) = (src.into(), flipped.into(), s.image_tint);
+        let command = || DrawCommand::Image {
+            image: img.clone(),
+            src,
             dst,
             angle,
-            center.into(),
-            flipped.into(),
-            s.image_tint,
-        )
+            center,
+            flipped,
+            tint,
+        };
+        if !self.defer(command) {
+            self.renderer
+                .image(img, src, dst, angle, center, flipped, tint)?;
+        }
+        self.record(command);
+        Ok(())
+    }
+
+    /// Draw a portion `src` of an [Image] repeated to fill the target `dst`, scrolled by `offset`.
+    /// Each tile is drawn at the size of `src` and tiles crossing the edges of `dst` are clipped.
+    /// Passing `None` for `src` repeats the entire image. Passing `None` for `dst` fills the entire
+    /// render target. [`PixState::image_tint`] can optionally add a tint color to the rendered
+    /// image.
+    ///
+    /// # Errors
+    ///
+    /// If the renderer fails to draw to the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { pattern: Image };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Fill a panel with a repeating pattern
+    ///     s.image_tiled(&self.pattern, None, rect![10, 10, 200, 100], [0, 0])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn image_tiled<R1, R2, P>(
+        &mut self,
+        img: &Image,
+        src: R1,
+        dst: R2,
+        offset: P,
+    ) -> PixResult<()>
+    where
+        R1: Into<Option<Rect<i32>>>,
+        R2: Into<Option<Rect<i32>>>,
+        P: Into<Point<i32>>,
+    {
+        let src = src.into().unwrap_or_else(|| img.bounding_rect());
+        let dst = match dst.into() {
+            Some(dst) if self.settings.image_mode == ImageMode::Center => {
+                Rect::from_center(dst.top_left(), dst.width(), dst.height())
+            }
+            Some(dst) => dst,
+            None => {
+                let (width, height) = self.renderer.dimensions()?;
+                let (width, height) = clamp_dimensions(width, height);
+                rect![0, 0, width, height]
+            }
+        };
+        let tint = self.settings.image_tint;
+        let camera = self.settings.camera;
+        for (src, dst) in tile_rects(src, dst, offset.into()) {
+            let (dst, angle, center) = camera.map_or((dst, 0.0, None), |camera| {
+                camera.transform_dst(dst, 0.0, None)
+            });
+            let command = || DrawCommand::Image {
+                image: img.clone(),
+                src: Some(src),
+                dst: Some(dst),
+                angle,
+                center,
+                flipped: None,
+                tint,
+            };
+            if !self.defer(command) {
+                self.renderer
+                    .image(img, Some(src), Some(dst), angle, center, None, tint)?;
+            }
+            self.record(command);
+        }
+        Ok(())
     }
 }
 
+impl PartialEq for Image {
+    fn eq(&self, other: &Self) -> bool {
+        (self.width, self.height, self.format, &self.data)
+            == (other.width, other.height, other.format, &other.data)
+    }
+}
+
+impl Eq for Image {}
+
 impl fmt::Debug for Image {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Image")
@@ -609,3 +736,18 @@ impl From<Image> for Icon {
         Self::Image(img)
     }
 }
+
+#[cfg(all(test, feature = "sdl"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_clones_share_id() {
+        let img = Image::new(2, 2);
+        let clone = img.clone();
+        assert_eq!(img.id(), clone.id());
+        let other = Image::new(2, 2);
+        assert_ne!(img.id(), other.id());
+        assert_eq!(img, other);
+    }
+}
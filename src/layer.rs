@@ -0,0 +1,222 @@
+//! Draw layer functions.
+//!
+//! Layers allow drawing to be submitted in any order and composited by depth at the end of the
+//! frame, so that drawing order no longer has to match update order. Drawing within a layer is
+//! recorded as a [`DisplayList`] instead of being rendered, and every layer is replayed to the
+//! window after [`PixEngine::on_update`] returns, in order of increasing `z`.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::layer`]: Draw to a layer at a given depth.
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App { player: Point<i32>, enemies: Vec<Point<i32>> };
+//! # impl PixEngine for App {
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     // Drawn above the enemies, even though it's drawn first
+//!     s.layer(1, |s| {
+//!         s.fill(Color::BLUE);
+//!         s.circle([self.player.x(), self.player.y(), 10])
+//!     })?;
+//!     s.layer(0, |s| {
+//!         s.fill(Color::RED);
+//!         for enemy in &self.enemies {
+//!             s.circle([enemy.x(), enemy.y(), 10])?;
+//!         }
+//!         Ok(())
+//!     })?;
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::{prelude::*, renderer::Rendering, texture::TextureRenderer};
+use std::mem;
+
+/// Drawing recorded to a layer, along with the render settings it was drawn with.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Layer {
+    pub(crate) z: i32,
+    pub(crate) clip: Option<Rect<i32>>,
+    pub(crate) scale: (f32, f32),
+    pub(crate) blend_mode: BlendMode,
+    pub(crate) commands: DisplayList,
+}
+
+impl PixState {
+    /// Draw to a layer at depth `z`. Drawing within `f` is recorded instead of being rendered and
+    /// is replayed to the window after [`PixEngine::on_update`] returns in order of increasing
+    /// `z`, so that higher layers are drawn on top of lower ones, regardless of the order `layer`
+    /// was called in. Anything drawn outside of a layer is drawn immediately and ends up below all
+    /// layers.
+    ///
+    /// Calling `layer` multiple times with the same `z` in a frame draws to the same layer in the
+    /// order called. Current settings, such as [`PixState::fill`] or [`PixState::set_camera`],
+    /// apply within `f`, but any changes made within `f` are reverted afterwards. Drawing is
+    /// replayed with the clip rectangle, scale and [`BlendMode`] it was drawn with. Drawing to a
+    /// texture target set within `f` is rendered immediately.
+    ///
+    /// Layers are only replayed to the window, so calling `layer` while a texture target is set
+    /// renders `f` to the texture immediately instead.
+    ///
+    /// # Errors
+    ///
+    /// If `f` returns an error, then an error is returned and everything drawn to the layer by
+    /// `f` is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { pos: Point<i32> };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.layer(10, |s| {
+    ///         s.fill(Color::YELLOW);
+    ///         s.rect([self.pos.x(), self.pos.y(), 20, 20])
+    ///     })?;
+    ///     // Drawn beneath the yellow square
+    ///     s.fill(Color::GRAY);
+    ///     s.rect([0, 0, 100, 100])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn layer<F>(&mut self, z: i32, f: F) -> PixResult<()>
+    where
+        F: FnOnce(&mut PixState) -> PixResult<()>,
+    {
+        if self.renderer.texture_target().is_some() {
+            self.push();
+            let result = f(self);
+            self.pop();
+            return result;
+        }
+        let index = self.push_layer(z);
+        let outer = self
+            .active_layer
+            .replace((index, self.renderer.texture_target()));
+        self.push();
+        let result = f(self);
+        self.pop();
+        self.active_layer = outer;
+        if result.is_err() {
+            self.layers.truncate(index);
+        }
+        result
+    }
+}
+
+impl PixState {
+    /// Record a [`DrawCommand`] to the current layer instead of rendering it. Returns whether the
+    /// command was recorded, which is only the case while drawing to the render target the layer
+    /// was started on.
+    #[inline]
+    pub(crate) fn defer<F>(&mut self, command: F) -> bool
+    where
+        F: FnOnce() -> DrawCommand,
+    {
+        match self.active_layer {
+            Some((mut index, target)) if target == self.renderer.texture_target() => {
+                let s = &self.settings;
+                let layer = &self.layers[index];
+                if (layer.clip, layer.scale, layer.blend_mode)
+                    != (s.clip, (s.scale_x, s.scale_y), s.blend_mode)
+                {
+                    // Render settings changed, so start a new layer at the same depth
+                    index = self.push_layer(layer.z);
+                    self.active_layer = Some((index, target));
+                }
+                self.layers[index].commands.push(command());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Add an empty layer at depth `z` with the current render settings, returning its index.
+    fn push_layer(&mut self, z: i32) -> usize {
+        let s = &self.settings;
+        self.layers.push(Layer {
+            z,
+            clip: s.clip,
+            scale: (s.scale_x, s.scale_y),
+            blend_mode: s.blend_mode,
+            commands: DisplayList::new(),
+        });
+        self.layers.len() - 1
+    }
+
+    /// Replay all layers drawn to this frame in order of increasing `z`.
+    pub(crate) fn draw_layers(&mut self) -> PixResult<()> {
+        if self.layers.is_empty() {
+            return Ok(());
+        }
+        let mut layers = mem::take(&mut self.layers);
+        sort_layers(&mut layers);
+        let result = layers.iter().try_for_each(|layer| {
+            self.renderer.clip(layer.clip)?;
+            self.renderer.scale(layer.scale.0, layer.scale.1)?;
+            self.renderer.blend_mode(layer.blend_mode);
+            layer.commands.render(self, layer.clip)
+        });
+        // Reuse the allocation for the next frame
+        layers.clear();
+        self.layers = layers;
+        let s = &self.settings;
+        self.renderer.blend_mode(s.blend_mode);
+        result
+            .and(self.renderer.clip(s.clip))
+            .and(self.renderer.scale(s.scale_x, s.scale_y))
+    }
+}
+
+/// Sort layers by increasing `z`, keeping layers with the same `z` in the order they were drawn.
+fn sort_layers(layers: &mut [Layer]) {
+    layers.sort_by_key(|layer| layer.z);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(z: i32, color: Color) -> Layer {
+        Layer {
+            z,
+            clip: None,
+            scale: (1.0, 1.0),
+            blend_mode: BlendMode::None,
+            commands: [DrawCommand::Clear { color }].into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn layers_sort_independent_of_call_order() {
+        let expected = [
+            layer(-1, Color::BLACK),
+            layer(0, Color::RED),
+            layer(0, Color::GREEN),
+            layer(2, Color::BLUE),
+        ];
+        let mut layers = vec![
+            expected[3].clone(),
+            expected[1].clone(),
+            expected[0].clone(),
+            expected[2].clone(),
+        ];
+        sort_layers(&mut layers);
+        assert_eq!(layers, expected);
+
+        let mut layers = vec![
+            expected[1].clone(),
+            expected[2].clone(),
+            expected[3].clone(),
+            expected[0].clone(),
+        ];
+        sort_layers(&mut layers);
+        assert_eq!(layers, expected);
+    }
+}
@@ -43,16 +43,21 @@
 
 #[macro_use]
 pub mod color;
+pub mod display_list;
 pub mod draw;
 pub mod engine;
 pub mod error;
 #[macro_use]
 pub mod shape;
 pub mod audio;
+pub mod camera;
 pub mod event;
 pub mod image;
+pub mod layer;
 pub mod lighting;
 pub mod ops;
+#[cfg(feature = "recording")]
+pub mod recording;
 pub mod state;
 pub mod texture;
 pub mod window;
@@ -72,7 +77,9 @@ pub mod prelude {
         AudioCallback, AudioDevice, AudioDeviceDriver, AudioFormat, AudioFormatNum, AudioSpec,
         AudioSpecDesired, AudioStatus,
     };
+    pub use super::camera::Camera2D;
     pub use super::color::{Color, Mode as ColorMode};
+    pub use super::display_list::{DisplayList, DrawCommand};
     pub use super::draw::Draw;
     pub use super::engine::{Engine, PixEngine};
     pub use super::error::{Error as PixError, Result as PixResult};
@@ -80,24 +87,32 @@ pub mod prelude {
         Axis, ControllerButton, ControllerEvent, ControllerId, ControllerUpdate, Event, HatState,
         Key, KeyEvent, KeyMod, Mouse, WindowEvent,
     };
+    pub use super::gui::text_layout::{TextGlyph, TextLayout, TextLine};
     pub use super::gui::theme::{self, ColorType, Font, Theme};
+    pub use super::gui::widgets::rich_text::{RichText, TextSpan};
     pub use super::image::{Image, PixelFormat};
     pub use super::lighting::{Light, LightSource};
     pub use super::math::{map, random_rng, Float, Num};
-    pub use super::shape::{Contains, Ellipse, Intersects, Line, Point, Quad, Rect, Sphere, Tri};
+    #[cfg(feature = "recording")]
+    pub use super::recording::RecordingFormat;
+    pub use super::shape::{
+        Collide, Contains, Ellipse, Intersects, Line, Manifold, Point, Polygon, PolygonSet, Quad,
+        Rect, Rounding, Sphere, Tri, Winding,
+    };
     pub use super::state::{
         settings::{
-            AngleMode, ArcMode, BlendMode, DrawMode, EllipseMode, FontStyle, ImageMode, RectMode,
+            AngleMode, ArcMode, BlendMode, ClipMask, DrawMode, EllipseMode, FontStyle, ImageMode,
+            RectMode, TextAlign, TextOverflow, VerticalAlign,
         },
         PixState,
     };
-    pub use super::texture::TextureId;
+    pub use super::texture::{ScaleQuality, TextureId};
     pub use super::transform::Flipped;
     pub use super::vector::Vector;
-    pub use super::window::{Cursor, Position, SystemCursor, WindowId};
+    pub use super::window::{Cursor, Position, ScaleMode, SystemCursor, WindowId};
 
     // Shape macros
-    pub use {circle, ellipse, line_, point, quad, rect, sphere, square, tri};
+    pub use {circle, ellipse, line_, point, polygon, quad, rect, sphere, square, tri};
     // Math macros
     pub use {noise, random, vector};
     // Color macros
@@ -0,0 +1,405 @@
+//! Canvas recording functions.
+//!
+//! Captures each presented frame of the current window target to an animated [gif] file, using
+//! palette quantization, or an uncompressed [Y4M] video stream which can be converted with tools
+//! like `ffmpeg`.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::start_recording`]: Start recording presented frames to a file.
+//! - [`PixState::stop_recording`]: Stop recording and finish writing the file.
+//! - [`PixState::is_recording`]: Whether frames are currently being recorded.
+//!
+//! [gif]: https://en.wikipedia.org/wiki/GIF
+//! [Y4M]: https://wiki.multimedia.cx/index.php/YUV4MPEG2
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App;
+//! # impl PixEngine for App {
+//! # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+//! fn on_key_pressed(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+//!     if let Key::R = event.key {
+//!         if s.is_recording() {
+//!             s.stop_recording()?;
+//!         } else {
+//!             s.start_recording("demo.gif", RecordingFormat::Gif)?;
+//!         }
+//!     }
+//!     Ok(false)
+//! }
+//! # }
+//! ```
+
+use crate::{prelude::*, renderer::Rendering};
+use anyhow::Context;
+use log::{info, warn};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// Shortest frame delay supported by most GIF viewers.
+const MIN_GIF_DELAY: Duration = Duration::from_millis(20);
+/// Frame rate used for Y4M recordings when no target frame rate is set.
+const DEFAULT_FRAME_RATE: usize = 60;
+/// Speed of GIF palette quantization, from `1` (slowest, best quality) to `30`.
+const GIF_QUANTIZE_SPEED: i32 = 10;
+
+/// File format used to record presented frames.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecordingFormat {
+    /// Animated GIF, quantized to a 256 color palette per frame. Frame delays are based on the time
+    /// between presented frames, skipping frames presented faster than 50 frames per second.
+    Gif,
+    /// Uncompressed YUV4MPEG2 video with 4:4:4 chroma. Every presented frame is written, played
+    /// back at the target frame rate, or 60 frames per second if none is set.
+    Y4m,
+}
+
+/// Encoder writing frames to the recording file.
+enum Encoder {
+    Gif {
+        encoder: gif::Encoder<BufWriter<File>>,
+        // Last captured frame, written once the next frame's timing is known
+        pending: Option<(Vec<u8>, Instant)>,
+        last_delay: u16,
+    },
+    Y4m(BufWriter<File>),
+}
+
+impl fmt::Debug for Encoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gif { last_delay, .. } => f
+                .debug_struct("Gif")
+                .field("last_delay", last_delay)
+                .finish_non_exhaustive(),
+            Self::Y4m(_) => f.debug_tuple("Y4m").finish(),
+        }
+    }
+}
+
+/// An in-progress recording.
+#[derive(Debug)]
+pub(crate) struct Recording {
+    encoder: Encoder,
+    width: u32,
+    height: u32,
+    frame_count: usize,
+}
+
+impl Recording {
+    /// Create a recording file at `path` for frames of the given dimensions.
+    fn new(
+        path: &Path,
+        format: RecordingFormat,
+        (width, height): (u32, u32),
+        frame_rate: usize,
+    ) -> PixResult<Self> {
+        let mut writer = BufWriter::new(
+            File::create(path).with_context(|| format!("failed to create {path:?}"))?,
+        );
+        let encoder = match format {
+            RecordingFormat::Gif => {
+                let (w, h) = (u16::try_from(width)?, u16::try_from(height)?);
+                let mut encoder = gif::Encoder::new(writer, w, h, &[])
+                    .with_context(|| format!("failed to write gif header: {path:?}"))?;
+                encoder.set_repeat(gif::Repeat::Infinite)?;
+                Encoder::Gif {
+                    encoder,
+                    pending: None,
+                    last_delay: 0,
+                }
+            }
+            RecordingFormat::Y4m => {
+                writeln!(
+                    writer,
+                    "YUV4MPEG2 W{width} H{height} F{frame_rate}:1 Ip A1:1 C444"
+                )
+                .with_context(|| format!("failed to write y4m header: {path:?}"))?;
+                Encoder::Y4m(writer)
+            }
+        };
+        Ok(Self {
+            encoder,
+            width,
+            height,
+            frame_count: 0,
+        })
+    }
+
+    /// Add a frame of RGBA `pixels` captured at `time`.
+    ///
+    /// # Errors
+    ///
+    /// If the length of `pixels` doesn't match the recording dimensions, then an error is
+    /// returned.
+    fn add_frame(&mut self, pixels: Vec<u8>, time: Instant) -> PixResult<()> {
+        let (width, height) = (self.width, self.height);
+        if pixels.len() != 4 * width as usize * height as usize {
+            return Err(PixError::InvalidImage {
+                width,
+                height,
+                size: pixels.len(),
+                format: PixelFormat::Rgba,
+            }
+            .into());
+        }
+        match self.encoder {
+            Encoder::Gif {
+                ref mut encoder,
+                ref mut pending,
+                ref mut last_delay,
+            } => {
+                if let Some((previous, previous_time)) = pending.take() {
+                    let elapsed = time - previous_time;
+                    if elapsed < MIN_GIF_DELAY {
+                        // Keep showing the previous frame
+                        *pending = Some((previous, previous_time));
+                        return Ok(());
+                    }
+                    *last_delay = centiseconds(elapsed);
+                    write_gif_frame(encoder, self.width, self.height, previous, *last_delay)?;
+                    self.frame_count += 1;
+                }
+                *pending = Some((pixels, time));
+            }
+            Encoder::Y4m(ref mut writer) => {
+                writer.write_all(b"FRAME\n")?;
+                writer.write_all(&rgba_to_yuv444(&pixels))?;
+                self.frame_count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write any remaining frames and flush the recording file.
+    fn finish(self) -> PixResult<usize> {
+        let mut frame_count = self.frame_count;
+        match self.encoder {
+            Encoder::Gif {
+                mut encoder,
+                pending,
+                last_delay,
+            } => {
+                if let Some((pixels, _)) = pending {
+                    let delay = last_delay.max(centiseconds(MIN_GIF_DELAY));
+                    write_gif_frame(&mut encoder, self.width, self.height, pixels, delay)?;
+                    frame_count += 1;
+                }
+                encoder.into_inner()?.flush()?;
+            }
+            Encoder::Y4m(mut writer) => writer.flush()?,
+        }
+        Ok(frame_count)
+    }
+}
+
+impl PixState {
+    /// Start recording each presented frame of the current window target to a file at `path`
+    /// using the given [`RecordingFormat`]. Any recording already in progress is stopped first.
+    /// Frames are captured when they're presented at the end of each
+    /// [`PixEngine::on_update`] until [`PixState::stop_recording`] is called or the application
+    /// exits. Frames are recorded at the size of the rendered output in pixels, which may be larger
+    /// than [`PixState::dimensions`] when a logical size is set or on high DPI displays. Frames
+    /// with different dimensions than when recording started, e.g. after the window is resized,
+    /// are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for any of the following:
+    ///     - The current render target is closed or dropped.
+    ///     - Stopping a previous recording fails.
+    ///     - An [`io::Error`] occurs attempting to create the file or write the header.
+    ///     - The window is larger than `65535` pixels in either dimension when recording a
+    ///       [`RecordingFormat::Gif`].
+    ///
+    /// [`io::Error`]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.start_recording("simulation.y4m", RecordingFormat::Y4m)?;
+    ///     Ok(())
+    /// }
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// # }
+    /// ```
+    pub fn start_recording<P>(&mut self, path: P, format: RecordingFormat) -> PixResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        self.stop_recording()?;
+        let path = path.as_ref();
+        info!("Recording {:?} to {}", format, path.display());
+        let frame_rate = self
+            .settings
+            .target_frame_rate
+            .unwrap_or(DEFAULT_FRAME_RATE);
+        // Frames are read back in output pixels, which can differ from the logical size
+        let (dimensions, _) = self.renderer.read_pixels()?;
+        self.recording = Some(Recording::new(path, format, dimensions, frame_rate)?);
+        Ok(())
+    }
+
+    /// Stop recording started with [`PixState::start_recording`], writing any remaining frames and
+    /// flushing the file. Does nothing if not recording.
+    ///
+    /// # Errors
+    ///
+    /// If an [`io::Error`] or encoding error occurs attempting to write the remaining frames, then
+    /// an error is returned.
+    ///
+    /// [`io::Error`]: std::io::Error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     if s.frame_count() == 300 {
+    ///         s.stop_recording()?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn stop_recording(&mut self) -> PixResult<()> {
+        if let Some(recording) = self.recording.take() {
+            let frame_count = recording.finish()?;
+            info!("Finished recording {frame_count} frames");
+        }
+        Ok(())
+    }
+
+    /// Returns whether presented frames are being recorded with [`PixState::start_recording`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     if s.is_recording() {
+    ///         s.fill(Color::RED);
+    ///         s.circle([20, 20, 5])?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+}
+
+impl PixState {
+    /// Capture the current window target as the next recorded frame, if recording. Recording is
+    /// stopped if the frame fails to be written.
+    pub(crate) fn record_frame(&mut self) {
+        let result = match self.recording {
+            Some(ref mut recording) => {
+                self.renderer
+                    .read_pixels()
+                    .and_then(|((width, height), pixels)| {
+                        if (width, height) == (recording.width, recording.height) {
+                            recording.add_frame(pixels, Instant::now())
+                        } else {
+                            Ok(())
+                        }
+                    })
+            }
+            None => return,
+        };
+        if let Err(err) = result {
+            warn!("Stopping recording: {err}");
+            let _ignore_result = self.stop_recording();
+        }
+    }
+}
+
+/// Quantize and write a frame of RGBA `pixels` to a GIF.
+fn write_gif_frame(
+    encoder: &mut gif::Encoder<BufWriter<File>>,
+    width: u32,
+    height: u32,
+    mut pixels: Vec<u8>,
+    delay: u16,
+) -> PixResult<()> {
+    // Dimensions were checked to fit when the recording started
+    let mut frame =
+        gif::Frame::from_rgba_speed(width as u16, height as u16, &mut pixels, GIF_QUANTIZE_SPEED);
+    frame.delay = delay;
+    encoder.write_frame(&frame)?;
+    Ok(())
+}
+
+/// Convert a [Duration] to GIF frame delay in hundredths of a second.
+fn centiseconds(duration: Duration) -> u16 {
+    u16::try_from((duration.as_millis() + 5) / 10).unwrap_or(u16::MAX)
+}
+
+/// Convert RGBA `pixels` to planar 8-bit Y, U and V using BT.601 limited range coefficients.
+fn rgba_to_yuv444(pixels: &[u8]) -> Vec<u8> {
+    let len = pixels.len() / 4;
+    let mut yuv = vec![0; len * 3];
+    let (y_plane, uv) = yuv.split_at_mut(len);
+    let (u_plane, v_plane) = uv.split_at_mut(len);
+    for (i, pixel) in pixels.chunks_exact(4).enumerate() {
+        let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(i32::from);
+        y_plane[i] = (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8;
+        u_plane[i] = (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8;
+        v_plane[i] = (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8;
+    }
+    yuv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yuv_conversion() {
+        let pixels = [0, 0, 0, 255, 255, 255, 255, 255];
+        assert_eq!(rgba_to_yuv444(&pixels), vec![16, 235, 128, 128, 128, 128]);
+    }
+
+    #[test]
+    fn mismatched_frame_size() -> PixResult<()> {
+        let path = std::env::temp_dir().join("pix_engine_mismatched_frame_size.y4m");
+        let mut recording = Recording::new(&path, RecordingFormat::Y4m, (4, 2), 60)?;
+        assert!(recording
+            .add_frame(vec![0; 4 * 2 * 2], Instant::now())
+            .is_err());
+        recording.add_frame(vec![0; 4 * 4 * 2], Instant::now())?;
+        assert_eq!(recording.finish()?, 1);
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn gif_delay() {
+        assert_eq!(centiseconds(Duration::from_millis(20)), 2);
+        assert_eq!(centiseconds(Duration::from_millis(33)), 3);
+        assert_eq!(centiseconds(Duration::from_millis(1000)), 100);
+    }
+}
@@ -5,11 +5,28 @@ use std::num::NonZeroUsize;
 
 pub(crate) use crate::{texture::TextureRenderer, window::WindowRenderer};
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    not(feature = "sdl"),
+    not(feature = "terminal")
+))]
+compile_error!("either the `sdl` or `terminal` feature must be enabled");
+#[cfg(all(not(target_arch = "wasm32"), feature = "sdl", feature = "terminal"))]
+compile_error!(
+    "the `sdl` and `terminal` features can't be enabled together, disable default features to \
+    use `terminal`"
+);
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "terminal")))]
 pub mod sdl;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "terminal")))]
 pub(crate) use sdl::Renderer;
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "terminal"))]
+pub mod terminal;
+#[cfg(all(not(target_arch = "wasm32"), feature = "terminal"))]
+pub(crate) use terminal::Renderer;
+
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
 #[cfg(target_arch = "wasm32")]
@@ -37,6 +54,10 @@ pub(crate) struct RendererSettings {
     pub(crate) scale_x: f32,
     /// Rendering scale for y-coordinates.
     pub(crate) scale_y: f32,
+    /// Logical rendering size, independent of the window size. `None` uses the window size.
+    pub(crate) logical_size: Option<(u32, u32)>,
+    /// How the logical rendering size is scaled to fit the window.
+    pub(crate) scale_mode: ScaleMode,
     /// Audio queue sample rate. `None` uses device default.
     pub(crate) audio_sample_rate: Option<i32>,
     /// Audio queue channel count. 1 for mono, 2 for stereo, etc. `None` uses device default.
@@ -77,6 +98,8 @@ impl Default for RendererSettings {
             height: 480,
             scale_x: 1.0,
             scale_y: 1.0,
+            logical_size: None,
+            scale_mode: ScaleMode::default(),
             audio_sample_rate: None,
             audio_channels: None,
             audio_buffer_size: None,
@@ -127,6 +150,13 @@ pub(crate) trait Rendering: Sized {
     /// Set the font family for drawing text to the current canvas.
     fn font_family(&mut self, font: &Font) -> Result<()>;
 
+    /// Set the horizontal alignment of each line of text drawn to the current canvas within the
+    /// width of the widest line.
+    fn text_align(&mut self, align: TextAlign);
+
+    /// Returns the distance from the top of a line of text to its baseline using the current font.
+    fn font_ascent(&self) -> Result<u32>;
+
     /// Get clipboard text from the system clipboard.
     fn clipboard_text(&self) -> String;
 
@@ -154,6 +184,10 @@ pub(crate) trait Rendering: Sized {
     /// as `(width, height)`.
     fn size_of(&self, text: &str, wrap_width: Option<u32>) -> Result<(u32, u32)>;
 
+    /// Lay out text using the current font, wrapping lines to `wrap_width` if provided. Each line
+    /// is aligned using the current text alignment.
+    fn layout_text(&self, text: &str, wrap_width: Option<u32>) -> Result<TextLayout>;
+
     /// Draw a pixel to the current canvas.
     fn point(&mut self, p: Point<i32>, color: Color) -> Result<()>;
 
@@ -240,7 +274,15 @@ pub(crate) trait Rendering: Sized {
     ) -> Result<()>;
 
     /// Return the current rendered target pixels as an array of bytes.
-    fn to_bytes(&mut self) -> Result<Vec<u8>>;
+    #[inline]
+    fn to_bytes(&mut self) -> Result<Vec<u8>> {
+        self.read_pixels().map(|(_, bytes)| bytes)
+    }
+
+    /// Return the current rendered target pixels as an array of RGBA bytes, along with the
+    /// `(width, height)` of the pixels read, which may differ from [`Rendering::dimensions`] when a
+    /// logical size is set or on high DPI displays.
+    fn read_pixels(&mut self) -> Result<((u32, u32), Vec<u8>)>;
 
     /// Connect a controller with the given joystick index to start receiving events.
     fn open_controller(&mut self, controller_id: ControllerId) -> Result<()>;
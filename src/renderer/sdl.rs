@@ -2,11 +2,15 @@
 
 use crate::{
     error::{Error, Result},
-    gui::theme::{FontId, FontSrc},
+    gui::{
+        text_layout::GlyphAdvances,
+        theme::{BitmapFont, FontId, FontSrc},
+    },
     prelude::*,
     renderer::{RendererSettings, Rendering},
 };
 use anyhow::{anyhow, Context};
+use glyph::{AtlasKey, GlyphAtlas, GlyphFonts};
 use log::{debug, warn};
 use lru::LruCache;
 use once_cell::sync::Lazy;
@@ -17,15 +21,20 @@ use sdl2::{
     mouse::{Cursor, SystemCursor},
     pixels::{Color as SdlColor, PixelFormatEnum as SdlPixelFormat},
     rect::{Point as SdlPoint, Rect as SdlRect},
-    render::{BlendMode as SdlBlendMode, Canvas, TextureQuery},
+    render::{BlendMode as SdlBlendMode, Canvas},
     rwops::RWops,
+    sys::SDL_ScaleMode as SdlScaleMode,
     ttf::{Font as SdlFont, FontStyle as SdlFontStyle, Sdl2TtfContext},
     video::Window,
     EventPump, GameControllerSubsystem, Sdl,
 };
-use std::{collections::HashMap, fmt};
-use texture::RendererTexture;
-use window::{TextCacheKey, WindowCanvas};
+use std::{
+    cell::{RefCell, RefMut},
+    collections::HashMap,
+    fmt,
+};
+use texture::{RendererTexture, TargetState};
+use window::WindowCanvas;
 
 #[allow(clippy::expect_used)]
 static TTF: Lazy<Sdl2TtfContext> = Lazy::new(|| sdl2::ttf::init().expect("sdl2_ttf initialized"));
@@ -34,6 +43,7 @@ pub use audio::{AudioDevice, AudioFormatNum};
 
 pub mod audio;
 mod event;
+mod glyph;
 mod texture;
 mod window;
 
@@ -48,16 +58,22 @@ pub(crate) struct Renderer {
     settings: RendererSettings,
     cursor: Option<Cursor>,
     blend_mode: SdlBlendMode,
+    target_state: TargetState,
     current_font: FontId,
+    current_faces: Vec<FontId>,
     font_size: u16,
     font_style: SdlFontStyle,
+    text_align: TextAlign,
     primary_window_id: WindowId,
     window_target: WindowId,
     texture_target: Option<TextureId>,
+    target_stack: Vec<(Option<TextureId>, TargetState)>,
     windows: HashMap<WindowId, WindowCanvas>,
     next_texture_id: usize,
     font_data: LruCache<FontId, Font>,
     loaded_fonts: LruCache<(FontId, u16), SdlFont<'static, 'static>>,
+    bitmap_fonts: LruCache<FontId, BitmapFont>,
+    glyph_advances: RefCell<LruCache<AtlasKey, GlyphAdvances>>,
 }
 
 impl Renderer {
@@ -67,6 +83,7 @@ impl Renderer {
         F: FnOnce(&mut Canvas<Window>) -> Result<()>,
     {
         if let Some(texture_id) = self.texture_target {
+            let target_state = self.target_state;
             let window = self
                 .windows
                 .values_mut()
@@ -81,7 +98,7 @@ impl Renderer {
                 window
                     .canvas
                     .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
-                        result = f(canvas);
+                        result = target_state.apply(canvas).and_then(|()| f(canvas));
                     })
                     .with_context(|| format!("failed to update texture target {texture_id}"))?;
                 result
@@ -93,49 +110,95 @@ impl Renderer {
         }
     }
 
-    /// Load font if family or size has not already been loaded. Returns `true` if a font was
-    /// loaded.
+    /// Set the current font family, storing the data for each of its faces.
+    fn set_font(&mut self, font: &Font) {
+        self.current_font = font.id();
+        self.current_faces = font.faces().map(Font::face_id).collect();
+        for face in font.faces() {
+            let face_id = face.face_id();
+            if !self.font_data.contains(&face_id) {
+                self.font_data.put(face_id, face.clone());
+            }
+        }
+    }
+
+    /// Load each face of the current font family if it has not already been loaded at the current
+    /// size. Returns `true` if a font was loaded.
     fn load_font(&mut self) -> Result<bool> {
-        let key = (self.current_font, self.font_size);
-        if self.loaded_fonts.contains(&key) {
-            return Ok(false);
+        let mut loaded = false;
+        for face_id in self.current_faces.clone() {
+            let key = (face_id, self.font_size);
+            if self.loaded_fonts.contains(&key) || self.bitmap_fonts.contains(&face_id) {
+                continue;
+            }
+
+            let font_data = self
+                .font_data
+                .get(&face_id)
+                .ok_or_else(|| anyhow!("invalid current font"))?;
+            let loaded_font = match font_data.source() {
+                FontSrc::None => return Err(anyhow!("Must provide a font data source")),
+                FontSrc::Bytes(bytes) => {
+                    let rwops = RWops::from_bytes(bytes).map_err(Error::Renderer)?;
+                    TTF.load_font_from_rwops(rwops, self.font_size)
+                        .map_err(Error::Renderer)?
+                }
+                FontSrc::Path(ref path) => TTF
+                    .load_font(path, self.font_size)
+                    .map_err(Error::Renderer)?,
+                FontSrc::Bitmap(ref src) => {
+                    // Bitmap fonts are scaled when rendered, so only need to be loaded once
+                    let bitmap_font = BitmapFont::load(src)?;
+                    self.bitmap_fonts.put(face_id, bitmap_font);
+                    loaded = true;
+                    continue;
+                }
+            };
+            self.loaded_fonts.put(key, loaded_font);
+            loaded = true;
         }
+        self.sync_font_style();
+        Ok(loaded)
+    }
 
-        let font_data = self
-            .font_data
-            .get(&self.current_font)
-            .ok_or_else(|| anyhow!("invalid current font"))?;
-        let loaded_font = match font_data.source() {
-            FontSrc::None => return Err(anyhow!("Must provide a font data source")),
-            FontSrc::Bytes(bytes) => {
-                let rwops = RWops::from_bytes(bytes).map_err(Error::Renderer)?;
-                TTF.load_font_from_rwops(rwops, self.font_size)
-                    .map_err(Error::Renderer)?
+    /// Apply the current font style to each TTF face of the current font family. Bitmap fonts have
+    /// no styles.
+    fn sync_font_style(&mut self) {
+        for &face_id in &self.current_faces {
+            if let Some(font) = self.loaded_fonts.get_mut(&(face_id, self.font_size)) {
+                if font.get_style() != self.font_style {
+                    font.set_style(self.font_style);
+                }
             }
-            FontSrc::Path(ref path) => TTF
-                .load_font(path, self.font_size)
-                .map_err(Error::Renderer)?,
-        };
-        self.loaded_fonts.put(key, loaded_font);
-        Ok(true)
+        }
     }
 
-    /// Returns a reference to the current SDL font.
-    #[inline]
-    fn font(&self) -> &SdlFont<'static, 'static> {
-        #[allow(clippy::expect_used)]
-        self.loaded_fonts
-            .peek(&(self.current_font, self.font_size))
-            .expect("valid font")
+    /// Returns the cached glyph advances for the current font family, size, style and outline.
+    fn glyph_advances(&self) -> RefMut<'_, GlyphAdvances> {
+        let outline = self
+            .current_faces
+            .first()
+            .and_then(|&face_id| self.loaded_fonts.peek(&(face_id, self.font_size)))
+            .map_or(0, SdlFont::get_outline_width);
+        let key = AtlasKey {
+            font_id: self.current_font,
+            size: self.font_size,
+            style: self.font_style.bits(),
+            outline,
+        };
+        RefMut::map(self.glyph_advances.borrow_mut(), |cache| {
+            cache.get_or_insert_mut(key, GlyphAdvances::default)
+        })
     }
 
-    /// Returns a mutable reference the current SDL font.
-    #[inline]
-    fn font_mut(&mut self) -> &mut SdlFont<'static, 'static> {
-        #[allow(clippy::expect_used)]
-        self.loaded_fonts
-            .get_mut(&(self.current_font, self.font_size))
-            .expect("valid font")
+    /// Returns the faces of the current font family.
+    fn glyph_fonts(&self) -> Result<GlyphFonts<'_>> {
+        GlyphFonts::current(
+            &self.bitmap_fonts,
+            &self.loaded_fonts,
+            &self.current_faces,
+            self.font_size,
+        )
     }
 }
 
@@ -175,12 +238,8 @@ impl Rendering for Renderer {
         debug!("Loaded AudioDevice: {:?}", audio_device.spec());
         let controller_subsys = context.game_controller().map_err(Error::Renderer)?;
 
-        let default_font = Font::default();
-        let current_font = default_font.id();
-        let mut font_data = LruCache::new(s.text_cache_size);
-        font_data.put(current_font, default_font);
-
         let texture_cache_size = s.texture_cache_size;
+        let text_cache_size = s.text_cache_size;
         let mut renderer = Self {
             context,
             event_pump,
@@ -191,17 +250,24 @@ impl Rendering for Renderer {
             title,
             cursor,
             blend_mode: SdlBlendMode::None,
-            current_font,
+            target_state: TargetState::default(),
+            current_font: 0,
+            current_faces: Vec::new(),
             font_size: 14,
             font_style: SdlFontStyle::NORMAL,
+            text_align: TextAlign::Left,
             primary_window_id: window_target,
             window_target,
             texture_target: None,
+            target_stack: Vec::new(),
             windows,
             next_texture_id: 0,
-            font_data,
+            font_data: LruCache::new(text_cache_size),
             loaded_fonts: LruCache::new(texture_cache_size),
+            bitmap_fonts: LruCache::new(text_cache_size),
+            glyph_advances: RefCell::new(LruCache::new(text_cache_size)),
         };
+        renderer.set_font(&Font::default());
         renderer.load_font()?;
 
         Ok(renderer)
@@ -228,6 +294,7 @@ impl Rendering for Renderer {
     /// Sets the clip rect used by the renderer to draw to the current canvas.
     #[inline]
     fn clip(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
+        self.target_state.clip = rect;
         self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
             canvas.set_clip_rect(rect.map(Into::into));
             Ok(())
@@ -245,6 +312,9 @@ impl Rendering for Renderer {
     fn present(&mut self) {
         for window in self.windows.values_mut() {
             window.canvas.present();
+            if let Err(err) = window.update_logical_size() {
+                log::warn!("failed to update logical size: {err}");
+            }
         }
     }
 
@@ -252,6 +322,10 @@ impl Rendering for Renderer {
     /// factors before being drawn to the canvas.
     #[inline]
     fn scale(&mut self, x: f32, y: f32) -> Result<()> {
+        self.target_state.scale = (x, y);
+        if self.texture_target.is_none() {
+            return self.window_canvas_mut()?.set_scale(x, y);
+        }
         self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
             Ok(canvas.set_scale(x, y).map_err(Error::Renderer)?)
         })
@@ -268,24 +342,30 @@ impl Rendering for Renderer {
     /// Set the font style for drawing to the current canvas.
     #[inline]
     fn font_style(&mut self, style: FontStyle) {
-        let style = style.into();
-        if self.font_style != style {
-            self.font_style = style;
-            self.font_mut().set_style(style);
-        }
+        self.font_style = style.into();
+        self.sync_font_style();
     }
 
     /// Set the font family for drawing to the current canvas.
     #[inline]
     fn font_family(&mut self, font: &Font) -> Result<()> {
-        self.current_font = font.id();
-        if !self.font_data.contains(&self.current_font) {
-            self.font_data.put(self.current_font, font.clone());
-        }
+        self.set_font(font);
         self.load_font()?;
         Ok(())
     }
 
+    /// Set the horizontal alignment of each line of text drawn to the current canvas.
+    #[inline]
+    fn text_align(&mut self, align: TextAlign) {
+        self.text_align = align;
+    }
+
+    /// Returns the distance from the top of a line of text to its baseline using the current font.
+    #[inline]
+    fn font_ascent(&self) -> Result<u32> {
+        Ok(self.glyph_fonts()?.ascent())
+    }
+
     /// Draw text to the current canvas.
     #[inline]
     fn text(
@@ -303,61 +383,90 @@ impl Rendering for Renderer {
             return self.size_of(text, wrap_width);
         }
         if let Some(fill) = fill {
+            for &face_id in &self.current_faces {
+                if let Some(font) = self.loaded_fonts.get_mut(&(face_id, self.font_size)) {
+                    if font.get_outline_width() != outline {
+                        font.set_outline_width(outline);
+                    }
+                }
+            }
+            // Borrow font fields separately from the window below
+            let font = GlyphFonts::current(
+                &self.bitmap_fonts,
+                &self.loaded_fonts,
+                &self.current_faces,
+                self.font_size,
+            )?;
+            let layout = font.layout(
+                text,
+                wrap_width,
+                self.text_align,
+                &mut self.glyph_advances(),
+            )?;
+            let (width, height) = layout.size;
+
+            let target_state = self.target_state;
             let window = self
                 .windows
                 .get_mut(&self.window_target)
                 .ok_or(Error::InvalidWindow(self.window_target))?;
 
-            let texture = {
-                // FIXME: Use default or return error
-                let font = self
-                    .loaded_fonts
-                    .get_mut(&(self.current_font, self.font_size))
-                    .ok_or_else(|| anyhow!("invalid current font"))?;
-                if font.get_outline_width() != outline {
-                    font.set_outline_width(outline);
-                }
-
-                let key = TextCacheKey::new(text, self.current_font, fill, self.font_size);
-                if !window.text_cache.contains(&key) {
-                    let surface = wrap_width
-                        .map_or_else(
-                            || font.render(text).blended(fill),
-                            |width| font.render(text).blended_wrapped(fill, width),
-                        )
-                        .context("invalid text")?;
-                    window.text_cache.put(
-                        key,
-                        RendererTexture::new(
-                            window
-                                .canvas
-                                .create_texture_from_surface(surface)
-                                .context("failed to create text surface")?,
-                        ),
-                    );
-                }
-
-                // SAFETY: We just checked or inserted a texture.
-                #[allow(clippy::expect_used)]
-                window.text_cache.get_mut(&key).expect("valid text cache")
+            let key = AtlasKey {
+                font_id: self.current_font,
+                size: self.font_size,
+                style: self.font_style.bits(),
+                outline,
             };
+            if !window.glyph_atlases.contains(&key) {
+                let atlas = GlyphAtlas::new(&window.canvas)?;
+                window.glyph_atlases.put(key, atlas);
+            }
+            // SAFETY: We just checked or inserted an atlas.
+            #[allow(clippy::expect_used)]
+            let atlas = window
+                .glyph_atlases
+                .get_mut(&key)
+                .expect("valid glyph atlas");
+            atlas.pack(&window.canvas, &layout.chars(), |c| font.rasterize(c))?;
+
+            // Each glyph is flipped and rotated about the center of the whole text
+            let horizontal = matches!(flipped, Some(Flipped::Horizontal | Flipped::Both));
+            let vertical = matches!(flipped, Some(Flipped::Vertical | Flipped::Both));
+            let center = center.unwrap_or_else(|| point![width as i32 / 2, height as i32 / 2]);
+            let copies: Vec<(SdlRect, SdlRect, SdlPoint)> = layout
+                .glyphs
+                .iter()
+                .filter_map(|&(c, p)| {
+                    let src = atlas.get(c).filter(|src| src.width() > 0)?;
+                    let x = if horizontal {
+                        width as i32 - p.x() - src.width()
+                    } else {
+                        p.x()
+                    };
+                    let y = if vertical {
+                        height as i32 - p.y() - src.height()
+                    } else {
+                        p.y()
+                    };
+                    let dst = rect![pos.x() + x, pos.y() + y, src.width(), src.height()];
+                    let center = SdlPoint::new(center.x() - x, center.y() - y);
+                    Some((src.into(), dst.into(), center))
+                })
+                .collect();
 
-            let TextureQuery {
-                width, mut height, ..
-            } = texture.query();
+            let texture = atlas.texture_mut();
+            texture.apply_mods(Some(fill), SdlBlendMode::Blend);
             let update = |canvas: &mut Canvas<_>| -> Result<()> {
-                let src = None;
-                let dst = Some(SdlRect::new(pos.x(), pos.y(), width, height));
-                let result = if angle.is_some() || center.is_some() || flipped.is_some() {
-                    let angle = angle.unwrap_or(0.0);
-                    let center = center.map(Into::into);
-                    let horizontal = matches!(flipped, Some(Flipped::Horizontal | Flipped::Both));
-                    let vertical = matches!(flipped, Some(Flipped::Vertical | Flipped::Both));
-                    canvas.copy_ex(texture, src, dst, angle, center, horizontal, vertical)
-                } else {
-                    canvas.copy(texture, src, dst)
-                };
-                Ok(result.map_err(Error::Renderer)?)
+                for &(src, dst, center) in &copies {
+                    if angle.is_some() || flipped.is_some() {
+                        let angle = angle.unwrap_or(0.0);
+                        canvas.copy_ex(texture, src, dst, angle, center, horizontal, vertical)
+                    } else {
+                        canvas.copy(texture, src, dst)
+                    }
+                    .map_err(Error::Renderer)?;
+                }
+                Ok(())
             };
 
             if let Some(texture_id) = self.texture_target {
@@ -366,7 +475,7 @@ impl Rendering for Renderer {
                     window
                         .canvas
                         .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
-                            result = update(canvas);
+                            result = target_state.apply(canvas).and_then(|()| update(canvas));
                         })
                         .with_context(|| format!("failed to update texture target {texture_id}"))?;
                     result?;
@@ -376,13 +485,6 @@ impl Rendering for Renderer {
             } else {
                 update(&mut window.canvas)?;
             }
-            if text.ends_with('\n') {
-                let font = self
-                    .loaded_fonts
-                    .get_mut(&(self.current_font, self.font_size))
-                    .ok_or_else(|| anyhow!("invalid current font"))?;
-                height += font.height() as u32;
-            }
             Ok((width, height))
         } else {
             self.size_of(text, wrap_width)
@@ -421,24 +523,25 @@ impl Rendering for Renderer {
     /// as `(width, height)`.
     #[inline]
     fn size_of(&self, text: &str, wrap_width: Option<u32>) -> Result<(u32, u32)> {
-        let font = self.font();
-        if text.is_empty() {
-            return Ok((0, font.height() as u32));
-        }
-        let (width, mut height) = if let Some(width) = wrap_width {
-            let (width, height) = font
-                .render(text)
-                .blended_wrapped(Color::BLACK, width)
-                .context("invalid text")?
-                .size();
-            (width, height)
-        } else {
-            font.size_of(text)?
-        };
-        if text.ends_with('\n') {
-            height += font.height() as u32;
-        }
-        Ok((width, height))
+        let font = self.glyph_fonts()?;
+        let layout = font.text_layout(
+            text,
+            wrap_width,
+            TextAlign::Left,
+            &mut self.glyph_advances(),
+        )?;
+        Ok(layout.size())
+    }
+
+    /// Lay out text using the current font, wrapping lines to `wrap_width` if provided.
+    #[inline]
+    fn layout_text(&self, text: &str, wrap_width: Option<u32>) -> Result<TextLayout> {
+        self.glyph_fonts()?.text_layout(
+            text,
+            wrap_width,
+            self.text_align,
+            &mut self.glyph_advances(),
+        )
     }
 
     /// Draw a pixel to the current canvas.
@@ -726,12 +829,13 @@ impl Rendering for Renderer {
         flipped: Option<Flipped>,
         tint: Option<Color>,
     ) -> Result<()> {
+        let target_state = self.target_state;
         let window = self
             .windows
             .get_mut(&self.window_target)
             .ok_or(Error::InvalidWindow(self.window_target))?;
         let texture = {
-            let key: *const Image = img;
+            let key = img.id();
             if !window.image_cache.contains(&key) {
                 window.image_cache.put(
                     key,
@@ -751,10 +855,7 @@ impl Rendering for Renderer {
             #[allow(clippy::expect_used)]
             window.image_cache.get_mut(&key).expect("valid image cache")
         };
-        let [r, g, b, a] = tint.map_or([255; 4], |t| t.channels());
-        texture.set_color_mod(r, g, b);
-        texture.set_alpha_mod(a);
-        texture.set_blend_mode(self.blend_mode);
+        texture.apply_mods(tint, self.blend_mode);
         texture
             .update(
                 None,
@@ -784,7 +885,7 @@ impl Rendering for Renderer {
                 window
                     .canvas
                     .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
-                        result = update(canvas);
+                        result = target_state.apply(canvas).and_then(|()| update(canvas));
                     })
                     .with_context(|| format!("failed to update texture target {texture_id}"))?;
                 result?;
@@ -798,9 +899,18 @@ impl Rendering for Renderer {
         Ok(())
     }
 
-    /// Return the current rendered target pixels as an array of bytes.
+    /// Return the current rendered target pixels as an array of RGBA bytes, along with the
+    /// `(width, height)` of the pixels read.
     #[inline]
-    fn to_bytes(&mut self) -> Result<Vec<u8>> {
+    fn read_pixels(&mut self) -> Result<((u32, u32), Vec<u8>)> {
+        // Reading `None` returns the full output size, in pixels
+        let read = |canvas: &Canvas<_>| -> Result<((u32, u32), Vec<u8>)> {
+            let dimensions = canvas.output_size().map_err(Error::Renderer)?;
+            let bytes = canvas
+                .read_pixels(None, SdlPixelFormat::RGBA32)
+                .map_err(Error::Renderer)?;
+            Ok((dimensions, bytes))
+        };
         if let Some(texture_id) = self.texture_target {
             let window = self
                 .windows
@@ -812,22 +922,19 @@ impl Rendering for Renderer {
                     .textures
                     .get(&texture_id)
                     .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?;
-                let mut result = Ok(vec![]);
+                let mut result = Ok(((0, 0), vec![]));
                 window
                     .canvas
                     .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
-                        result = canvas.read_pixels(None, SdlPixelFormat::RGBA32);
+                        result = read(canvas);
                     })
                     .with_context(|| format!("failed to read texture target {texture_id}"))?;
-                Ok(result.map_err(Error::Renderer)?)
+                result
             } else {
                 Err(Error::InvalidTexture(texture_id).into())
             }
         } else {
-            Ok(self
-                .canvas()?
-                .read_pixels(None, SdlPixelFormat::RGBA32)
-                .map_err(Error::Renderer)?)
+            read(self.canvas()?)
         }
     }
 
@@ -864,18 +971,27 @@ impl fmt::Debug for Renderer {
             .field("title", &self.title)
             .field("settings", &self.settings)
             .field("blend_mode", &self.blend_mode)
+            .field("target_state", &self.target_state)
             .field(
                 "current_font",
-                &self.font_data.peek(&self.current_font).map(Font::name),
+                &self
+                    .current_faces
+                    .iter()
+                    .filter_map(|face_id| self.font_data.peek(face_id).map(Font::name))
+                    .collect::<Vec<_>>(),
             )
             .field("font_size", &self.font_size)
             .field("font_style", &self.font_style)
+            .field("text_align", &self.text_align)
             .field("window_target", &self.texture_target)
             .field("texture_target", &self.texture_target)
+            .field("target_stack", &self.target_stack)
             .field("windows", &self.windows)
             .field("next_texture_id", &self.next_texture_id)
             .field("font_data", &self.font_data)
             .field("loaded_fonts", &self.loaded_fonts)
+            .field("bitmap_fonts", &self.bitmap_fonts)
+            .field("glyph_advances", &self.glyph_advances)
             .finish_non_exhaustive()
     }
 }
@@ -979,6 +1095,18 @@ impl From<BlendMode> for SdlBlendMode {
     }
 }
 
+#[doc(hidden)]
+impl From<ScaleQuality> for SdlScaleMode {
+    /// Convert [`ScaleQuality`] to [`SdlScaleMode`].
+    fn from(quality: ScaleQuality) -> Self {
+        match quality {
+            ScaleQuality::Nearest => Self::SDL_ScaleModeNearest,
+            ScaleQuality::Linear => Self::SDL_ScaleModeLinear,
+            ScaleQuality::Best => Self::SDL_ScaleModeBest,
+        }
+    }
+}
+
 #[doc(hidden)]
 impl From<PixelFormat> for SdlPixelFormat {
     /// Convert [`PixelFormat`] to [`SdlPixelFormat`].
@@ -0,0 +1,425 @@
+//! Glyph atlas text layout and rendering.
+
+use super::{texture::RendererTexture, FontId};
+use crate::{
+    error::{Error, Result},
+    gui::{
+        text_layout::{FontMetrics, GlyphAdvances, TextLayout},
+        theme::BitmapFont,
+    },
+    prelude::*,
+};
+use anyhow::{anyhow, Context};
+use lru::LruCache;
+use sdl2::{
+    pixels::PixelFormatEnum as SdlPixelFormat,
+    render::{BlendMode as SdlBlendMode, Canvas},
+    ttf::Font as SdlFont,
+    video::Window,
+};
+use std::{collections::HashMap, fmt};
+
+/// Initial width and height of a glyph atlas texture.
+const ATLAS_SIZE: u32 = 512;
+/// Maximum width and height a glyph atlas texture can grow to.
+const MAX_ATLAS_SIZE: u32 = 4096;
+/// Spacing between packed glyphs to avoid sampling neighboring glyphs when scaled.
+const GLYPH_PADDING: u32 = 1;
+
+/// Identifies a glyph atlas by the font properties that affect glyph rasterization.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(super) struct AtlasKey {
+    pub(super) font_id: FontId,
+    pub(super) size: u16,
+    pub(super) style: i32,
+    pub(super) outline: u16,
+}
+
+/// A texture of white glyphs packed into rows, drawn using color modulation.
+pub(super) struct GlyphAtlas {
+    texture: RendererTexture,
+    size: u32,
+    glyphs: HashMap<char, Rect<i32>>,
+    cursor: (u32, u32),
+    row_height: u32,
+}
+
+impl GlyphAtlas {
+    /// Create an empty glyph atlas.
+    pub(super) fn new(canvas: &Canvas<Window>) -> Result<Self> {
+        Self::with_size(canvas, ATLAS_SIZE)
+    }
+
+    /// Create an empty glyph atlas with the given texture `size`.
+    fn with_size(canvas: &Canvas<Window>, size: u32) -> Result<Self> {
+        let mut texture = canvas
+            .create_texture_static(Some(SdlPixelFormat::RGBA32), size, size)
+            .context("failed to create glyph atlas texture")?;
+        texture.set_blend_mode(SdlBlendMode::Blend);
+        Ok(Self {
+            texture: RendererTexture::new(texture),
+            size,
+            glyphs: HashMap::new(),
+            cursor: (0, 0),
+            row_height: 0,
+        })
+    }
+
+    /// Returns the atlas region for a character, if it has been packed.
+    #[inline]
+    pub(super) fn get(&self, c: char) -> Option<Rect<i32>> {
+        self.glyphs.get(&c).copied()
+    }
+
+    /// Returns the atlas texture.
+    #[inline]
+    pub(super) fn texture_mut(&mut self) -> &mut RendererTexture {
+        &mut self.texture
+    }
+
+    /// Ensure all `chars` are packed into the atlas, rasterizing missing glyphs with `rasterize`.
+    /// When the atlas is full, it's cleared and `chars` are packed again if it contains any glyphs
+    /// not in `chars`, or otherwise grown.
+    pub(super) fn pack<F>(
+        &mut self,
+        canvas: &Canvas<Window>,
+        chars: &[char],
+        mut rasterize: F,
+    ) -> Result<()>
+    where
+        F: FnMut(char) -> Result<Option<Image>>,
+    {
+        loop {
+            let mut full = false;
+            for &c in chars {
+                if self.glyphs.contains_key(&c) {
+                    continue;
+                }
+                let image = rasterize(c)?.unwrap_or_default();
+                if !self.insert(c, &image)? {
+                    full = true;
+                    break;
+                }
+            }
+            if !full {
+                return Ok(());
+            }
+            if self.glyphs.keys().any(|c| !chars.contains(c)) {
+                self.clear();
+            } else if self.size < MAX_ATLAS_SIZE {
+                *self = Self::with_size(canvas, 2 * self.size)?;
+            } else {
+                return Err(anyhow!("text has too many glyphs to fit in a glyph atlas"));
+            }
+        }
+    }
+
+    /// Pack and upload a glyph image into the next free space. Returns `false` if there's no room
+    /// left. Empty images are stored as empty regions so they aren't rasterized again.
+    fn insert(&mut self, c: char, image: &Image) -> Result<bool> {
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            self.glyphs.insert(c, rect![0, 0, 0, 0]);
+            return Ok(true);
+        }
+        if self.cursor.0 + width > self.size {
+            self.cursor = (0, self.cursor.1 + self.row_height + GLYPH_PADDING);
+            self.row_height = 0;
+        }
+        let (x, y) = self.cursor;
+        if x + width > self.size || y + height > self.size {
+            return Ok(false);
+        }
+        let rect = rect![x as i32, y as i32, width as i32, height as i32];
+        self.texture
+            .update(
+                Some(rect.into()),
+                image.as_bytes(),
+                image.format().channels() * width as usize,
+            )
+            .context("failed to update glyph atlas texture")?;
+        self.glyphs.insert(c, rect);
+        self.cursor.0 += width + GLYPH_PADDING;
+        self.row_height = self.row_height.max(height);
+        Ok(true)
+    }
+
+    /// Remove all packed glyphs.
+    fn clear(&mut self) {
+        self.glyphs.clear();
+        self.cursor = (0, 0);
+        self.row_height = 0;
+    }
+}
+
+impl fmt::Debug for GlyphAtlas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlyphAtlas")
+            .field("size", &self.size)
+            .field("glyph_count", &self.glyphs.len())
+            .field("cursor", &self.cursor)
+            .field("row_height", &self.row_height)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A font face used to lay out and rasterize glyphs.
+#[derive(Copy, Clone)]
+enum GlyphFont<'a> {
+    /// A TTF font loaded at the current font size.
+    Ttf(&'a SdlFont<'static, 'static>),
+    /// A bitmap font along with the current font size.
+    Bitmap(&'a BitmapFont, u32),
+}
+
+impl<'a> GlyphFont<'a> {
+    /// Returns a loaded font face, preferring a loaded bitmap font.
+    fn loaded(
+        bitmap_fonts: &'a LruCache<FontId, BitmapFont>,
+        loaded_fonts: &'a LruCache<(FontId, u16), SdlFont<'static, 'static>>,
+        face_id: FontId,
+        size: u16,
+    ) -> Result<Self> {
+        if let Some(font) = bitmap_fonts.peek(&face_id) {
+            Ok(Self::Bitmap(font, u32::from(size)))
+        } else {
+            loaded_fonts
+                .peek(&(face_id, size))
+                .map(Self::Ttf)
+                .ok_or_else(|| anyhow!("invalid current font"))
+        }
+    }
+
+    /// Returns whether the face has a glyph for a character.
+    fn has_glyph(self, c: char) -> bool {
+        match self {
+            Self::Ttf(font) => font.find_glyph(c).is_some(),
+            Self::Bitmap(font, _) => font.has_glyph(c),
+        }
+    }
+
+    /// Returns the rendered width of a single line of text.
+    fn width(self, text: &str) -> Result<u32> {
+        match self {
+            Self::Ttf(_) if text.is_empty() => Ok(0),
+            Self::Ttf(font) => Ok(font.size_of(text)?.0),
+            Self::Bitmap(font, size) => Ok(font.size_of(text, None, size).0),
+        }
+    }
+
+    /// Returns the height of a line.
+    fn height(self) -> u32 {
+        match self {
+            Self::Ttf(font) => font.height().max(0) as u32,
+            Self::Bitmap(font, size) => font.height(size),
+        }
+    }
+
+    /// Returns the distance between the tops of consecutive lines.
+    fn line_skip(self) -> i32 {
+        match self {
+            Self::Ttf(font) => font.recommended_line_spacing(),
+            Self::Bitmap(font, size) => font.height(size) as i32,
+        }
+    }
+
+    /// Returns the distance from the top of a line to its baseline.
+    fn ascent(self) -> u32 {
+        match self {
+            Self::Ttf(font) => font.ascent().max(0) as u32,
+            Self::Bitmap(font, size) => font.ascent(size),
+        }
+    }
+
+    /// Returns the offset of the rasterized glyph for a character from its pen position.
+    fn offset(self, c: char) -> Point<i32> {
+        match self {
+            Self::Ttf(_) => point![0, 0],
+            Self::Bitmap(font, size) => font.glyph_offset(c, size),
+        }
+    }
+
+    /// Rasterize a single glyph as a white RGBA [Image], using alpha for coverage.
+    fn rasterize(self, c: char) -> Result<Option<Image>> {
+        match self {
+            Self::Ttf(font) => {
+                let surface = font
+                    .render_char(c)
+                    .blended(Color::WHITE)
+                    .context("failed to render glyph")?
+                    .convert_format(SdlPixelFormat::RGBA32)
+                    .map_err(Error::Renderer)?;
+                let (width, height) = surface.size();
+                let pitch = surface.pitch() as usize;
+                let row_size = 4 * width as usize;
+                let bytes = surface.with_lock(|pixels| {
+                    pixels
+                        .chunks(pitch)
+                        .take(height as usize)
+                        .flat_map(|row| &row[..row_size])
+                        .copied()
+                        .collect::<Vec<u8>>()
+                });
+                Ok(Some(Image::from_vec(
+                    width,
+                    height,
+                    bytes,
+                    PixelFormat::Rgba,
+                )))
+            }
+            Self::Bitmap(font, size) => Ok(font.glyph_image(c, size)),
+        }
+    }
+}
+
+/// The current font face along with its fallback faces, used to lay out and rasterize glyphs.
+/// Each character is drawn with the first face that has a glyph for it.
+pub(super) struct GlyphFonts<'a> {
+    primary: GlyphFont<'a>,
+    fallbacks: Vec<GlyphFont<'a>>,
+}
+
+impl<'a> GlyphFonts<'a> {
+    /// Returns the loaded faces for the current font.
+    pub(super) fn current(
+        bitmap_fonts: &'a LruCache<FontId, BitmapFont>,
+        loaded_fonts: &'a LruCache<(FontId, u16), SdlFont<'static, 'static>>,
+        face_ids: &[FontId],
+        size: u16,
+    ) -> Result<Self> {
+        let mut faces = face_ids
+            .iter()
+            .map(|&face_id| GlyphFont::loaded(bitmap_fonts, loaded_fonts, face_id, size));
+        let primary = faces
+            .next()
+            .ok_or_else(|| anyhow!("invalid current font"))??;
+        Ok(Self {
+            primary,
+            fallbacks: faces.collect::<Result<_>>()?,
+        })
+    }
+
+    /// Returns the index and face used to draw a character, where `0` is the primary face.
+    fn face(&self, c: char) -> (usize, GlyphFont<'a>) {
+        if self.fallbacks.is_empty() || self.primary.has_glyph(c) {
+            return (0, self.primary);
+        }
+        self.fallbacks
+            .iter()
+            .enumerate()
+            .find(|(_, face)| face.has_glyph(c))
+            .map_or((0, self.primary), |(idx, &face)| (idx + 1, face))
+    }
+
+    /// Returns the rendered width of a single line of text, measuring runs of characters drawn
+    /// with the same face together.
+    fn width(&self, text: &str) -> Result<u32> {
+        if self.fallbacks.is_empty() {
+            return self.primary.width(text);
+        }
+        let mut width = 0;
+        let mut start = 0;
+        let mut current: Option<(usize, GlyphFont<'_>)> = None;
+        for (idx, c) in text.char_indices() {
+            let (face_idx, face) = self.face(c);
+            match current {
+                Some((current_idx, _)) if current_idx == face_idx => (),
+                Some((_, current_face)) => {
+                    width += current_face.width(&text[start..idx])?;
+                    start = idx;
+                    current = Some((face_idx, face));
+                }
+                None => current = Some((face_idx, face)),
+            }
+        }
+        if let Some((_, face)) = current {
+            width += face.width(&text[start..])?;
+        }
+        Ok(width)
+    }
+
+    /// Lay out text, wrapping lines to `wrap_width` if provided and aligning each line within the
+    /// width of the widest line. Glyphs drawn with fallback faces share the baseline of the
+    /// primary face. Character advances are cached in `advances`.
+    pub(super) fn text_layout(
+        &self,
+        text: &str,
+        wrap_width: Option<u32>,
+        align: TextAlign,
+        advances: &mut GlyphAdvances,
+    ) -> Result<TextLayout> {
+        let metrics = FontMetrics {
+            height: self.primary.height(),
+            line_skip: self.primary.line_skip(),
+            ascent: self.primary.ascent(),
+        };
+        TextLayout::new(text, wrap_width, align, metrics, advances, |line| {
+            self.width(line)
+        })
+    }
+
+    /// Lay out the glyph images of text, wrapping lines to `wrap_width` if provided and aligning
+    /// each line within the width of the widest line. Character advances are cached in
+    /// `advances`.
+    pub(super) fn layout(
+        &self,
+        text: &str,
+        wrap_width: Option<u32>,
+        align: TextAlign,
+        advances: &mut GlyphAdvances,
+    ) -> Result<GlyphLayout> {
+        let layout = self.text_layout(text, wrap_width, align, advances)?;
+        let ascent = self.primary.ascent() as i32;
+        let glyphs = layout
+            .glyphs()
+            .iter()
+            .map(|glyph| {
+                let c = glyph.char();
+                let face = self.face(c).1;
+                let offset = face.offset(c);
+                let baseline = ascent - face.ascent() as i32;
+                let rect = glyph.rect();
+                (
+                    c,
+                    point![rect.x() + offset.x(), rect.y() + baseline + offset.y()],
+                )
+            })
+            .collect();
+        Ok(GlyphLayout {
+            glyphs,
+            size: layout.size(),
+        })
+    }
+
+    /// Returns the distance from the top of a line to its baseline.
+    pub(super) fn ascent(&self) -> u32 {
+        self.primary.ascent()
+    }
+
+    /// Rasterize a single glyph as a white RGBA [Image] using the face that has a glyph for it.
+    pub(super) fn rasterize(&self, c: char) -> Result<Option<Image>> {
+        self.face(c).1.rasterize(c)
+    }
+}
+
+/// Glyph image positions of laid out text, relative to the top-left of the text.
+#[derive(Debug, Clone)]
+pub(super) struct GlyphLayout {
+    pub(super) glyphs: Vec<(char, Point<i32>)>,
+    pub(super) size: (u32, u32),
+}
+
+impl GlyphLayout {
+    /// Returns the unique characters in the layout that have visible glyphs.
+    pub(super) fn chars(&self) -> Vec<char> {
+        let mut chars: Vec<char> = self
+            .glyphs
+            .iter()
+            .map(|&(c, _)| c)
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        chars.sort_unstable();
+        chars.dedup();
+        chars
+    }
+}
@@ -5,7 +5,10 @@ use crate::{
     renderer::TextureRenderer,
 };
 use anyhow::{anyhow, Context};
-use sdl2::render::{Canvas, Texture as SdlTexture};
+use sdl2::{
+    render::{BlendMode as SdlBlendMode, Canvas, RenderTarget, Texture as SdlTexture},
+    sys::{self, SDL_BlendFactor, SDL_BlendOperation},
+};
 use std::{
     cell::RefCell,
     ops::{Deref, DerefMut},
@@ -13,14 +16,34 @@ use std::{
 
 pub(crate) struct RendererTexture {
     inner: Option<SdlTexture>,
+    color_mod: Option<Color>,
+    alpha_mod: Option<u8>,
+    blend_mode: Option<SdlBlendMode>,
 }
 
 impl RendererTexture {
     pub(crate) const fn new(texture: SdlTexture) -> Self {
         Self {
             inner: Some(texture),
+            color_mod: None,
+            alpha_mod: None,
+            blend_mode: None,
         }
     }
+
+    /// Apply color, alpha and blend modulation prior to rendering. Persistent texture settings take
+    /// precedence over the given `tint` and `blend_mode`.
+    pub(crate) fn apply_mods(&mut self, tint: Option<Color>, blend_mode: SdlBlendMode) {
+        let [r, g, b, a] = tint.map_or([255; 4], |t| t.channels());
+        let [r, g, b] = self
+            .color_mod
+            .map_or([r, g, b], |c| [c.red(), c.green(), c.blue()]);
+        let a = self.alpha_mod.unwrap_or(a);
+        let blend_mode = self.blend_mode.unwrap_or(blend_mode);
+        self.set_color_mod(r, g, b);
+        self.set_alpha_mod(a);
+        self.set_blend_mode(blend_mode);
+    }
 }
 
 impl Deref for RendererTexture {
@@ -48,6 +71,55 @@ impl Drop for RendererTexture {
     }
 }
 
+/// Render state of the current texture target which SDL resets each time the target changes, and
+/// so has to be re-applied before every draw.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct TargetState {
+    pub(crate) clip: Option<Rect<i32>>,
+    pub(crate) viewport: Option<Rect<i32>>,
+    pub(crate) scale: (f32, f32),
+}
+
+impl Default for TargetState {
+    fn default() -> Self {
+        Self {
+            clip: None,
+            viewport: None,
+            scale: (1.0, 1.0),
+        }
+    }
+}
+
+impl TargetState {
+    /// Apply the render state to a canvas targeting a texture.
+    pub(crate) fn apply<T: RenderTarget>(&self, canvas: &mut Canvas<T>) -> Result<()> {
+        let (scale_x, scale_y) = self.scale;
+        canvas
+            .set_scale(scale_x, scale_y)
+            .map_err(Error::Renderer)?;
+        canvas.set_viewport(self.viewport.map(Into::into));
+        canvas.set_clip_rect(self.clip.map(Into::into));
+        Ok(())
+    }
+}
+
+impl Renderer {
+    /// Call `f` with a mutable reference to the texture with the given `texture_id`, regardless of
+    /// which window it was created in.
+    fn with_texture_mut<F>(&self, texture_id: TextureId, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut RendererTexture) -> Result<()>,
+    {
+        let texture = self
+            .windows
+            .values()
+            .find_map(|w| w.textures.get(&texture_id))
+            .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?;
+        let mut texture = texture.borrow_mut();
+        f(&mut texture)
+    }
+}
+
 impl TextureRenderer for Renderer {
     /// Create a texture to render to.
     #[inline]
@@ -134,6 +206,7 @@ impl TextureRenderer for Renderer {
         );
 
         let target_texture = self.texture_target;
+        let target_state = self.target_state;
         let window = self
             .windows
             .values_mut()
@@ -144,13 +217,7 @@ impl TextureRenderer for Renderer {
                 .textures
                 .get(&texture_id)
                 .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?;
-            {
-                let mut texture = texture.borrow_mut();
-                let [r, g, b, a] = tint.map_or([255; 4], |t| t.channels());
-                texture.set_color_mod(r, g, b);
-                texture.set_alpha_mod(a);
-                texture.set_blend_mode(self.blend_mode);
-            }
+            texture.borrow_mut().apply_mods(tint, self.blend_mode);
             let src = src.map(Into::into);
             let dst = dst.map(Into::into);
             let update = |canvas: &mut Canvas<_>| -> Result<()> {
@@ -176,7 +243,7 @@ impl TextureRenderer for Renderer {
                     window
                         .canvas
                         .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
-                            result = update(canvas);
+                            result = target_state.apply(canvas).and_then(|()| update(canvas));
                         })
                         .with_context(|| format!("failed to update texture target {texture_id}"))?;
                     result
@@ -191,6 +258,114 @@ impl TextureRenderer for Renderer {
         }
     }
 
+    /// Set the [`ScaleQuality`] used when rendering a `Texture`.
+    #[inline]
+    fn set_texture_scale_quality(
+        &mut self,
+        texture_id: TextureId,
+        quality: ScaleQuality,
+    ) -> Result<()> {
+        self.with_texture_mut(texture_id, |texture| {
+            // SAFETY: `raw` is a valid texture pointer for as long as `texture` is borrowed.
+            let result =
+                unsafe { sdl2::sys::SDL_SetTextureScaleMode(texture.raw(), quality.into()) };
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(Error::Renderer(sdl2::get_error()).into())
+            }
+        })
+    }
+
+    /// Set or clear a persistent color modulation for a `Texture`.
+    #[inline]
+    fn set_texture_color_mod(&mut self, texture_id: TextureId, color: Option<Color>) -> Result<()> {
+        self.with_texture_mut(texture_id, |texture| {
+            texture.color_mod = color;
+            Ok(())
+        })
+    }
+
+    /// Set or clear a persistent alpha modulation for a `Texture`.
+    #[inline]
+    fn set_texture_alpha_mod(&mut self, texture_id: TextureId, alpha: Option<u8>) -> Result<()> {
+        self.with_texture_mut(texture_id, |texture| {
+            texture.alpha_mod = alpha;
+            Ok(())
+        })
+    }
+
+    /// Set or clear a persistent [`BlendMode`] for a `Texture`.
+    #[inline]
+    fn set_texture_blend_mode(
+        &mut self,
+        texture_id: TextureId,
+        mode: Option<BlendMode>,
+    ) -> Result<()> {
+        self.with_texture_mut(texture_id, |texture| {
+            texture.blend_mode = mode.map(Into::into);
+            Ok(())
+        })
+    }
+
+    /// Returns the dimensions of a `Texture` as `(width, height)`.
+    #[inline]
+    fn texture_dimensions(&self, texture_id: TextureId) -> Result<(u32, u32)> {
+        self.windows
+            .values()
+            .find_map(|w| w.textures.get(&texture_id))
+            .map(|texture| {
+                let query = texture.borrow().query();
+                (query.width, query.height)
+            })
+            .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))
+    }
+
+    /// Multiply the alpha channel of a `Texture` by the alpha channel of a `mask` texture.
+    fn mask_texture(&mut self, texture_id: TextureId, mask_id: TextureId) -> Result<()> {
+        let window = self
+            .windows
+            .values_mut()
+            .find(|w| w.textures.contains_key(&texture_id) && w.textures.contains_key(&mask_id))
+            .ok_or_else(|| anyhow!(Error::InvalidTexture(mask_id)))?;
+        // We ensured there are valid textures above
+        let texture = window
+            .textures
+            .get(&texture_id)
+            .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?;
+        let mask = window
+            .textures
+            .get(&mask_id)
+            .ok_or_else(|| anyhow!(Error::InvalidTexture(mask_id)))?;
+
+        // SAFETY: The mask texture is valid, and the composed blend mode is passed straight back
+        // to SDL without being inspected.
+        let result = unsafe {
+            // Keep destination color, multiplying destination alpha by mask alpha
+            let blend_mode = sys::SDL_ComposeCustomBlendMode(
+                SDL_BlendFactor::SDL_BLENDFACTOR_ZERO,
+                SDL_BlendFactor::SDL_BLENDFACTOR_ONE,
+                SDL_BlendOperation::SDL_BLENDOPERATION_ADD,
+                SDL_BlendFactor::SDL_BLENDFACTOR_ZERO,
+                SDL_BlendFactor::SDL_BLENDFACTOR_SRC_ALPHA,
+                SDL_BlendOperation::SDL_BLENDOPERATION_ADD,
+            );
+            sys::SDL_SetTextureBlendMode(mask.borrow().raw(), blend_mode)
+        };
+        if result != 0 {
+            return Err(Error::Renderer(sdl2::get_error()).into());
+        }
+
+        let mut result = Ok(());
+        window
+            .canvas
+            .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
+                result = canvas.copy(&mask.borrow(), None, None);
+            })
+            .with_context(|| format!("failed to mask texture {texture_id}"))?;
+        Ok(result.map_err(Error::Renderer)?)
+    }
+
     /// Returns texture used as the target for drawing operations, if set.
     #[inline]
     fn texture_target(&self) -> Option<TextureId> {
@@ -205,17 +380,42 @@ impl TextureRenderer for Renderer {
     /// If the texture has been dropped or is invalid, then an error is returned.
     #[inline]
     fn set_texture_target(&mut self, id: TextureId) -> Result<()> {
-        self.windows
-            .values()
-            .find(|window| window.textures.contains_key(&id))
-            .map(|_| self.texture_target = Some(id))
-            .ok_or_else(|| anyhow!(Error::InvalidTexture(id)))
+        if !self.windows.values().any(|w| w.textures.contains_key(&id)) {
+            return Err(Error::InvalidTexture(id).into());
+        }
+        self.texture_target = Some(id);
+        self.target_state = TargetState::default();
+        Ok(())
     }
 
-    /// Clear `Texture` target back to the window target canvas for drawing operations.
+    /// Clear the `Texture` target set by `set_texture_target` or the last call to
+    /// `push_texture_target`, restoring the previous render target.
     #[inline]
     fn clear_texture_target(&mut self) {
-        self.texture_target = None;
+        self.pop_texture_target();
+    }
+
+    /// Push the current render target and its clip, viewport and scale to a stack and set a
+    /// `Texture` as the primary target for drawing operations.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    #[inline]
+    fn push_texture_target(&mut self, id: TextureId) -> Result<()> {
+        let previous = (self.texture_target, self.target_state);
+        self.set_texture_target(id)?;
+        self.target_stack.push(previous);
+        Ok(())
+    }
+
+    /// Restore the render target and its clip, viewport and scale saved by the last call to
+    /// `push_texture_target`, or the window target canvas if the stack is empty.
+    #[inline]
+    fn pop_texture_target(&mut self) {
+        let (texture_target, target_state) = self.target_stack.pop().unwrap_or_default();
+        self.texture_target = texture_target;
+        self.target_state = target_state;
     }
 
     /// Returns whether a texture is set as the target for drawing operations.
@@ -228,8 +428,9 @@ impl TextureRenderer for Renderer {
     #[inline]
     fn clear_texture_cache(&mut self) {
         self.loaded_fonts.clear();
+        self.glyph_advances.borrow_mut().clear();
         for window_canvas in self.windows.values_mut() {
-            window_canvas.text_cache.clear();
+            window_canvas.glyph_atlases.clear();
             window_canvas.image_cache.clear();
         }
     }
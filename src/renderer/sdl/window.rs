@@ -1,7 +1,11 @@
-use super::{texture::RendererTexture, FontId, Renderer};
+use super::{
+    glyph::{AtlasKey, GlyphAtlas},
+    texture::RendererTexture,
+    Renderer,
+};
 use crate::{
     error::{Error, Result},
-    image::Icon,
+    image::{Icon, ImageId},
     prelude::*,
     renderer::{RendererSettings, WindowRenderer},
     window::{Position, WindowId},
@@ -9,6 +13,7 @@ use crate::{
 use anyhow::Context;
 use lru::LruCache;
 use sdl2::{
+    event::{Event as SdlEvent, WindowEvent as SdlWindowEvent},
     image::LoadSurface,
     mouse::{Cursor as SdlCursor, SystemCursor as SdlSystemCursor},
     render::{Canvas, TextureQuery},
@@ -18,33 +23,10 @@ use sdl2::{
 };
 use std::{
     cell::RefCell,
-    collections::{hash_map::DefaultHasher, HashMap},
+    collections::HashMap,
     fmt::{self, Write},
-    hash::{Hash, Hasher},
 };
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub(super) struct TextCacheKey {
-    pub(super) text_id: FontId,
-    pub(super) font_id: FontId,
-    pub(super) color: Color,
-    pub(super) size: u16,
-}
-
-impl TextCacheKey {
-    pub(super) fn new(text: &str, font_id: FontId, color: Color, size: u16) -> Self {
-        let mut hasher = DefaultHasher::new();
-        text.hash(&mut hasher);
-        let text_id = hasher.finish();
-        Self {
-            text_id,
-            font_id,
-            color,
-            size,
-        }
-    }
-}
-
 impl Renderer {
     /// Returns the current window canvas, holding the canvas and texture creators for a window.
     #[inline]
@@ -89,12 +71,19 @@ impl Renderer {
     }
 }
 
+/// Offset and scale, in output pixels, mapping a logical size onto a window.
+type LogicalTransform = ((f64, f64), (f64, f64));
+
 pub(super) struct WindowCanvas {
     pub(super) id: WindowId,
     pub(super) canvas: Canvas<Window>,
     pub(super) textures: HashMap<TextureId, RefCell<RendererTexture>>,
-    pub(super) text_cache: LruCache<TextCacheKey, RendererTexture>,
-    pub(super) image_cache: LruCache<*const Image, RendererTexture>,
+    pub(super) glyph_atlases: LruCache<AtlasKey, GlyphAtlas>,
+    pub(super) image_cache: LruCache<ImageId, RendererTexture>,
+    pub(super) logical_size: Option<(u32, u32)>,
+    pub(super) scale_mode: ScaleMode,
+    pub(super) scale: (f32, f32),
+    pub(super) viewport: Option<Rect<i32>>,
 }
 
 impl WindowCanvas {
@@ -152,12 +141,14 @@ impl WindowCanvas {
         }
         let mut canvas = canvas_builder.build().context("failed to build canvas")?;
         log::debug!("Using SDL Renderer `{}`", canvas.info().name);
-        canvas
-            .set_logical_size(s.width, s.height)
-            .context("invalid logical canvas size")?;
-        canvas
-            .set_scale(s.scale_x, s.scale_y)
-            .map_err(Error::Renderer)?;
+        if s.logical_size.is_none() {
+            canvas
+                .set_logical_size(s.width, s.height)
+                .context("invalid logical canvas size")?;
+            canvas
+                .set_scale(s.scale_x, s.scale_y)
+                .map_err(Error::Renderer)?;
+        }
 
         if let Some(ref mut icon) = s.icon {
             let surface = match icon {
@@ -176,13 +167,159 @@ impl WindowCanvas {
         }
 
         log::debug!("Created new window: {}", window_id);
-        Ok(Self {
+        let mut window_canvas = Self {
             id: window_id,
             canvas,
             textures: HashMap::new(),
-            text_cache: LruCache::new(s.text_cache_size),
+            glyph_atlases: LruCache::new(s.text_cache_size),
             image_cache: LruCache::new(s.texture_cache_size),
-        })
+            logical_size: s.logical_size,
+            scale_mode: s.scale_mode,
+            scale: (s.scale_x, s.scale_y),
+            viewport: None,
+        };
+        window_canvas.update_logical_size()?;
+        Ok(window_canvas)
+    }
+
+    /// Returns the offset and scale, in output pixels, used to map the logical size onto the
+    /// window based on the [`ScaleMode`], if a logical size is set.
+    fn logical_transform(&self) -> Result<Option<LogicalTransform>> {
+        let (width, height) = match self.logical_size {
+            Some((width, height)) => (f64::from(width.max(1)), f64::from(height.max(1))),
+            None => return Ok(None),
+        };
+        let (output_width, output_height) = self.canvas.output_size().map_err(Error::Renderer)?;
+        let (output_width, output_height) = (f64::from(output_width), f64::from(output_height));
+        let (scale_x, scale_y) = (output_width / width, output_height / height);
+        let (scale_x, scale_y) = match self.scale_mode {
+            ScaleMode::Stretch => (scale_x, scale_y),
+            ScaleMode::Integer => {
+                let scale = scale_x.min(scale_y).floor().max(1.0);
+                (scale, scale)
+            }
+            ScaleMode::Fill => {
+                let scale = scale_x.max(scale_y);
+                (scale, scale)
+            }
+            ScaleMode::Fit => {
+                let scale = scale_x.min(scale_y);
+                (scale, scale)
+            }
+        };
+        let offset_x = ((output_width - width * scale_x) / 2.0).floor();
+        let offset_y = ((output_height - height * scale_y) / 2.0).floor();
+        Ok(Some(((offset_x, offset_y), (scale_x, scale_y))))
+    }
+
+    /// Updates the canvas to scale the logical size to the window based on the [`ScaleMode`],
+    /// combined with the user scale and viewport, if a logical size is set. SDL resets the
+    /// viewport whenever the window size changes, so this is re-applied on resize and every time
+    /// the window is presented.
+    pub(super) fn update_logical_size(&mut self) -> Result<()> {
+        let ((offset_x, offset_y), (scale_x, scale_y)) = match self.logical_transform()? {
+            Some(transform) => transform,
+            None => return Ok(()),
+        };
+        let (user_x, user_y) = self.scale;
+        let (user_x, user_y) = (f64::from(user_x), f64::from(user_y));
+        let (x, y, width, height) = match self.viewport {
+            Some(rect) => (
+                f64::from(rect.x()) * scale_x * user_x,
+                f64::from(rect.y()) * scale_y * user_y,
+                f64::from(rect.width()) * scale_x * user_x,
+                f64::from(rect.height()) * scale_y * user_y,
+            ),
+            None => {
+                let (width, height) = self.logical_size.unwrap_or_default();
+                (
+                    0.0,
+                    0.0,
+                    f64::from(width) * scale_x,
+                    f64::from(height) * scale_y,
+                )
+            }
+        };
+        let viewport = rect![
+            (offset_x + x).round() as i32,
+            (offset_y + y).round() as i32,
+            width.round() as i32,
+            height.round() as i32,
+        ];
+        // Viewports are stored in output pixels after scaling, so set it unscaled for accuracy
+        let canvas = &mut self.canvas;
+        canvas.set_scale(1.0, 1.0).map_err(Error::Renderer)?;
+        canvas.set_viewport(Some(viewport.into()));
+        canvas
+            .set_scale((scale_x * user_x) as f32, (scale_y * user_y) as f32)
+            .map_err(Error::Renderer)?;
+        Ok(())
+    }
+
+    /// Set the user rendering scale for this window, combining it with the logical size scale if
+    /// one is set.
+    pub(super) fn set_scale(&mut self, x: f32, y: f32) -> Result<()> {
+        self.scale = (x, y);
+        if self.logical_size.is_some() {
+            self.update_logical_size()
+        } else {
+            Ok(self.canvas.set_scale(x, y).map_err(Error::Renderer)?)
+        }
+    }
+
+    /// Set the user viewport for this window, offsetting it by the logical size scale if one is
+    /// set.
+    pub(super) fn set_viewport(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
+        self.viewport = rect;
+        if self.logical_size.is_some() {
+            self.update_logical_size()
+        } else {
+            self.canvas.set_viewport(rect.map(Into::into));
+            Ok(())
+        }
+    }
+
+    /// Returns the user viewport for this window.
+    pub(super) fn viewport(&self) -> Rect<i32> {
+        match (self.viewport, self.logical_size) {
+            (Some(viewport), _) => viewport,
+            (None, Some((width, height))) => {
+                let (scale_x, scale_y) = self.scale;
+                rect![
+                    0,
+                    0,
+                    (width as f32 / scale_x) as i32,
+                    (height as f32 / scale_y) as i32
+                ]
+            }
+            (None, None) => self.canvas.viewport().into(),
+        }
+    }
+
+    /// Maps window coordinates into logical size coordinates, if a logical size is set.
+    pub(super) fn to_logical(&self, x: i32, y: i32) -> (i32, i32) {
+        self.logical_transform().ok().flatten().map_or(
+            (x, y),
+            |((offset_x, offset_y), (scale_x, scale_y))| {
+                // Mouse coordinates are reported in window coordinates, which differ from output
+                // pixels on high DPI displays
+                let (window_width, window_height) = self.canvas.window().size();
+                let (output_width, output_height) = self.canvas.output_size().unwrap_or_default();
+                let dpi_x = f64::from(output_width) / f64::from(window_width.max(1));
+                let dpi_y = f64::from(output_height) / f64::from(window_height.max(1));
+                (
+                    ((f64::from(x) * dpi_x - offset_x) / scale_x).floor() as i32,
+                    ((f64::from(y) * dpi_y - offset_y) / scale_y).floor() as i32,
+                )
+            },
+        )
+    }
+
+    /// Maps relative window motion into logical size coordinates, if a logical size is set.
+    pub(super) fn to_logical_rel(&self, x: i32, y: i32) -> (i32, i32) {
+        let ((origin_x, origin_y), (target_x, target_y)) =
+            (self.to_logical(0, 0), self.to_logical(x, y));
+        (target_x - origin_x, target_y - origin_y)
     }
 }
 
@@ -198,7 +335,7 @@ impl fmt::Debug for WindowCanvas {
             .field("draw_color", &canvas.draw_color())
             .field("clip", &canvas.clip_rect())
             .field("texture_count", &self.textures.len())
-            .field("text_cache", &self.text_cache)
+            .field("glyph_atlases", &self.glyph_atlases)
             .field("image_cache", &self.image_cache)
             .finish_non_exhaustive()
     }
@@ -269,7 +406,51 @@ impl WindowRenderer for Renderer {
     /// Returns a single event or None if the event pump is empty.
     #[inline]
     fn poll_event(&mut self) -> Option<Event> {
-        self.event_pump.poll_event().map(Into::into)
+        let mut event = self.event_pump.poll_event()?;
+        match event {
+            SdlEvent::Window {
+                window_id,
+                win_event: SdlWindowEvent::SizeChanged(..),
+                ..
+            } => {
+                if let Some(window) = self.windows.get_mut(&WindowId(window_id)) {
+                    if let Err(err) = window.update_logical_size() {
+                        log::warn!("failed to update logical size: {err}");
+                    }
+                }
+            }
+            SdlEvent::MouseMotion {
+                window_id,
+                ref mut x,
+                ref mut y,
+                ref mut xrel,
+                ref mut yrel,
+                ..
+            } => {
+                if let Some(window) = self.windows.get(&WindowId(window_id)) {
+                    (*x, *y) = window.to_logical(*x, *y);
+                    (*xrel, *yrel) = window.to_logical_rel(*xrel, *yrel);
+                }
+            }
+            SdlEvent::MouseButtonDown {
+                window_id,
+                ref mut x,
+                ref mut y,
+                ..
+            }
+            | SdlEvent::MouseButtonUp {
+                window_id,
+                ref mut x,
+                ref mut y,
+                ..
+            } => {
+                if let Some(window) = self.windows.get(&WindowId(window_id)) {
+                    (*x, *y) = window.to_logical(*x, *y);
+                }
+            }
+            _ => (),
+        }
+        Some(event.into())
     }
 
     /// Get the current window title.
@@ -304,7 +485,11 @@ impl WindowRenderer for Renderer {
     #[inline]
     fn dimensions(&self) -> Result<(u32, u32)> {
         self.texture_target.map_or_else(
-            || self.window_dimensions(),
+            || {
+                self.window_canvas()?
+                    .logical_size
+                    .map_or_else(|| self.window_dimensions(), Ok)
+            },
             |texture_id| {
                 self.windows
                     .values()
@@ -342,23 +527,45 @@ impl WindowRenderer for Renderer {
             .window_mut()
             .set_size(width, height)
             .context("invalid window dimensions")?;
-        canvas
-            .set_logical_size(width, height)
-            .context("invalid logical window size")?;
-        Ok(())
+        let window_canvas = self.window_canvas_mut()?;
+        if window_canvas.logical_size.is_some() {
+            window_canvas.update_logical_size()
+        } else {
+            window_canvas
+                .canvas
+                .set_logical_size(width, height)
+                .context("invalid logical window size")?;
+            Ok(())
+        }
     }
 
     /// Returns the rendering viewport of the current render target.
     #[inline]
     fn viewport(&self) -> Result<Rect<i32>> {
-        Ok(self.canvas()?.viewport().into())
+        if self.texture_target.is_some() {
+            match self.target_state.viewport {
+                Some(viewport) => Ok(viewport),
+                None => {
+                    let (width, height) = self.dimensions()?;
+                    Ok(rect![0, 0, width as i32, height as i32])
+                }
+            }
+        } else {
+            Ok(self.window_canvas()?.viewport())
+        }
     }
 
     /// Set the rendering viewport of the current render target.
     #[inline]
     fn set_viewport(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
-        self.canvas_mut()?.set_viewport(rect.map(Into::into));
-        Ok(())
+        self.target_state.viewport = rect;
+        if self.texture_target.is_none() {
+            return self.window_canvas_mut()?.set_viewport(rect);
+        }
+        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
+            canvas.set_viewport(rect.map(Into::into));
+            Ok(())
+        })
     }
 
     /// Dimensions of the primary display as `(width, height)`.
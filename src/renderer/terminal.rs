@@ -0,0 +1,601 @@
+//! Terminal Renderer
+//!
+//! Renders to a software canvas which is presented to the terminal using 24-bit color half-block
+//! characters, where each character cell displays two vertically stacked pixels. Keyboard input is
+//! read from stdin. Enabled with the `terminal` feature, which replaces the default SDL renderer.
+//!
+//! Text is drawn as terminal characters on top of the presented canvas instead of being
+//! rasterized, so font family and size have no effect and text drawn to a texture target is not
+//! displayed. Bitmap fonts are the exception and are drawn to the canvas like any other image.
+//! Mouse, controller and audio support are unavailable.
+
+use crate::{
+    error::{Error, Result},
+    gui::{
+        text_layout::{FontMetrics, GlyphAdvances},
+        theme::{BitmapFont, FontId, FontSrc},
+    },
+    prelude::*,
+    renderer::{RendererSettings, Rendering},
+};
+use anyhow::anyhow;
+use canvas::{Canvas, CopyMods, Painter, TargetState};
+use event::Input;
+use log::debug;
+use std::{collections::HashMap, fmt, ops::Range};
+use texture::RendererTexture;
+use window::{Terminal, TextSpan};
+
+pub use audio::{AudioDevice, AudioFormatNum};
+
+pub mod audio;
+mod canvas;
+mod event;
+mod texture;
+mod window;
+
+/// A terminal [Renderer] implementation.
+pub(crate) struct Renderer {
+    settings: RendererSettings,
+    title: String,
+    terminal: Terminal,
+    input: Input,
+    draw_color: Color,
+    blend_mode: BlendMode,
+    font_style: FontStyle,
+    font_size: u32,
+    text_align: TextAlign,
+    current_font: FontId,
+    bitmap_fonts: HashMap<FontId, BitmapFont>,
+    canvas: Canvas,
+    window_state: TargetState,
+    target_state: TargetState,
+    texture_target: Option<TextureId>,
+    target_stack: Vec<(Option<TextureId>, TargetState)>,
+    textures: HashMap<TextureId, RendererTexture>,
+    next_texture_id: usize,
+    spans: Vec<TextSpan>,
+    hidden: bool,
+}
+
+impl Renderer {
+    /// Returns a [Painter] for the current render target canvas.
+    fn painter(&mut self) -> Result<Painter<'_>> {
+        match self.texture_target {
+            Some(texture_id) => {
+                let texture = self
+                    .textures
+                    .get_mut(&texture_id)
+                    .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?;
+                Ok(Painter::new(&mut texture.canvas, self.target_state))
+            }
+            None => Ok(Painter::new(&mut self.canvas, self.window_state)),
+        }
+    }
+
+    /// Returns a mutable reference to the render state of the current render target.
+    #[inline]
+    fn state_mut(&mut self) -> &mut TargetState {
+        if self.texture_target.is_some() {
+            &mut self.target_state
+        } else {
+            &mut self.window_state
+        }
+    }
+
+    /// Returns the number of canvas pixels, horizontally, represented by a single terminal cell.
+    /// Vertically, a cell represents twice as many.
+    #[inline]
+    fn cell_size(&self) -> u32 {
+        let (width, height) = self.canvas.dimensions();
+        self.terminal.cell_size(width, height)
+    }
+
+    /// Returns the byte ranges of the lines of `text` after wrapping to `wrap_width` cells.
+    fn wrap_lines(text: &str, wrap_width: Option<u32>) -> Vec<Range<usize>> {
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for line in text.split('\n') {
+            let start = offset;
+            offset += line.len() + 1;
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            match wrap_width {
+                Some(width) if width > 0 => {
+                    let mut chunk_start = start;
+                    for (i, (idx, _)) in line.char_indices().enumerate().skip(1) {
+                        if i % width as usize == 0 {
+                            lines.push(chunk_start..start + idx);
+                            chunk_start = start + idx;
+                        }
+                    }
+                    lines.push(chunk_start..start + line.len());
+                }
+                _ => lines.push(start..start + line.len()),
+            }
+        }
+        lines
+    }
+
+    /// Draw a polygon through a list of [Point]s to the current render target.
+    fn fill_polygon<I>(&mut self, ps: I, fill: Option<Color>, stroke: Option<Color>) -> Result<()>
+    where
+        I: IntoIterator<Item = Point<i32>>,
+    {
+        self.painter()?.polygon(ps, fill, stroke);
+        Ok(())
+    }
+}
+
+impl Rendering for Renderer {
+    /// Initializes the `TerminalRenderer` using the given settings.
+    #[inline]
+    fn new(s: RendererSettings) -> Result<Self> {
+        debug!("Initializing TerminalRenderer");
+
+        let terminal = Terminal::new()?;
+        let input = Input::new();
+        let (width, height) = s.logical_size.unwrap_or((s.width, s.height));
+        let mut renderer = Self {
+            title: s.title.clone(),
+            hidden: s.hidden,
+            settings: s,
+            terminal,
+            input,
+            draw_color: Color::BLACK,
+            blend_mode: BlendMode::None,
+            font_style: FontStyle::NORMAL,
+            font_size: 14,
+            text_align: TextAlign::Left,
+            current_font: Font::default().id(),
+            bitmap_fonts: HashMap::new(),
+            canvas: Canvas::new(width, height),
+            window_state: TargetState::default(),
+            target_state: TargetState::default(),
+            texture_target: None,
+            target_stack: Vec::new(),
+            textures: HashMap::new(),
+            next_texture_id: 0,
+            spans: Vec::new(),
+        };
+        renderer.canvas.clear(Color::BLACK);
+        renderer.terminal.set_title(&renderer.title)?;
+        Ok(renderer)
+    }
+
+    /// Clears the canvas to the current clear color.
+    #[inline]
+    fn clear(&mut self) -> Result<()> {
+        let color = self.draw_color;
+        match self.texture_target {
+            Some(texture_id) => self
+                .textures
+                .get_mut(&texture_id)
+                .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?
+                .canvas
+                .clear(color),
+            None => {
+                self.canvas.clear(color);
+                self.spans.clear();
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the color used by the renderer to draw to the current canvas.
+    #[inline]
+    fn set_draw_color(&mut self, color: Color) -> Result<()> {
+        self.draw_color = color;
+        Ok(())
+    }
+
+    /// Sets the clip rect used by the renderer to draw to the current canvas.
+    #[inline]
+    fn clip(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
+        self.state_mut().clip = rect;
+        Ok(())
+    }
+
+    /// Sets the blend mode used by the renderer to drawing.
+    #[inline]
+    fn blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    /// Writes the canvas to the terminal.
+    #[inline]
+    fn present(&mut self) {
+        if !self.hidden {
+            if let Err(err) = self.terminal.present(&self.canvas, &self.spans) {
+                log::warn!("failed to present to terminal: {err}");
+            }
+        }
+        self.input.release_keys();
+    }
+
+    /// Set the rendering scale of the current canvas. Drawing coordinates are scaled by x/y
+    /// factors before being drawn to the canvas.
+    #[inline]
+    fn scale(&mut self, x: f32, y: f32) -> Result<()> {
+        self.state_mut().scale = (x, y);
+        Ok(())
+    }
+
+    /// Set the font size for drawing to the current canvas. Text is drawn one character per
+    /// terminal cell, so this only affects bitmap fonts.
+    #[inline]
+    fn font_size(&mut self, size: u32) -> Result<()> {
+        self.font_size = size;
+        Ok(())
+    }
+
+    /// Set the font style for drawing to the current canvas.
+    #[inline]
+    fn font_style(&mut self, style: FontStyle) {
+        self.font_style = style;
+    }
+
+    /// Set the font family for drawing to the current canvas. Text is drawn using the terminal
+    /// font unless a bitmap font is provided.
+    #[inline]
+    fn font_family(&mut self, font: &Font) -> Result<()> {
+        self.current_font = font.id();
+        if let FontSrc::Bitmap(src) = font.source() {
+            if !self.bitmap_fonts.contains_key(&self.current_font) {
+                let bitmap_font = BitmapFont::load(src)?;
+                self.bitmap_fonts.insert(self.current_font, bitmap_font);
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the horizontal alignment of each line of text drawn to the current canvas.
+    #[inline]
+    fn text_align(&mut self, align: TextAlign) {
+        self.text_align = align;
+    }
+
+    /// Returns the distance from the top of a line of text to its baseline. Terminal text sits on
+    /// the bottom of its cell.
+    #[inline]
+    fn font_ascent(&self) -> Result<u32> {
+        match self.bitmap_fonts.get(&self.current_font) {
+            Some(font) => Ok(font.ascent(self.font_size)),
+            None => Ok(2 * self.cell_size()),
+        }
+    }
+
+    /// Draw text to the terminal on top of the canvas. Text can not be rotated or flipped and is
+    /// only displayed when drawn to the window target, unless drawn with a bitmap font.
+    #[inline]
+    fn text(
+        &mut self,
+        pos: Point<i32>,
+        text: &str,
+        wrap_width: Option<u32>,
+        angle: Option<f64>,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        fill: Option<Color>,
+        _outline: u16,
+    ) -> Result<(u32, u32)> {
+        let size = self.size_of(text, wrap_width)?;
+        if let Some(font) = self.bitmap_fonts.get(&self.current_font) {
+            if let Some(fill) = fill.filter(|_| size.0 > 0 && size.1 > 0) {
+                let image = Canvas::from_image(&font.render(
+                    text,
+                    wrap_width,
+                    self.font_size,
+                    self.text_align,
+                    fill,
+                ));
+                let dst = rect![pos.x(), pos.y(), size.0 as i32, size.1 as i32];
+                let mods = CopyMods {
+                    color: None,
+                    alpha: None,
+                    blend_mode: BlendMode::Blend,
+                };
+                let angle = angle.unwrap_or(0.0);
+                self.painter()?
+                    .copy(&image, None, Some(dst), angle, center, flipped, mods);
+            }
+            return Ok(size);
+        }
+        let fill = match fill {
+            Some(fill) if self.texture_target.is_none() && !text.is_empty() => fill,
+            _ => return Ok(size),
+        };
+
+        let cell_size = self.cell_size();
+        let TargetState {
+            viewport, scale, ..
+        } = self.window_state;
+        let (offset_x, offset_y) = viewport.map_or((0, 0), |v| (v.x(), v.y()));
+        let x = offset_x + (pos.x() as f32 * scale.0) as i32;
+        let y = offset_y + (pos.y() as f32 * scale.1) as i32;
+        let (col, row) = (x / cell_size as i32, y / (2 * cell_size as i32));
+        let wrap_width = wrap_width.map(|width| width / cell_size);
+        let columns = size.0 / cell_size;
+        for (i, range) in Self::wrap_lines(text, wrap_width).into_iter().enumerate() {
+            let line = &text[range];
+            if line.is_empty() {
+                continue;
+            }
+            let extra = columns.saturating_sub(line.chars().count() as u32);
+            let span = TextSpan {
+                col: col + self.text_align.offset(extra),
+                row: row + i as i32,
+                text: line.to_owned(),
+                color: fill,
+                style: self.font_style,
+            };
+            // Text drawn in the same place replaces what was there
+            self.spans
+                .retain(|s| (s.col, s.row) != (span.col, span.row));
+            self.spans.push(span);
+        }
+        Ok(size)
+    }
+
+    /// Get clipboard text from the system clipboard. Terminals don't allow reading the clipboard,
+    /// so this is always empty.
+    #[inline]
+    fn clipboard_text(&self) -> String {
+        String::new()
+    }
+
+    /// Set clipboard text to the system clipboard, if supported by the terminal.
+    #[inline]
+    fn set_clipboard_text(&self, value: &str) -> Result<()> {
+        self.terminal.set_clipboard_text(value)
+    }
+
+    /// Open a URL in the default system browser.
+    #[inline]
+    fn open_url(&self, url: &str) -> Result<()> {
+        Err(anyhow!(
+            "opening {url} is not supported by the terminal renderer"
+        ))
+    }
+
+    /// Returns the rendered dimensions of the given text as `(width, height)`, where each
+    /// character occupies a terminal cell.
+    #[inline]
+    fn size_of(&self, text: &str, wrap_width: Option<u32>) -> Result<(u32, u32)> {
+        if let Some(font) = self.bitmap_fonts.get(&self.current_font) {
+            return Ok(font.size_of(text, wrap_width, self.font_size));
+        }
+        let cell_size = self.cell_size();
+        let lines = Self::wrap_lines(text, wrap_width.map(|width| width / cell_size));
+        let width = lines
+            .iter()
+            .map(|range| text[range.clone()].chars().count() as u32)
+            .max()
+            .unwrap_or_default();
+        Ok((width * cell_size, lines.len() as u32 * 2 * cell_size))
+    }
+
+    /// Lay out text using the current font, wrapping lines to `wrap_width` if provided. Without a
+    /// bitmap font, each character occupies a terminal cell and lines wrap on any character.
+    #[inline]
+    fn layout_text(&self, text: &str, wrap_width: Option<u32>) -> Result<TextLayout> {
+        if let Some(font) = self.bitmap_fonts.get(&self.current_font) {
+            let size = self.font_size;
+            let metrics = FontMetrics {
+                height: font.height(size),
+                line_skip: font.height(size) as i32,
+                ascent: font.ascent(size),
+            };
+            let mut advances = GlyphAdvances::default();
+            return TextLayout::new(
+                text,
+                wrap_width,
+                self.text_align,
+                metrics,
+                &mut advances,
+                |line| Ok(font.size_of(line, None, size).0),
+            );
+        }
+        let cell_size = self.cell_size();
+        let metrics = FontMetrics {
+            height: 2 * cell_size,
+            line_skip: 2 * cell_size as i32,
+            ascent: 2 * cell_size,
+        };
+        let lines = Self::wrap_lines(text, wrap_width.map(|width| width / cell_size));
+        let mut advances = GlyphAdvances::default();
+        TextLayout::from_lines(
+            text,
+            lines,
+            self.text_align,
+            metrics,
+            &mut advances,
+            |line| Ok(line.chars().count() as u32 * cell_size),
+        )
+    }
+
+    /// Draw a pixel to the current canvas.
+    #[inline]
+    fn point(&mut self, p: Point<i32>, color: Color) -> Result<()> {
+        self.painter()?.point(p, color);
+        Ok(())
+    }
+
+    /// Draw a line to the current canvas.
+    #[inline]
+    fn line(&mut self, line: Line<i32>, _smooth: bool, width: u8, color: Color) -> Result<()> {
+        self.painter()?.line(line, width, color);
+        Ok(())
+    }
+
+    /// Draw a cubic Bezier curve to the current canvas.
+    #[inline]
+    fn bezier<I>(&mut self, ps: I, detail: i32, stroke: Option<Color>) -> Result<()>
+    where
+        I: Iterator<Item = Point<i32>>,
+    {
+        if let Some(stroke) = stroke {
+            let ps: Vec<Point<i32>> = ps.collect();
+            self.painter()?.bezier(&ps, detail, stroke);
+        }
+        Ok(())
+    }
+
+    /// Draw a triangle to the current canvas.
+    #[inline]
+    fn triangle(
+        &mut self,
+        tri: Tri<i32>,
+        _smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        self.fill_polygon(tri.points(), fill, stroke)
+    }
+
+    /// Draw a rectangle to the current canvas.
+    #[inline]
+    fn rect(
+        &mut self,
+        rect: Rect<i32>,
+        radius: Option<i32>,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        self.painter()?.rect(rect, radius, fill, stroke);
+        Ok(())
+    }
+
+    /// Draw a quadrilateral to the current canvas.
+    #[inline]
+    fn quad(
+        &mut self,
+        quad: Quad<i32>,
+        _smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        self.fill_polygon(quad.points(), fill, stroke)
+    }
+
+    /// Draw a polygon to the current canvas.
+    #[inline]
+    fn polygon<I>(
+        &mut self,
+        ps: I,
+        _smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()>
+    where
+        I: Iterator<Item = Point<i32>>,
+    {
+        self.fill_polygon(ps, fill, stroke)
+    }
+
+    /// Draw a ellipse to the current canvas.
+    #[inline]
+    fn ellipse(
+        &mut self,
+        ellipse: Ellipse<i32>,
+        _smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        self.painter()?.ellipse(ellipse, fill, stroke);
+        Ok(())
+    }
+
+    /// Draw an arc to the current canvas.
+    #[inline]
+    fn arc(
+        &mut self,
+        p: Point<i32>,
+        radius: i32,
+        start: i32,
+        end: i32,
+        mode: ArcMode,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        self.painter()?
+            .arc(p, radius, start, end, mode, fill, stroke);
+        Ok(())
+    }
+
+    /// Draw an image to the current canvas, optionally rotated about a `center`, flipped or
+    /// tinted
+    #[inline]
+    fn image(
+        &mut self,
+        img: &Image,
+        src: Option<Rect<i32>>,
+        dst: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        tint: Option<Color>,
+    ) -> Result<()> {
+        let image = Canvas::from_image(img);
+        let mods = CopyMods {
+            color: tint,
+            alpha: None,
+            blend_mode: self.blend_mode,
+        };
+        self.painter()?
+            .copy(&image, src, dst, angle, center, flipped, mods);
+        Ok(())
+    }
+
+    /// Return the current rendered target pixels as an array of RGBA bytes, along with the
+    /// `(width, height)` of the pixels read.
+    #[inline]
+    fn read_pixels(&mut self) -> Result<((u32, u32), Vec<u8>)> {
+        let canvas = match self.texture_target {
+            Some(texture_id) => self
+                .textures
+                .get(&texture_id)
+                .map(|texture| &texture.canvas)
+                .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?,
+            None => &self.canvas,
+        };
+        Ok((canvas.dimensions(), canvas.to_bytes()))
+    }
+
+    /// Controllers are not supported by the terminal renderer.
+    fn open_controller(&mut self, controller_id: ControllerId) -> Result<()> {
+        log::warn!(
+            "Controller {} can not be opened. Controllers are unsupported by the terminal renderer.",
+            *controller_id
+        );
+        Ok(())
+    }
+
+    /// Controllers are not supported by the terminal renderer.
+    fn close_controller(&mut self, _controller_id: ControllerId) {}
+}
+
+impl fmt::Debug for Renderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TerminalRenderer")
+            .field("settings", &self.settings)
+            .field("title", &self.title)
+            .field("terminal", &self.terminal)
+            .field("draw_color", &self.draw_color)
+            .field("blend_mode", &self.blend_mode)
+            .field("font_style", &self.font_style)
+            .field("font_size", &self.font_size)
+            .field("text_align", &self.text_align)
+            .field("current_font", &self.current_font)
+            .field("bitmap_font_count", &self.bitmap_fonts.len())
+            .field("canvas_dimensions", &self.canvas.dimensions())
+            .field("window_state", &self.window_state)
+            .field("target_state", &self.target_state)
+            .field("texture_target", &self.texture_target)
+            .field("target_stack", &self.target_stack)
+            .field("texture_count", &self.textures.len())
+            .field("next_texture_id", &self.next_texture_id)
+            .field("span_count", &self.spans.len())
+            .field("hidden", &self.hidden)
+            .finish_non_exhaustive()
+    }
+}
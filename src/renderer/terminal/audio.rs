@@ -0,0 +1,150 @@
+//! Terminal Audio
+//!
+//! Audio output is not supported by the terminal renderer. Queued samples are discarded and audio
+//! devices fail to open.
+
+use super::Renderer;
+use crate::{
+    audio::{AudioDeviceDriver, AudioDriver},
+    error::Result,
+    prelude::*,
+};
+use anyhow::anyhow;
+use std::marker::PhantomData;
+
+/// Default sample rate reported when none is requested.
+const DEFAULT_SAMPLE_RATE: i32 = 48_000;
+
+/// Audio callback or playback device that can be paused and resumed.
+#[derive(Debug)]
+pub struct AudioDevice<CB: AudioCallback>(PhantomData<CB>);
+
+impl<CB: AudioCallback> AudioDeviceDriver for AudioDevice<CB> {
+    /// Return the status of this audio callback device.
+    #[inline]
+    fn status(&self) -> AudioStatus {
+        AudioStatus::Stopped
+    }
+
+    /// Return the current driver of this audio callback device.
+    #[inline]
+    fn driver(&self) -> &'static str {
+        "terminal"
+    }
+
+    /// Returns the [`AudioSpec`] for this audio callback device.
+    #[inline]
+    fn spec(&self) -> AudioSpec {
+        AudioSpec::default()
+    }
+
+    /// Resumes playback of this audio callback device.
+    #[inline]
+    fn resume(&self) {}
+
+    /// Pause playback of this audio callback device.
+    #[inline]
+    fn pause(&self) {}
+}
+
+/// Represents a valid Channel format for generic audio sample types.
+pub trait AudioFormatNum {}
+
+impl AudioFormatNum for i8 {}
+impl AudioFormatNum for u8 {}
+impl AudioFormatNum for i16 {}
+impl AudioFormatNum for u16 {}
+impl AudioFormatNum for i32 {}
+impl AudioFormatNum for f32 {}
+
+impl AudioDriver for Renderer {
+    /// Add audio samples to the audio buffer queue. Samples are discarded.
+    #[inline]
+    fn enqueue_audio(&mut self, _samples: &[f32]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Clear audio samples from the audio buffer queue.
+    #[inline]
+    fn clear_audio(&mut self) {}
+
+    /// Return the status of the current audio queue device.
+    #[inline]
+    fn audio_status(&self) -> AudioStatus {
+        AudioStatus::Stopped
+    }
+
+    /// Return the current driver of the current audio queue device.
+    #[inline]
+    fn audio_driver(&self) -> &'static str {
+        "terminal"
+    }
+
+    /// Returns the sample rate for the current audio queue device.
+    #[inline]
+    fn audio_sample_rate(&self) -> i32 {
+        self.settings
+            .audio_sample_rate
+            .unwrap_or(DEFAULT_SAMPLE_RATE)
+    }
+
+    /// Returns the queued buffer size of the current audio queue device.
+    #[inline]
+    fn audio_queued_size(&self) -> u32 {
+        0
+    }
+
+    /// Returns the buffer size of the current audio queue device.
+    #[inline]
+    fn audio_size(&self) -> u32 {
+        0
+    }
+
+    /// Resume playback of the current audio queue device.
+    #[inline]
+    fn resume_audio(&mut self) {}
+
+    /// Pause playback of the current audio queue device.
+    #[inline]
+    fn pause_audio(&mut self) {}
+
+    /// Opens and returns an audio callback device for playback. Always returns an error, since
+    /// audio is not supported by the terminal renderer.
+    #[allow(single_use_lifetimes)]
+    #[inline]
+    fn open_playback<'a, CB, F, D>(
+        &self,
+        _device: D,
+        _desired_spec: &AudioSpecDesired,
+        _get_callback: F,
+    ) -> Result<AudioDevice<CB>>
+    where
+        CB: AudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        Err(anyhow!(
+            "audio playback is not supported by the terminal renderer"
+        ))
+    }
+
+    /// Opens and returns an audio capture device for recording. Always returns an error, since
+    /// audio is not supported by the terminal renderer.
+    #[allow(single_use_lifetimes)]
+    #[inline]
+    fn open_capture<'a, CB, F, D>(
+        &self,
+        _device: D,
+        _desired_spec: &AudioSpecDesired,
+        _get_callback: F,
+    ) -> Result<AudioDevice<CB>>
+    where
+        CB: AudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        Err(anyhow!(
+            "audio capture is not supported by the terminal renderer"
+        ))
+    }
+}
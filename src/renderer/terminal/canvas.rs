@@ -0,0 +1,690 @@
+//! Software rasterized pixel canvas used by the terminal renderer.
+
+use crate::prelude::*;
+use std::f64::consts::PI;
+
+/// Clip, viewport and scale of a render target, applied to all drawing.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct TargetState {
+    pub(crate) clip: Option<Rect<i32>>,
+    pub(crate) viewport: Option<Rect<i32>>,
+    pub(crate) scale: (f32, f32),
+}
+
+impl Default for TargetState {
+    fn default() -> Self {
+        Self {
+            clip: None,
+            viewport: None,
+            scale: (1.0, 1.0),
+        }
+    }
+}
+
+/// A buffer of RGBA pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 4]>,
+}
+
+impl Canvas {
+    /// Constructs a transparent `Canvas` with the given `width` and `height`.
+    pub(crate) fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0; 4]; width as usize * height as usize],
+        }
+    }
+
+    /// Constructs a `Canvas` from the pixels of an [Image].
+    pub(crate) fn from_image(img: &Image) -> Self {
+        let mut canvas = Self::new(img.width(), img.height());
+        canvas.update(
+            None,
+            img.as_bytes(),
+            img.format().channels() * img.width() as usize,
+            img.format().channels(),
+        );
+        canvas
+    }
+
+    /// Returns the dimensions of the canvas as `(width, height)`.
+    #[inline]
+    pub(crate) const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the color of the pixel at `(x, y)`, if it's within the canvas.
+    #[inline]
+    pub(crate) fn get(&self, x: i32, y: i32) -> Option<[u8; 4]> {
+        self.index(x, y).map(|idx| self.pixels[idx])
+    }
+
+    /// Returns the canvas pixels as RGBA bytes.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+
+    /// Fills the entire canvas with a [Color], ignoring any clip or viewport.
+    pub(crate) fn clear(&mut self, color: Color) {
+        self.pixels.fill(color.channels());
+    }
+
+    /// Update a portion `rect` of the canvas with pixel data, or the entire canvas if `rect` is
+    /// `None`. `pitch` is the number of bytes in a row of `pixels` and `channels` is the number of
+    /// bytes per pixel.
+    pub(crate) fn update(
+        &mut self,
+        rect: Option<Rect<i32>>,
+        pixels: &[u8],
+        pitch: usize,
+        channels: usize,
+    ) {
+        let rect = rect.unwrap_or_else(|| rect![0, 0, self.width as i32, self.height as i32]);
+        for (row, y) in (rect.top()..rect.bottom()).enumerate() {
+            for (col, x) in (rect.left()..rect.right()).enumerate() {
+                let offset = row * pitch + col * channels;
+                if let (Some(idx), Some(pixel)) =
+                    (self.index(x, y), pixels.get(offset..offset + channels))
+                {
+                    match *pixel {
+                        [red, green, blue] => self.pixels[idx] = [red, green, blue, 255],
+                        [red, green, blue, alpha] => self.pixels[idx] = [red, green, blue, alpha],
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Multiply the alpha channel of the canvas by the alpha channel of a `mask` canvas.
+    pub(crate) fn mask(&mut self, mask: &Canvas) {
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                if let Some(idx) = self.index(x, y) {
+                    let mask_alpha = mask.get(x, y).map_or(0, |[_, _, _, alpha]| alpha);
+                    let alpha = &mut self.pixels[idx][3];
+                    *alpha = mul(*alpha, mask_alpha);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// Pixel modulation and blending used when copying one canvas onto another.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct CopyMods {
+    pub(crate) color: Option<Color>,
+    pub(crate) alpha: Option<u8>,
+    pub(crate) blend_mode: BlendMode,
+}
+
+/// Draws to a [Canvas] through the clip, viewport and scale of a [`TargetState`].
+#[derive(Debug)]
+pub(crate) struct Painter<'a> {
+    canvas: &'a mut Canvas,
+    offset: (f64, f64),
+    scale: (f64, f64),
+    size: (f64, f64),
+    // Clip bounds as `[left, top, right, bottom)` in canvas pixels.
+    clip: [i32; 4],
+}
+
+impl<'a> Painter<'a> {
+    /// Constructs a `Painter` drawing to `canvas` with the given [`TargetState`].
+    pub(crate) fn new(canvas: &'a mut Canvas, state: TargetState) -> Self {
+        let (width, height) = (canvas.width as i32, canvas.height as i32);
+        let viewport = state.viewport.unwrap_or_else(|| rect![0, 0, width, height]);
+        let scale = (f64::from(state.scale.0), f64::from(state.scale.1));
+        let offset = (f64::from(viewport.x()), f64::from(viewport.y()));
+        let mut clip = [
+            viewport.left().max(0),
+            viewport.top().max(0),
+            viewport.right().min(width),
+            viewport.bottom().min(height),
+        ];
+        if let Some(rect) = state.clip {
+            // Clip is relative to the viewport and scaled
+            let left = (offset.0 + f64::from(rect.left()) * scale.0).floor() as i32;
+            let top = (offset.1 + f64::from(rect.top()) * scale.1).floor() as i32;
+            let right = (offset.0 + f64::from(rect.right()) * scale.0).ceil() as i32;
+            let bottom = (offset.1 + f64::from(rect.bottom()) * scale.1).ceil() as i32;
+            clip = [
+                clip[0].max(left),
+                clip[1].max(top),
+                clip[2].min(right),
+                clip[3].min(bottom),
+            ];
+        }
+        let size = (
+            f64::from(viewport.width()) / scale.0,
+            f64::from(viewport.height()) / scale.1,
+        );
+        Self {
+            canvas,
+            offset,
+            scale,
+            size,
+            clip,
+        }
+    }
+
+    /// Transform a position in drawing coordinates to canvas coordinates.
+    #[inline]
+    fn transform(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.offset.0 + x * self.scale.0,
+            self.offset.1 + y * self.scale.1,
+        )
+    }
+
+    /// Transform the center of the pixel at a [Point] in drawing coordinates to canvas
+    /// coordinates.
+    #[inline]
+    fn center_of(&self, p: Point<i32>) -> (f64, f64) {
+        self.transform(f64::from(p.x()) + 0.5, f64::from(p.y()) + 0.5)
+    }
+
+    /// Blend a [Color] onto the canvas pixel at `(x, y)` if it's within the clip bounds.
+    #[inline]
+    fn plot(&mut self, x: i32, y: i32, color: [u8; 4], mode: BlendMode) {
+        let [left, top, right, bottom] = self.clip;
+        if x >= left && x < right && y >= top && y < bottom {
+            if let Some(idx) = self.canvas.index(x, y) {
+                blend(&mut self.canvas.pixels[idx], color, mode);
+            }
+        }
+    }
+
+    /// Fill a span of pixels on row `y` whose centers lie within `[x1, x2]`.
+    fn span(&mut self, x1: f64, x2: f64, y: i32, color: [u8; 4], mode: BlendMode) {
+        let start = (x1 - 0.5).ceil().max(f64::from(self.clip[0])) as i32;
+        let end = (x2 - 0.5).floor().min(f64::from(self.clip[2] - 1)) as i32;
+        for x in start..=end {
+            self.plot(x, y, color, mode);
+        }
+    }
+
+    /// Returns the range of rows whose pixel centers lie within `[y1, y2]`.
+    fn rows(&self, y1: f64, y2: f64) -> std::ops::RangeInclusive<i32> {
+        let start = (y1 - 0.5).ceil().max(f64::from(self.clip[1])) as i32;
+        let end = (y2 - 0.5).floor().min(f64::from(self.clip[3] - 1)) as i32;
+        start..=end
+    }
+
+    /// Draw a single pixel.
+    pub(crate) fn point(&mut self, p: Point<i32>, color: Color) {
+        let (x, y) = (f64::from(p.x()), f64::from(p.y()));
+        let (x1, y1) = self.transform(x, y);
+        let (x2, y2) = self.transform(x + 1.0, y + 1.0);
+        self.fill_rect_f(x1, y1, x2, y2, color);
+    }
+
+    /// Draw a line, `width` pixels thick.
+    pub(crate) fn line(&mut self, line: Line<i32>, width: u8, color: Color) {
+        let start = self.center_of(line.start());
+        let end = self.center_of(line.end());
+        let thickness = f64::from(width.max(1)) * self.scale.0.max(self.scale.1);
+        if thickness > 1.5 {
+            self.thick_line(start, end, thickness, color);
+        } else {
+            self.thin_line(start, end, color);
+        }
+    }
+
+    /// Draw a 1-pixel line between two positions in canvas coordinates.
+    fn thin_line(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64), color: Color) {
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let steps = dx.abs().max(dy.abs()).round().max(1.0);
+        let (step_x, step_y) = (dx / steps, dy / steps);
+        let (mode, color) = paint(color);
+        for i in 0..=(steps as i32) {
+            let x = x1 + step_x * f64::from(i);
+            let y = y1 + step_y * f64::from(i);
+            self.plot(x.floor() as i32, y.floor() as i32, color, mode);
+        }
+    }
+
+    /// Draw a thick line between two positions in canvas coordinates as a filled quad.
+    fn thick_line(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64), width: f64, color: Color) {
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let len = dx.hypot(dy);
+        let half = width / 2.0;
+        let (nx, ny) = if len > 0.0 {
+            (-dy / len * half, dx / len * half)
+        } else {
+            (0.0, half)
+        };
+        // Extend ends by half the width for square caps
+        let (ex, ey) = if len > 0.0 {
+            (dx / len * half, dy / len * half)
+        } else {
+            (half, 0.0)
+        };
+        self.fill_polygon_f(
+            &[
+                (x1 - ex + nx, y1 - ey + ny),
+                (x2 + ex + nx, y2 + ey + ny),
+                (x2 + ex - nx, y2 + ey - ny),
+                (x1 - ex - nx, y1 - ey - ny),
+            ],
+            color,
+        );
+    }
+
+    /// Draw connected 1-pixel lines through a list of positions in canvas coordinates,
+    /// optionally closing the path back to the first position.
+    fn polyline_f(&mut self, points: &[(f64, f64)], closed: bool, color: Color) {
+        for pair in points.windows(2) {
+            self.thin_line(pair[0], pair[1], color);
+        }
+        if closed && points.len() > 2 {
+            self.thin_line(points[points.len() - 1], points[0], color);
+        }
+    }
+
+    /// Fill a rectangle with edges in canvas coordinates.
+    fn fill_rect_f(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: Color) {
+        let (mode, color) = paint(color);
+        for y in self.rows(y1, y2) {
+            self.span(x1, x2, y, color, mode);
+        }
+    }
+
+    /// Fill a polygon with vertices in canvas coordinates using the even-odd rule.
+    fn fill_polygon_f(&mut self, points: &[(f64, f64)], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+        let (mode, color) = paint(color);
+        let (min_y, max_y) = points
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &(_, y)| {
+                (min.min(y), max.max(y))
+            });
+        let mut crossings = Vec::new();
+        for y in self.rows(min_y, max_y) {
+            let center = f64::from(y) + 0.5;
+            crossings.clear();
+            for (i, &(x1, y1)) in points.iter().enumerate() {
+                let (x2, y2) = points[(i + 1) % points.len()];
+                if (y1 <= center && y2 > center) || (y2 <= center && y1 > center) {
+                    crossings.push(x1 + (center - y1) / (y2 - y1) * (x2 - x1));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+            for pair in crossings.chunks_exact(2) {
+                self.span(pair[0], pair[1], y, color, mode);
+            }
+        }
+    }
+
+    /// Draw a polygon, filled and/or outlined, through a list of [Point]s.
+    pub(crate) fn polygon<I>(&mut self, ps: I, fill: Option<Color>, stroke: Option<Color>)
+    where
+        I: IntoIterator<Item = Point<i32>>,
+    {
+        let points: Vec<_> = ps.into_iter().map(|p| self.center_of(p)).collect();
+        if let Some(fill) = fill {
+            self.fill_polygon_f(&points, fill);
+        }
+        if let Some(stroke) = stroke {
+            self.polyline_f(&points, true, stroke);
+        }
+    }
+
+    /// Draw a cubic Bezier curve through a list of anchor and control points.
+    pub(crate) fn bezier(&mut self, ps: &[Point<i32>], detail: i32, stroke: Color) {
+        if ps.len() < 2 {
+            return;
+        }
+        let steps = detail.max(2);
+        let points: Vec<_> = (0..=steps)
+            .map(|step| {
+                let t = f64::from(step) / f64::from(steps);
+                // De Casteljau's algorithm
+                let mut ps: Vec<_> = ps.iter().map(|&p| self.center_of(p)).collect();
+                while ps.len() > 1 {
+                    ps = ps
+                        .windows(2)
+                        .map(|w| {
+                            (
+                                w[0].0 + (w[1].0 - w[0].0) * t,
+                                w[0].1 + (w[1].1 - w[0].1) * t,
+                            )
+                        })
+                        .collect();
+                }
+                ps[0]
+            })
+            .collect();
+        self.polyline_f(&points, false, stroke);
+    }
+
+    /// Draw a rectangle, optionally with rounded corners.
+    pub(crate) fn rect(
+        &mut self,
+        rect: Rect<i32>,
+        radius: Option<i32>,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) {
+        let (x, y) = (f64::from(rect.x()), f64::from(rect.y()));
+        let (width, height) = (f64::from(rect.width()), f64::from(rect.height()));
+        let radius = radius.map_or(0.0, |radius| {
+            f64::from(radius).clamp(0.0, width.min(height) / 2.0)
+        });
+        if let Some(fill) = fill {
+            let (x1, y1) = self.transform(x, y);
+            let (x2, y2) = self.transform(x + width, y + height);
+            if radius > 0.0 {
+                let points = rounded_rect_points(
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    radius * self.scale.0,
+                    radius * self.scale.1,
+                );
+                self.fill_polygon_f(&points, fill);
+            } else {
+                self.fill_rect_f(x1, y1, x2, y2, fill);
+            }
+        }
+        if let Some(stroke) = stroke {
+            // Outline through the centers of the edge pixels
+            let (x1, y1) = self.transform(x + 0.5, y + 0.5);
+            let (x2, y2) = self.transform(x + width - 0.5, y + height - 0.5);
+            let points =
+                rounded_rect_points(x1, y1, x2, y2, radius * self.scale.0, radius * self.scale.1);
+            self.polyline_f(&points, true, stroke);
+        }
+    }
+
+    /// Draw an ellipse centered on its position.
+    pub(crate) fn ellipse(
+        &mut self,
+        ellipse: Ellipse<i32>,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) {
+        let (cx, cy) = self.center_of(point![ellipse.x(), ellipse.y()]);
+        let rx = f64::from(ellipse.width() / 2) * self.scale.0;
+        let ry = f64::from(ellipse.height() / 2) * self.scale.1;
+        if let Some(fill) = fill {
+            let (mode, color) = paint(fill);
+            for y in self.rows(cy - ry - 0.5, cy + ry + 0.5) {
+                let dy = (f64::from(y) + 0.5 - cy) / (ry + 0.5);
+                if dy.abs() <= 1.0 {
+                    let half = (rx + 0.5) * (1.0 - dy * dy).sqrt();
+                    self.span(cx - half, cx + half, y, color, mode);
+                }
+            }
+        }
+        if let Some(stroke) = stroke {
+            let points = arc_points(cx, cy, rx, ry, 0.0, 360.0);
+            self.polyline_f(&points, true, stroke);
+        }
+    }
+
+    /// Draw an arc from `start` to `end` degrees, clockwise from the positive x-axis.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn arc(
+        &mut self,
+        p: Point<i32>,
+        radius: i32,
+        start: i32,
+        end: i32,
+        mode: ArcMode,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) {
+        let (cx, cy) = self.center_of(p);
+        let rx = f64::from(radius) * self.scale.0;
+        let ry = f64::from(radius) * self.scale.1;
+        let (start, mut end) = (f64::from(start), f64::from(end));
+        while end < start {
+            end += 360.0;
+        }
+        let points = arc_points(cx, cy, rx, ry, start, end);
+        match mode {
+            ArcMode::Default => {
+                if let Some(stroke) = stroke {
+                    self.polyline_f(&points, false, stroke);
+                }
+            }
+            ArcMode::Pie => {
+                let mut points = points;
+                points.insert(0, (cx, cy));
+                if let Some(fill) = fill {
+                    self.fill_polygon_f(&points, fill);
+                }
+                if let Some(stroke) = stroke {
+                    self.polyline_f(&points, true, stroke);
+                }
+            }
+        }
+    }
+
+    /// Copy a portion `src` of a [Canvas] to `dst`, or the entire viewport if `dst` is `None`,
+    /// optionally rotated by `angle` degrees clockwise about `center`, relative to `dst`, or
+    /// `flipped`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn copy(
+        &mut self,
+        texture: &Canvas,
+        src: Option<Rect<i32>>,
+        dst: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        mods: CopyMods,
+    ) {
+        let src = src.unwrap_or_else(|| rect![0, 0, texture.width as i32, texture.height as i32]);
+        let (x1, y1, x2, y2) = match dst {
+            Some(dst) => {
+                let (x1, y1) = self.transform(f64::from(dst.left()), f64::from(dst.top()));
+                let (x2, y2) = self.transform(f64::from(dst.right()), f64::from(dst.bottom()));
+                (x1, y1, x2, y2)
+            }
+            None => {
+                let (x2, y2) = self.transform(self.size.0, self.size.1);
+                (self.offset.0, self.offset.1, x2, y2)
+            }
+        };
+        let (width, height) = (x2 - x1, y2 - y1);
+        if width <= 0.0 || height <= 0.0 || src.width() <= 0 || src.height() <= 0 {
+            return;
+        }
+        let (cx, cy) = center.map_or((x1 + width / 2.0, y1 + height / 2.0), |c| {
+            (
+                x1 + f64::from(c.x()) * self.scale.0,
+                y1 + f64::from(c.y()) * self.scale.1,
+            )
+        });
+        let (sin, cos) = (angle * PI / 180.0).sin_cos();
+        let rotate = |x: f64, y: f64, sin: f64| {
+            let (dx, dy) = (x - cx, y - cy);
+            (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos)
+        };
+
+        // Bounding box of the rotated destination
+        let corners = [
+            rotate(x1, y1, sin),
+            rotate(x2, y1, sin),
+            rotate(x2, y2, sin),
+            rotate(x1, y2, sin),
+        ];
+        let (min_x, max_x, min_y, max_y) = corners.iter().fold(
+            (
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(min_x, max_x, min_y, max_y), &(x, y)| {
+                (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y))
+            },
+        );
+
+        let flip_x = matches!(flipped, Some(Flipped::Horizontal | Flipped::Both));
+        let flip_y = matches!(flipped, Some(Flipped::Vertical | Flipped::Both));
+        for y in self.rows(min_y, max_y) {
+            let start = (min_x - 0.5).ceil().max(f64::from(self.clip[0])) as i32;
+            let end = (max_x - 0.5).floor().min(f64::from(self.clip[2] - 1)) as i32;
+            for x in start..=end {
+                // Map the destination pixel center back into the unrotated destination
+                let (u, v) = rotate(f64::from(x) + 0.5, f64::from(y) + 0.5, -sin);
+                let (u, v) = ((u - x1) / width, (v - y1) / height);
+                if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v) {
+                    continue;
+                }
+                let u = if flip_x { 1.0 - u } else { u };
+                let v = if flip_y { 1.0 - v } else { v };
+                let sx = src.x() + (u * f64::from(src.width())) as i32;
+                let sy = src.y() + (v * f64::from(src.height())) as i32;
+                if let Some(color) = texture.get(sx, sy) {
+                    let color = modulate(color, mods.color, mods.alpha);
+                    self.plot(x, y, color, mods.blend_mode);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the [`BlendMode`] and channels to draw a primitive [Color] with. Opaque colors are
+/// drawn as-is, while translucent colors are alpha blended.
+#[inline]
+fn paint(color: Color) -> (BlendMode, [u8; 4]) {
+    let channels = color.channels();
+    if channels[3] == 255 {
+        (BlendMode::None, channels)
+    } else {
+        (BlendMode::Blend, channels)
+    }
+}
+
+/// Multiply two channel values as if they were in the range `0.0..=1.0`.
+#[inline]
+fn mul(a: u8, b: u8) -> u8 {
+    ((u16::from(a) * u16::from(b) + 127) / 255) as u8
+}
+
+/// Modulate pixel channels by a color and alpha.
+#[inline]
+fn modulate(pixel: [u8; 4], color: Option<Color>, alpha: Option<u8>) -> [u8; 4] {
+    let [mut red, mut green, mut blue, mut a] = pixel;
+    if let Some(color) = color {
+        let [r, g, b, ca] = color.channels();
+        (red, green, blue, a) = (mul(red, r), mul(green, g), mul(blue, b), mul(a, ca));
+    }
+    if let Some(alpha) = alpha {
+        a = mul(a, alpha);
+    }
+    [red, green, blue, a]
+}
+
+/// Blend `src` onto `dst` using a [`BlendMode`].
+#[inline]
+fn blend(dst: &mut [u8; 4], src: [u8; 4], mode: BlendMode) {
+    let alpha = src[3];
+    match mode {
+        BlendMode::None => *dst = src,
+        BlendMode::Blend => {
+            let inv = 255 - alpha;
+            for (dst, src) in dst.iter_mut().zip(src).take(3) {
+                *dst = mul(src, alpha).saturating_add(mul(*dst, inv));
+            }
+            dst[3] = alpha.saturating_add(mul(dst[3], inv));
+        }
+        BlendMode::Add => {
+            for (dst, src) in dst.iter_mut().zip(src).take(3) {
+                *dst = dst.saturating_add(mul(src, alpha));
+            }
+        }
+        BlendMode::Mod => {
+            for (dst, src) in dst.iter_mut().zip(src).take(3) {
+                *dst = mul(*dst, src);
+            }
+        }
+    }
+}
+
+/// Returns points along an elliptical arc from `start` to `end` degrees, clockwise from the
+/// positive x-axis.
+fn arc_points(cx: f64, cy: f64, rx: f64, ry: f64, start: f64, end: f64) -> Vec<(f64, f64)> {
+    let sweep = end - start;
+    let segments = ((sweep / 360.0) * 2.0 * PI * rx.max(ry) / 2.0)
+        .ceil()
+        .clamp(4.0, 720.0) as usize;
+    (0..=segments)
+        .map(|i| {
+            let angle = (start + sweep * i as f64 / segments as f64) * PI / 180.0;
+            let (sin, cos) = angle.sin_cos();
+            (cx + rx * cos, cy + ry * sin)
+        })
+        .collect()
+}
+
+/// Returns the outline of a rectangle with rounded corners, with edges in canvas coordinates.
+fn rounded_rect_points(x1: f64, y1: f64, x2: f64, y2: f64, rx: f64, ry: f64) -> Vec<(f64, f64)> {
+    if rx <= 0.0 || ry <= 0.0 {
+        return vec![(x1, y1), (x2, y1), (x2, y2), (x1, y2)];
+    }
+    let mut points = arc_points(x2 - rx, y1 + ry, rx, ry, 270.0, 360.0);
+    points.extend(arc_points(x2 - rx, y2 - ry, rx, ry, 0.0, 90.0));
+    points.extend(arc_points(x1 + rx, y2 - ry, rx, ry, 90.0, 180.0));
+    points.extend(arc_points(x1 + rx, y1 + ry, rx, ry, 180.0, 270.0));
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rect_fills_exact_pixels() {
+        let mut canvas = Canvas::new(4, 4);
+        let mut painter = Painter::new(&mut canvas, TargetState::default());
+        painter.rect(rect![1, 1, 2, 2], None, Some(Color::WHITE), None);
+        assert_eq!(canvas.get(0, 0), Some([0, 0, 0, 0]));
+        assert_eq!(canvas.get(1, 1), Some([255, 255, 255, 255]));
+        assert_eq!(canvas.get(2, 2), Some([255, 255, 255, 255]));
+        assert_eq!(canvas.get(3, 3), Some([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn clip_limits_drawing() {
+        let mut canvas = Canvas::new(4, 4);
+        let state = TargetState {
+            clip: Some(rect![0, 0, 2, 2]),
+            ..TargetState::default()
+        };
+        let mut painter = Painter::new(&mut canvas, state);
+        painter.rect(rect![0, 0, 4, 4], None, Some(Color::WHITE), None);
+        assert_eq!(canvas.get(1, 1), Some([255, 255, 255, 255]));
+        assert_eq!(canvas.get(2, 2), Some([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn blend_modes() {
+        let mut dst = [0, 0, 255, 255];
+        blend(&mut dst, [255, 0, 0, 128], BlendMode::Blend);
+        assert_eq!(dst, [128, 0, 127, 255]);
+        let mut dst = [100, 100, 100, 255];
+        blend(&mut dst, [200, 0, 0, 255], BlendMode::Add);
+        assert_eq!(dst, [255, 100, 100, 255]);
+    }
+}
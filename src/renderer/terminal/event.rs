@@ -0,0 +1,305 @@
+use crate::prelude::*;
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+    iter::Peekable,
+    str::Chars,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+#[rustfmt::skip]
+const LETTERS: [Key; 26] = [
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L,
+    Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X,
+    Key::Y, Key::Z,
+];
+#[rustfmt::skip]
+const DIGITS: [Key; 10] = [
+    Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7,
+    Key::Num8, Key::Num9,
+];
+
+/// Keyboard input read from stdin.
+///
+/// Terminals only report key presses, so each key press generates a `KeyDown` event followed by a
+/// `KeyUp` event once the next frame has been presented.
+#[derive(Debug)]
+pub(crate) struct Input {
+    receiver: Option<Receiver<Vec<u8>>>,
+    events: VecDeque<Event>,
+    released: Vec<Event>,
+}
+
+impl Input {
+    /// Start reading input from stdin on a background thread.
+    pub(crate) fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("pix-engine-stdin".into())
+            .spawn(move || {
+                let mut stdin = io::stdin();
+                let mut buffer = [0; 256];
+                // Stop on EOF, error or once the renderer has been dropped
+                while let Ok(len @ 1..) = stdin.read(&mut buffer) {
+                    if sender.send(buffer[..len].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            });
+        if let Err(err) = &spawned {
+            log::warn!("failed to read terminal input: {err}");
+        }
+        Self {
+            receiver: spawned.ok().map(|_| receiver),
+            events: VecDeque::new(),
+            released: Vec::new(),
+        }
+    }
+
+    /// Returns a single event or None if there are no pending events.
+    pub(crate) fn poll_event(&mut self) -> Option<Event> {
+        if self.events.is_empty() {
+            self.read();
+        }
+        self.events.pop_front()
+    }
+
+    /// Queue `KeyUp` events for keys pressed since the last call.
+    pub(crate) fn release_keys(&mut self) {
+        self.events.extend(self.released.drain(..));
+    }
+
+    /// Parse any input received from stdin into events.
+    fn read(&mut self) {
+        let mut bytes = Vec::new();
+        if let Some(receiver) = &self.receiver {
+            loop {
+                match receiver.try_recv() {
+                    Ok(chunk) => bytes.extend(chunk),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        self.receiver = None;
+                        break;
+                    }
+                }
+            }
+        }
+        if !bytes.is_empty() {
+            self.parse(&String::from_utf8_lossy(&bytes));
+        }
+    }
+
+    /// Parse terminal input into events.
+    fn parse(&mut self, input: &str) {
+        let mut chars = input.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                // Ctrl-C, since raw mode disables the interrupt signal
+                '\x03' => self.events.push_back(Event::Quit),
+                '\x1b' => match chars.peek() {
+                    Some('[' | 'O') => {
+                        chars.next();
+                        if let Some((key, keymod)) = parse_escape(&mut chars) {
+                            self.key(key, keymod);
+                        }
+                    }
+                    Some(&c) if char_key(c).is_some() => {
+                        chars.next();
+                        if let Some((key, keymod)) = char_key(c) {
+                            self.key(key, keymod | KeyMod::ALT);
+                        }
+                    }
+                    _ => self.key(Key::Escape, KeyMod::NONE),
+                },
+                '\r' | '\n' => self.key(Key::Return, KeyMod::NONE),
+                '\t' => self.key(Key::Tab, KeyMod::NONE),
+                '\x7f' | '\x08' => self.key(Key::Backspace, KeyMod::NONE),
+                '\x01'..='\x1a' => {
+                    let letter = c as usize - 1;
+                    self.key(LETTERS[letter], KeyMod::CTRL);
+                }
+                c => {
+                    if let Some((key, keymod)) = char_key(c) {
+                        self.key(key, keymod);
+                    }
+                    if !c.is_control() {
+                        self.events.push_back(Event::TextInput {
+                            text: c.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Queue a key press, releasing it after the next frame.
+    fn key(&mut self, key: Key, keymod: KeyMod) {
+        self.events.push_back(Event::KeyDown {
+            key: Some(key),
+            keymod,
+            repeat: false,
+        });
+        self.released.push(Event::KeyUp {
+            key: Some(key),
+            keymod,
+            repeat: false,
+        });
+    }
+}
+
+/// Parse the remainder of a CSI or SS3 escape sequence into a key and modifiers, e.g.
+/// `ESC [ 1 ; 5 A` for `Ctrl+Up`.
+fn parse_escape(chars: &mut Peekable<Chars<'_>>) -> Option<(Key, KeyMod)> {
+    let mut params = String::new();
+    let end = loop {
+        match chars.next()? {
+            c @ ('0'..='9' | ';') => params.push(c),
+            c => break c,
+        }
+    };
+    let mut params = params.split(';').map(|param| param.parse::<u8>().ok());
+    let code = params.next().flatten();
+    let keymod = params.next().flatten().map_or(KeyMod::NONE, |modifier| {
+        // Modifiers are encoded as 1 + a bitmask of Shift = 1, Alt = 2 and Ctrl = 4
+        let bits = modifier.saturating_sub(1);
+        let mut keymod = KeyMod::NONE;
+        for (bit, flag) in [(1, KeyMod::SHIFT), (2, KeyMod::ALT), (4, KeyMod::CTRL)] {
+            if bits & bit != 0 {
+                keymod |= flag;
+            }
+        }
+        keymod
+    });
+    let key = match (end, code) {
+        ('A', _) => Key::Up,
+        ('B', _) => Key::Down,
+        ('C', _) => Key::Right,
+        ('D', _) => Key::Left,
+        ('H', _) | ('~', Some(1 | 7)) => Key::Home,
+        ('F', _) | ('~', Some(4 | 8)) => Key::End,
+        ('P', _) | ('~', Some(11)) => Key::F1,
+        ('Q', _) | ('~', Some(12)) => Key::F2,
+        ('R', _) | ('~', Some(13)) => Key::F3,
+        ('S', _) | ('~', Some(14)) => Key::F4,
+        ('Z', _) => return Some((Key::Tab, KeyMod::SHIFT)),
+        ('~', Some(2)) => Key::Insert,
+        ('~', Some(3)) => Key::Delete,
+        ('~', Some(5)) => Key::PageUp,
+        ('~', Some(6)) => Key::PageDown,
+        ('~', Some(15)) => Key::F5,
+        ('~', Some(17)) => Key::F6,
+        ('~', Some(18)) => Key::F7,
+        ('~', Some(19)) => Key::F8,
+        ('~', Some(20)) => Key::F9,
+        ('~', Some(21)) => Key::F10,
+        ('~', Some(23)) => Key::F11,
+        ('~', Some(24)) => Key::F12,
+        _ => return None,
+    };
+    Some((key, keymod))
+}
+
+/// Returns the key and modifiers used to type a character on a US keyboard layout.
+fn char_key(c: char) -> Option<(Key, KeyMod)> {
+    let key = match c {
+        'a'..='z' => LETTERS[c as usize - 'a' as usize],
+        'A'..='Z' => return Some((LETTERS[c as usize - 'A' as usize], KeyMod::SHIFT)),
+        '0'..='9' => DIGITS[c as usize - '0' as usize],
+        ' ' => Key::Space,
+        '-' => Key::Minus,
+        '=' => Key::Equals,
+        '[' => Key::LeftBracket,
+        ']' => Key::RightBracket,
+        '\\' => Key::Backslash,
+        ';' => Key::Semicolon,
+        '\'' => Key::Quote,
+        ',' => Key::Comma,
+        '.' => Key::Period,
+        '/' => Key::Slash,
+        '`' => Key::Backquote,
+        _ => {
+            let key = match c {
+                '!' => Key::Num1,
+                '@' => Key::Num2,
+                '#' => Key::Num3,
+                '$' => Key::Num4,
+                '%' => Key::Num5,
+                '^' => Key::Num6,
+                '&' => Key::Num7,
+                '*' => Key::Num8,
+                '(' => Key::Num9,
+                ')' => Key::Num0,
+                '_' => Key::Minus,
+                '+' => Key::Equals,
+                '{' => Key::LeftBracket,
+                '}' => Key::RightBracket,
+                '|' => Key::Backslash,
+                ':' => Key::Semicolon,
+                '"' => Key::Quote,
+                '<' => Key::Comma,
+                '>' => Key::Period,
+                '?' => Key::Slash,
+                '~' => Key::Backquote,
+                _ => return None,
+            };
+            return Some((key, KeyMod::SHIFT));
+        }
+    };
+    Some((key, KeyMod::NONE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Vec<Event> {
+        let mut events = Input {
+            receiver: None,
+            events: VecDeque::new(),
+            released: Vec::new(),
+        };
+        events.parse(input);
+        events.events.into()
+    }
+
+    fn key_down(key: Key, keymod: KeyMod) -> Event {
+        Event::KeyDown {
+            key: Some(key),
+            keymod,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn parse_text() {
+        assert_eq!(
+            parse("aB"),
+            vec![
+                key_down(Key::A, KeyMod::NONE),
+                Event::TextInput { text: "a".into() },
+                key_down(Key::B, KeyMod::SHIFT),
+                Event::TextInput { text: "B".into() },
+            ]
+        );
+        assert_eq!(parse("é"), vec![Event::TextInput { text: "é".into() }]);
+    }
+
+    #[test]
+    fn parse_escape_sequences() {
+        assert_eq!(parse("\x1b[A"), vec![key_down(Key::Up, KeyMod::NONE)]);
+        assert_eq!(parse("\x1b[1;5C"), vec![key_down(Key::Right, KeyMod::CTRL)]);
+        assert_eq!(parse("\x1b[3~"), vec![key_down(Key::Delete, KeyMod::NONE)]);
+        assert_eq!(parse("\x1bOP"), vec![key_down(Key::F1, KeyMod::NONE)]);
+        assert_eq!(parse("\x1b"), vec![key_down(Key::Escape, KeyMod::NONE)]);
+        assert_eq!(parse("\x1bx"), vec![key_down(Key::X, KeyMod::ALT)]);
+    }
+
+    #[test]
+    fn parse_control_keys() {
+        assert_eq!(parse("\r"), vec![key_down(Key::Return, KeyMod::NONE)]);
+        assert_eq!(parse("\x7f"), vec![key_down(Key::Backspace, KeyMod::NONE)]);
+        assert_eq!(parse("\x01"), vec![key_down(Key::A, KeyMod::CTRL)]);
+        assert_eq!(parse("\x03"), vec![Event::Quit]);
+    }
+}
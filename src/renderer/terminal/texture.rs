@@ -0,0 +1,316 @@
+use super::{
+    canvas::{Canvas, CopyMods, TargetState},
+    Renderer,
+};
+use crate::{
+    error::{Error, Result},
+    prelude::*,
+    renderer::TextureRenderer,
+};
+use anyhow::anyhow;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RendererTexture {
+    pub(crate) canvas: Canvas,
+    color_mod: Option<Color>,
+    alpha_mod: Option<u8>,
+    blend_mode: Option<BlendMode>,
+}
+
+impl RendererTexture {
+    pub(crate) const fn new(canvas: Canvas) -> Self {
+        Self {
+            canvas,
+            color_mod: None,
+            alpha_mod: None,
+            blend_mode: None,
+        }
+    }
+
+    /// Returns the color, alpha and blend modulation used when rendering. Persistent texture
+    /// settings take precedence over the given `tint` and `blend_mode`.
+    pub(crate) fn mods(&self, tint: Option<Color>, blend_mode: BlendMode) -> CopyMods {
+        let [r, g, b, a] = tint.map_or([255; 4], |t| t.channels());
+        let [r, g, b] = self
+            .color_mod
+            .map_or([r, g, b], |c| [c.red(), c.green(), c.blue()]);
+        CopyMods {
+            color: Some(Color::rgb(r, g, b)),
+            alpha: Some(self.alpha_mod.unwrap_or(a)),
+            blend_mode: self.blend_mode.unwrap_or(blend_mode),
+        }
+    }
+}
+
+impl Renderer {
+    /// Returns a mutable reference to the texture with the given `texture_id`.
+    fn texture_mut(&mut self, texture_id: TextureId) -> Result<&mut RendererTexture> {
+        self.textures
+            .get_mut(&texture_id)
+            .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))
+    }
+}
+
+impl TextureRenderer for Renderer {
+    /// Create a texture to render to.
+    #[inline]
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        _format: Option<PixelFormat>,
+    ) -> Result<TextureId> {
+        let texture_id = TextureId(self.next_texture_id);
+        self.next_texture_id += 1;
+        self.textures
+            .insert(texture_id, RendererTexture::new(Canvas::new(width, height)));
+        Ok(texture_id)
+    }
+
+    /// Delete texture.
+    #[inline]
+    fn delete_texture(&mut self, texture_id: TextureId) -> Result<()> {
+        self.textures
+            .remove(&texture_id)
+            .map_or(Err(Error::InvalidTexture(texture_id).into()), |_| Ok(()))
+    }
+
+    /// Update texture with pixel data.
+    #[inline]
+    fn update_texture<P: AsRef<[u8]>>(
+        &mut self,
+        texture_id: TextureId,
+        rect: Option<Rect<i32>>,
+        pixels: P,
+        pitch: usize,
+    ) -> Result<()> {
+        let texture = self.texture_mut(texture_id)?;
+        let width = rect.map_or_else(|| texture.canvas.dimensions().0, |r| r.width() as u32);
+        let channels = if width > 0 { pitch / width as usize } else { 4 };
+        texture
+            .canvas
+            .update(rect, pixels.as_ref(), pitch, channels);
+        Ok(())
+    }
+
+    /// Draw texture canvas.
+    #[inline]
+    fn texture(
+        &mut self,
+        texture_id: TextureId,
+        src: Option<Rect<i32>>,
+        dst: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        tint: Option<Color>,
+    ) -> Result<()> {
+        assert_ne!(
+            Some(texture_id),
+            self.texture_target,
+            "`texture_id` must not equal the current `texture_target`"
+        );
+
+        // Temporarily take the texture so the target canvas can be borrowed mutably
+        let texture = self
+            .textures
+            .remove(&texture_id)
+            .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?;
+        let mods = texture.mods(tint, self.blend_mode);
+        let result = self.painter().map(|mut painter| {
+            painter.copy(&texture.canvas, src, dst, angle, center, flipped, mods);
+        });
+        self.textures.insert(texture_id, texture);
+        result
+    }
+
+    /// Set the [`ScaleQuality`] used when rendering a `Texture`. Textures are always sampled
+    /// using nearest pixels when scaled, so this has no effect.
+    #[inline]
+    fn set_texture_scale_quality(
+        &mut self,
+        texture_id: TextureId,
+        _quality: ScaleQuality,
+    ) -> Result<()> {
+        self.texture_mut(texture_id).map(|_| ())
+    }
+
+    /// Set or clear a persistent color modulation for a `Texture`.
+    #[inline]
+    fn set_texture_color_mod(&mut self, texture_id: TextureId, color: Option<Color>) -> Result<()> {
+        self.texture_mut(texture_id)?.color_mod = color;
+        Ok(())
+    }
+
+    /// Set or clear a persistent alpha modulation for a `Texture`.
+    #[inline]
+    fn set_texture_alpha_mod(&mut self, texture_id: TextureId, alpha: Option<u8>) -> Result<()> {
+        self.texture_mut(texture_id)?.alpha_mod = alpha;
+        Ok(())
+    }
+
+    /// Set or clear a persistent [`BlendMode`] for a `Texture`.
+    #[inline]
+    fn set_texture_blend_mode(
+        &mut self,
+        texture_id: TextureId,
+        mode: Option<BlendMode>,
+    ) -> Result<()> {
+        self.texture_mut(texture_id)?.blend_mode = mode;
+        Ok(())
+    }
+
+    /// Returns the dimensions of a `Texture` as `(width, height)`.
+    #[inline]
+    fn texture_dimensions(&self, texture_id: TextureId) -> Result<(u32, u32)> {
+        self.textures
+            .get(&texture_id)
+            .map(|texture| texture.canvas.dimensions())
+            .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))
+    }
+
+    /// Multiply the alpha channel of a `Texture` by the alpha channel of a `mask` texture.
+    fn mask_texture(&mut self, texture_id: TextureId, mask_id: TextureId) -> Result<()> {
+        let mask = self
+            .textures
+            .get(&mask_id)
+            .map(|mask| mask.canvas.clone())
+            .ok_or_else(|| anyhow!(Error::InvalidTexture(mask_id)))?;
+        self.texture_mut(texture_id)?.canvas.mask(&mask);
+        Ok(())
+    }
+
+    /// Returns texture used as the target for drawing operations, if set.
+    #[inline]
+    fn texture_target(&self) -> Option<TextureId> {
+        self.texture_target
+    }
+
+    /// Set a `Texture` as the primary target for drawing operations instead of the window
+    /// target canvas.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    #[inline]
+    fn set_texture_target(&mut self, id: TextureId) -> Result<()> {
+        if !self.textures.contains_key(&id) {
+            return Err(Error::InvalidTexture(id).into());
+        }
+        self.texture_target = Some(id);
+        self.target_state = TargetState::default();
+        Ok(())
+    }
+
+    /// Clear the `Texture` target set by `set_texture_target` or the last call to
+    /// `push_texture_target`, restoring the previous render target.
+    #[inline]
+    fn clear_texture_target(&mut self) {
+        self.pop_texture_target();
+    }
+
+    /// Push the current render target and its clip, viewport and scale to a stack and set a
+    /// `Texture` as the primary target for drawing operations.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    #[inline]
+    fn push_texture_target(&mut self, id: TextureId) -> Result<()> {
+        let previous = (self.texture_target, self.target_state);
+        self.set_texture_target(id)?;
+        self.target_stack.push(previous);
+        Ok(())
+    }
+
+    /// Restore the render target and its clip, viewport and scale saved by the last call to
+    /// `push_texture_target`, or the window target canvas if the stack is empty.
+    #[inline]
+    fn pop_texture_target(&mut self) {
+        let (texture_target, target_state) = self.target_stack.pop().unwrap_or_default();
+        self.texture_target = texture_target;
+        self.target_state = target_state;
+    }
+
+    /// Returns whether a texture is set as the target for drawing operations.
+    #[inline]
+    fn has_texture_target(&self) -> bool {
+        self.texture_target.is_some()
+    }
+
+    /// Clear internal texture cache. Images and text aren't cached by the terminal renderer, so
+    /// this has no effect.
+    #[inline]
+    fn clear_texture_cache(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::{RendererSettings, Rendering};
+
+    #[test]
+    fn clear_texture_target_pops_one_level() -> Result<()> {
+        let mut renderer = Renderer::new(RendererSettings::default())?;
+        let panel = renderer.create_texture(16, 16, None)?;
+        let icon = renderer.create_texture(8, 8, None)?;
+
+        renderer.set_texture_target(panel)?;
+        renderer.push_texture_target(icon)?;
+        renderer.push_texture_target(panel)?;
+        renderer.clear_texture_target();
+        assert_eq!(renderer.texture_target(), Some(icon));
+        renderer.pop_texture_target();
+        assert_eq!(renderer.texture_target(), Some(panel));
+        renderer.clear_texture_target();
+        assert_eq!(renderer.texture_target(), None);
+        renderer.pop_texture_target();
+        assert_eq!(renderer.texture_target(), None);
+        Ok(())
+    }
+    #[test]
+    fn texture_settings_override_tint_and_blend_mode() -> Result<()> {
+        let mut renderer = Renderer::new(RendererSettings::default())?;
+        let texture_id = renderer.create_texture(4, 4, None)?;
+        let tint = Some(Color::rgba(10, 20, 30, 40));
+        let mods = |renderer: &Renderer| renderer.textures[&texture_id].mods(tint, BlendMode::None);
+        assert_eq!(
+            mods(&renderer),
+            CopyMods {
+                color: Some(Color::rgb(10, 20, 30)),
+                alpha: Some(40),
+                blend_mode: BlendMode::None,
+            }
+        );
+
+        assert_eq!(ScaleQuality::default(), ScaleQuality::Nearest);
+        renderer.set_texture_scale_quality(texture_id, ScaleQuality::Linear)?;
+        renderer.set_texture_color_mod(texture_id, Some(Color::RED))?;
+        renderer.set_texture_alpha_mod(texture_id, Some(128))?;
+        renderer.set_texture_blend_mode(texture_id, Some(BlendMode::Add))?;
+        assert_eq!(
+            mods(&renderer),
+            CopyMods {
+                color: Some(Color::rgb(255, 0, 0)),
+                alpha: Some(128),
+                blend_mode: BlendMode::Add,
+            }
+        );
+
+        renderer.set_texture_color_mod(texture_id, None)?;
+        renderer.set_texture_alpha_mod(texture_id, None)?;
+        renderer.set_texture_blend_mode(texture_id, None)?;
+        assert_eq!(mods(&renderer).color, Some(Color::rgb(10, 20, 30)));
+        assert_eq!(mods(&renderer).alpha, Some(40));
+        assert_eq!(mods(&renderer).blend_mode, BlendMode::None);
+
+        renderer.delete_texture(texture_id)?;
+        assert!(renderer
+            .set_texture_scale_quality(texture_id, ScaleQuality::Nearest)
+            .is_err());
+        assert!(renderer.set_texture_color_mod(texture_id, None).is_err());
+        assert!(renderer.set_texture_alpha_mod(texture_id, None).is_err());
+        assert!(renderer.set_texture_blend_mode(texture_id, None).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,592 @@
+use super::{canvas::Canvas, Renderer};
+use crate::{
+    error::{Error, Result},
+    prelude::*,
+    renderer::{RendererSettings, TextureRenderer, WindowRenderer},
+};
+use anyhow::{anyhow, Context};
+use std::{
+    env,
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+/// The single window presented to the terminal.
+const WINDOW_ID: WindowId = WindowId(1);
+/// Terminal size used when it can't be determined.
+const DEFAULT_SIZE: (u32, u32) = (80, 24);
+
+/// A line of text drawn on top of the canvas, positioned in terminal cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TextSpan {
+    pub(crate) col: i32,
+    pub(crate) row: i32,
+    pub(crate) text: String,
+    pub(crate) color: Color,
+    pub(crate) style: FontStyle,
+}
+
+/// Terminal output state.
+#[derive(Debug)]
+pub(crate) struct Terminal {
+    /// Original terminal mode to restore on exit, if stdin and stdout are a terminal.
+    saved_mode: Option<sys::Mode>,
+    /// Terminal size in `(columns, rows)`.
+    size: (u32, u32),
+    last_frame: String,
+}
+
+impl Terminal {
+    /// Set up the terminal for drawing. If stdin and stdout are a terminal, input is switched to
+    /// raw mode and the alternate screen is used. Otherwise, frames are written sequentially, e.g.
+    /// for logs.
+    pub(crate) fn new() -> Result<Self> {
+        // Otherwise, e.g. when piped to a file or on Windows, fall back to writing frames
+        // sequentially
+        if !sys::is_terminal() {
+            return Ok(Self {
+                saved_mode: None,
+                size: env_size().unwrap_or(DEFAULT_SIZE),
+                last_frame: String::new(),
+            });
+        }
+        let size = sys::terminal_size()?;
+        let saved_mode = sys::enable_raw_mode()?;
+        sys::watch_resize();
+        let terminal = Self {
+            saved_mode: Some(saved_mode),
+            size,
+            last_frame: String::new(),
+        };
+        // Use alternate screen, hide the cursor and clear
+        write_out("\x1b[?1049h\x1b[?25l\x1b[2J")?;
+        Ok(terminal)
+    }
+
+    /// Whether drawing to an interactive terminal.
+    #[inline]
+    pub(crate) const fn is_interactive(&self) -> bool {
+        self.saved_mode.is_some()
+    }
+
+    /// Returns the number of canvas pixels, horizontally, displayed by a single terminal cell so
+    /// that a canvas of the given dimensions fits in the terminal. Vertically, a cell displays
+    /// twice as many.
+    pub(crate) fn cell_size(&self, width: u32, height: u32) -> u32 {
+        let (cols, rows) = self.size;
+        let cell_size = ((width + cols - 1) / cols).max((height + 2 * rows - 1) / (2 * rows));
+        cell_size.max(1)
+    }
+
+    /// Set the terminal title.
+    pub(crate) fn set_title(&self, title: &str) -> Result<()> {
+        if self.is_interactive() {
+            write_out(&format!("\x1b]0;{title}\x07"))?;
+        }
+        Ok(())
+    }
+
+    /// Set the system clipboard using the OSC 52 escape sequence, if supported by the terminal.
+    pub(crate) fn set_clipboard_text(&self, value: &str) -> Result<()> {
+        if self.is_interactive() {
+            write_out(&format!("\x1b]52;c;{}\x07", base64(value.as_bytes())))
+        } else {
+            Err(anyhow!(
+                "clipboard is only available when running in a terminal"
+            ))
+        }
+    }
+
+    /// Write a canvas and text spans to the terminal, unless it's unchanged since the last call.
+    pub(crate) fn present(&mut self, canvas: &Canvas, spans: &[TextSpan]) -> Result<()> {
+        if self.is_interactive() && sys::take_resized() {
+            let size = sys::terminal_size()?;
+            if size != self.size {
+                self.size = size;
+                self.last_frame.clear();
+                write_out("\x1b[2J")?;
+            }
+        }
+
+        let frame = self.render(canvas, spans);
+        if frame != self.last_frame {
+            write_out(&frame)?;
+            self.last_frame = frame;
+        }
+        Ok(())
+    }
+
+    /// Render a canvas and text spans as half-block characters with escape sequences.
+    fn render(&self, canvas: &Canvas, spans: &[TextSpan]) -> String {
+        let (width, height) = canvas.dimensions();
+        let cell_size = self.cell_size(width, height);
+        let cols = ((width + cell_size - 1) / cell_size) as usize;
+        let rows = ((height + 2 * cell_size - 1) / (2 * cell_size)) as usize;
+
+        let mut text = vec![None; cols * rows];
+        for span in spans {
+            for (i, c) in span.text.chars().enumerate() {
+                let (col, row) = (span.col + i as i32, span.row);
+                if col >= 0 && row >= 0 && (col as usize) < cols && (row as usize) < rows {
+                    text[row as usize * cols + col as usize] = Some((c, span.color, span.style));
+                }
+            }
+        }
+
+        let mut frame = String::with_capacity(cols * rows * 20);
+        for row in 0..rows {
+            if self.is_interactive() {
+                let _ = write!(frame, "\x1b[{};1H", row + 1);
+            }
+            let (mut last_fg, mut last_bg) = (None, None);
+            let mut set_colors = |frame: &mut String, fg: [u8; 3], bg: [u8; 3]| {
+                if last_fg != Some(fg) {
+                    let _ = write!(frame, "\x1b[38;2;{};{};{}m", fg[0], fg[1], fg[2]);
+                    last_fg = Some(fg);
+                }
+                if last_bg != Some(bg) {
+                    let _ = write!(frame, "\x1b[48;2;{};{};{}m", bg[0], bg[1], bg[2]);
+                    last_bg = Some(bg);
+                }
+            };
+            for col in 0..cols {
+                let x = (col as u32 * cell_size) as i32;
+                let y = (row as u32 * 2 * cell_size) as i32;
+                let size = cell_size as i32;
+                let top = average(canvas, x, y, size, size);
+                let bottom = average(canvas, x, y + size, size, size);
+                match text[row * cols + col] {
+                    Some((c, color, style)) => {
+                        let bg = mix(top, bottom);
+                        let [red, green, blue, alpha] = color.channels();
+                        let fg = mix_alpha([red, green, blue], bg, alpha);
+                        set_colors(&mut frame, fg, bg);
+                        frame.push_str(&style_codes(style));
+                        frame.push(c);
+                        if style != FontStyle::NORMAL {
+                            frame.push_str("\x1b[22;23;24;29m");
+                        }
+                    }
+                    None => {
+                        set_colors(&mut frame, top, bottom);
+                        frame.push('▀');
+                    }
+                }
+            }
+            frame.push_str("\x1b[0m");
+            if !self.is_interactive() {
+                frame.push('\n');
+            }
+        }
+        frame
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        if let Some(mode) = self.saved_mode.take() {
+            // Reset colors, show the cursor and leave the alternate screen
+            if let Err(err) = write_out("\x1b[0m\x1b[?25h\x1b[?1049l") {
+                log::warn!("failed to restore terminal: {err}");
+            }
+            if let Err(err) = sys::set_mode(&mode) {
+                log::warn!("failed to restore terminal mode: {err}");
+            }
+        }
+    }
+}
+
+/// Returns the terminal size in `(columns, rows)` from the `COLUMNS` and `LINES` environment
+/// variables, if set.
+fn env_size() -> Option<(u32, u32)> {
+    let cols = env::var("COLUMNS").ok()?.parse().ok()?;
+    let rows = env::var("LINES").ok()?.parse().ok()?;
+    (cols > 0 && rows > 0).then_some((cols, rows))
+}
+
+#[cfg(unix)]
+mod sys {
+    use crate::error::Result;
+    use anyhow::{anyhow, Context};
+    use std::{
+        fmt, io, mem,
+        os::raw::c_int,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    /// Terminal attributes of stdin.
+    pub(super) struct Mode(libc::termios);
+
+    impl fmt::Debug for Mode {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Mode").finish_non_exhaustive()
+        }
+    }
+
+    /// Set by the `SIGWINCH` handler when the terminal is resized.
+    static RESIZED: AtomicBool = AtomicBool::new(false);
+
+    /// Returns whether stdin and stdout are both attached to a terminal.
+    pub(super) fn is_terminal() -> bool {
+        // SAFETY: `isatty` only inspects the file descriptors.
+        unsafe { libc::isatty(libc::STDIN_FILENO) == 1 && libc::isatty(libc::STDOUT_FILENO) == 1 }
+    }
+
+    /// Returns the terminal size in `(columns, rows)`.
+    pub(super) fn terminal_size() -> Result<(u32, u32)> {
+        // SAFETY: `winsize` is plain data, which `ioctl` fills in on success.
+        let mut size: libc::winsize = unsafe { mem::zeroed() };
+        if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } != 0 {
+            return Err(io::Error::last_os_error())
+                .context("failed to query terminal size, stdout is not a terminal");
+        }
+        match (u32::from(size.ws_col), u32::from(size.ws_row)) {
+            (cols, rows) if cols > 0 && rows > 0 => Ok((cols, rows)),
+            _ => Err(anyhow!("terminal reported an empty size")),
+        }
+    }
+
+    /// Switch stdin to raw mode without echo, returning the previous mode to restore on exit.
+    pub(super) fn enable_raw_mode() -> Result<Mode> {
+        // SAFETY: `termios` is plain data, which `tcgetattr` fills in on success.
+        let mut mode: libc::termios = unsafe { mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut mode) } != 0 {
+            return Err(io::Error::last_os_error()).context("failed to get terminal mode");
+        }
+        let mut raw = mode;
+        // SAFETY: `raw` is a valid `termios` returned by `tcgetattr`.
+        unsafe { libc::cfmakeraw(&mut raw) };
+        set_mode(&Mode(raw))?;
+        Ok(Mode(mode))
+    }
+
+    /// Set the terminal mode of stdin.
+    pub(super) fn set_mode(mode: &Mode) -> Result<()> {
+        // SAFETY: `mode` is a valid `termios` returned by `tcgetattr`.
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &mode.0) } != 0 {
+            return Err(io::Error::last_os_error()).context("failed to set terminal mode");
+        }
+        Ok(())
+    }
+
+    /// Handle `SIGWINCH` to flag when the terminal is resized.
+    pub(super) fn watch_resize() {
+        extern "C" fn on_resize(_signal: c_int) {
+            RESIZED.store(true, Ordering::Relaxed);
+        }
+        let handler: extern "C" fn(c_int) = on_resize;
+        // SAFETY: The handler only stores to an atomic, which is async-signal-safe.
+        unsafe { libc::signal(libc::SIGWINCH, handler as libc::sighandler_t) };
+    }
+
+    /// Returns whether the terminal has been resized since the last call.
+    pub(super) fn take_resized() -> bool {
+        RESIZED.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    use crate::error::Result;
+    use anyhow::anyhow;
+
+    /// Terminal modes aren't supported, so frames are always written sequentially.
+    #[derive(Debug)]
+    pub(super) struct Mode;
+
+    pub(super) fn is_terminal() -> bool {
+        false
+    }
+
+    pub(super) fn terminal_size() -> Result<(u32, u32)> {
+        Err(anyhow!(
+            "querying the terminal size is only supported on unix"
+        ))
+    }
+
+    pub(super) fn enable_raw_mode() -> Result<Mode> {
+        Err(anyhow!("raw terminal mode is only supported on unix"))
+    }
+
+    pub(super) fn set_mode(_mode: &Mode) -> Result<()> {
+        Ok(())
+    }
+
+    pub(super) fn watch_resize() {}
+
+    pub(super) fn take_resized() -> bool {
+        false
+    }
+}
+
+/// Write to stdout and flush.
+fn write_out(s: &str) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(s.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Returns the average color of a block of canvas pixels, composited over black.
+fn average(canvas: &Canvas, x: i32, y: i32, width: i32, height: i32) -> [u8; 3] {
+    let mut sum = [0u32; 3];
+    let mut count = 0;
+    for y in y..y + height {
+        for x in x..x + width {
+            if let Some([red, green, blue, alpha]) = canvas.get(x, y) {
+                let alpha = u32::from(alpha);
+                sum[0] += u32::from(red) * alpha / 255;
+                sum[1] += u32::from(green) * alpha / 255;
+                sum[2] += u32::from(blue) * alpha / 255;
+                count += 1;
+            }
+        }
+    }
+    if count == 0 {
+        return [0; 3];
+    }
+    sum.map(|channel| (channel / count) as u8)
+}
+
+/// Returns the average of two colors.
+fn mix(a: [u8; 3], b: [u8; 3]) -> [u8; 3] {
+    [0, 1, 2].map(|i| ((u16::from(a[i]) + u16::from(b[i])) / 2) as u8)
+}
+
+/// Returns `src` composited over `dst` with the given `alpha`.
+fn mix_alpha(src: [u8; 3], dst: [u8; 3], alpha: u8) -> [u8; 3] {
+    let alpha = u16::from(alpha);
+    [0, 1, 2].map(|i| ((u16::from(src[i]) * alpha + u16::from(dst[i]) * (255 - alpha)) / 255) as u8)
+}
+
+/// Returns the escape sequence to enable a [`FontStyle`].
+fn style_codes(style: FontStyle) -> String {
+    let mut codes = String::new();
+    for (flag, code) in [
+        (FontStyle::BOLD, "\x1b[1m"),
+        (FontStyle::ITALIC, "\x1b[3m"),
+        (FontStyle::UNDERLINE, "\x1b[4m"),
+        (FontStyle::STRIKETHROUGH, "\x1b[9m"),
+    ] {
+        if style.contains(flag) {
+            codes.push_str(code);
+        }
+    }
+    codes
+}
+
+/// Encode bytes as standard base64.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+impl WindowRenderer for Renderer {
+    /// Get the count of open windows. The terminal renderer only supports a single window.
+    fn window_count(&self) -> usize {
+        1
+    }
+
+    /// Get the primary window ID.
+    #[inline]
+    fn primary_window_id(&self) -> WindowId {
+        WINDOW_ID
+    }
+
+    /// Get the current window target ID.
+    #[inline]
+    fn window_id(&self) -> WindowId {
+        WINDOW_ID
+    }
+
+    /// Create a new window. The terminal renderer only supports a single window.
+    fn create_window(&mut self, _s: &mut RendererSettings) -> Result<WindowId> {
+        Err(anyhow!(
+            "multiple windows are not supported by the terminal renderer"
+        ))
+    }
+
+    /// Close a window.
+    fn close_window(&mut self, id: WindowId) -> Result<()> {
+        Err(Error::InvalidWindow(id).into())
+    }
+
+    /// Set the mouse cursor. The terminal renderer has no mouse cursor, so this has no effect.
+    fn cursor(&mut self, _cursor: Option<&Cursor>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns a single event or None if there are no pending events.
+    #[inline]
+    fn poll_event(&mut self) -> Option<Event> {
+        self.input.poll_event()
+    }
+
+    /// Get the current window title.
+    #[inline]
+    fn title(&self) -> &str {
+        &self.settings.title
+    }
+
+    /// Set the current window title.
+    #[inline]
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        self.settings.title.replace_range(.., title);
+        self.terminal.set_title(title)
+    }
+
+    #[inline]
+    fn set_fps(&mut self, fps: f32) -> Result<()> {
+        self.title.clear();
+        write!(self.title, "{} - FPS: {:.02}", &self.settings.title, fps)
+            .context("invalid title")?;
+        self.terminal.set_title(&self.title)
+    }
+
+    /// Dimensions of the current render target as `(width, height)`.
+    #[inline]
+    fn dimensions(&self) -> Result<(u32, u32)> {
+        self.texture_target.map_or_else(
+            || Ok(self.canvas.dimensions()),
+            |texture_id| self.texture_dimensions(texture_id),
+        )
+    }
+
+    /// Dimensions of the current window target as `(width, height)`.
+    #[inline]
+    fn window_dimensions(&self) -> Result<(u32, u32)> {
+        Ok((self.settings.width, self.settings.height))
+    }
+
+    /// Position of the current window target as `(x, y)`.
+    #[inline]
+    fn window_position(&self) -> Result<(i32, i32)> {
+        Ok((0, 0))
+    }
+
+    /// Set dimensions of the current window target as `(width, height)`.
+    #[inline]
+    fn set_window_dimensions(&mut self, (width, height): (u32, u32)) -> Result<()> {
+        self.settings.width = width;
+        self.settings.height = height;
+        if self.settings.logical_size.is_none() {
+            self.canvas = Canvas::new(width, height);
+            self.canvas.clear(Color::BLACK);
+            self.spans.clear();
+        }
+        Ok(())
+    }
+
+    /// Returns the rendering viewport of the current render target.
+    #[inline]
+    fn viewport(&self) -> Result<Rect<i32>> {
+        let state = if self.texture_target.is_some() {
+            self.target_state
+        } else {
+            self.window_state
+        };
+        match state.viewport {
+            Some(viewport) => Ok(viewport),
+            None => {
+                let (width, height) = self.dimensions()?;
+                Ok(rect![0, 0, width as i32, height as i32])
+            }
+        }
+    }
+
+    /// Set the rendering viewport of the current render target.
+    #[inline]
+    fn set_viewport(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
+        self.state_mut().viewport = rect;
+        Ok(())
+    }
+
+    /// Dimensions of the terminal in half-block pixels as `(width, height)`.
+    #[inline]
+    fn display_dimensions(&self) -> Result<(u32, u32)> {
+        let (cols, rows) = self.terminal.size;
+        Ok((cols, rows * 2))
+    }
+
+    /// Returns whether the application is fullscreen or not. The terminal renderer is never
+    /// fullscreen.
+    #[inline]
+    fn fullscreen(&self) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Set the application to fullscreen or not. This has no effect for the terminal renderer.
+    #[inline]
+    fn set_fullscreen(&mut self, _val: bool) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns whether the window synchronizes frame rate to the screens refresh rate.
+    #[inline]
+    fn vsync(&self) -> bool {
+        self.settings.vsync
+    }
+
+    /// Set the window to synchronize frame rate to the screens refresh rate. This has no effect
+    /// for the terminal renderer, so the current `WindowId` is always returned.
+    fn set_vsync(&mut self, val: bool) -> Result<WindowId> {
+        self.settings.vsync = val;
+        Ok(WINDOW_ID)
+    }
+
+    /// Set window as the target for drawing operations.
+    #[inline]
+    fn set_window_target(&mut self, id: WindowId) -> Result<()> {
+        if id == WINDOW_ID {
+            Ok(())
+        } else {
+            Err(Error::InvalidWindow(id).into())
+        }
+    }
+
+    /// Reset main window as the target for drawing operations.
+    #[inline]
+    fn reset_window_target(&mut self) {}
+
+    /// Show the current window target.
+    #[inline]
+    fn show(&mut self) -> Result<()> {
+        self.hidden = false;
+        Ok(())
+    }
+
+    /// Hide the current window target.
+    #[inline]
+    fn hide(&mut self) -> Result<()> {
+        self.hidden = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_encodes_with_padding() {
+        assert_eq!(base64(b"pix"), "cGl4");
+        assert_eq!(base64(b"pi"), "cGk=");
+        assert_eq!(base64(b"p"), "cA==");
+    }
+}
@@ -52,6 +52,14 @@ impl Rendering for Renderer {
         todo!()
     }
 
+    fn text_align(&mut self, align: crate::prelude::TextAlign) {
+        todo!()
+    }
+
+    fn font_ascent(&self) -> crate::prelude::Result<u32> {
+        todo!()
+    }
+
     fn clipboard_text(&self) -> String {
         todo!()
     }
@@ -86,6 +94,14 @@ impl Rendering for Renderer {
         todo!()
     }
 
+    fn layout_text(
+        &self,
+        text: &str,
+        wrap_width: Option<u32>,
+    ) -> crate::prelude::Result<crate::prelude::TextLayout> {
+        todo!()
+    }
+
     fn point(
         &mut self,
         p: crate::prelude::Point<i32>,
@@ -195,7 +211,7 @@ impl Rendering for Renderer {
         todo!()
     }
 
-    fn to_bytes(&mut self) -> crate::prelude::Result<Vec<u8>> {
+    fn read_pixels(&mut self) -> crate::prelude::Result<((u32, u32), Vec<u8>)> {
         todo!()
     }
 
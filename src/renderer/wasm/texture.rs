@@ -41,6 +41,53 @@ impl TextureRenderer for Renderer {
         todo!()
     }
 
+    fn set_texture_scale_quality(
+        &mut self,
+        texture_id: crate::prelude::TextureId,
+        quality: crate::prelude::ScaleQuality,
+    ) -> crate::prelude::Result<()> {
+        todo!()
+    }
+
+    fn set_texture_color_mod(
+        &mut self,
+        texture_id: crate::prelude::TextureId,
+        color: Option<crate::prelude::Color>,
+    ) -> crate::prelude::Result<()> {
+        todo!()
+    }
+
+    fn set_texture_alpha_mod(
+        &mut self,
+        texture_id: crate::prelude::TextureId,
+        alpha: Option<u8>,
+    ) -> crate::prelude::Result<()> {
+        todo!()
+    }
+
+    fn set_texture_blend_mode(
+        &mut self,
+        texture_id: crate::prelude::TextureId,
+        mode: Option<crate::prelude::BlendMode>,
+    ) -> crate::prelude::Result<()> {
+        todo!()
+    }
+
+    fn texture_dimensions(
+        &self,
+        texture_id: crate::prelude::TextureId,
+    ) -> crate::prelude::Result<(u32, u32)> {
+        todo!()
+    }
+
+    fn mask_texture(
+        &mut self,
+        texture_id: crate::prelude::TextureId,
+        mask_id: crate::prelude::TextureId,
+    ) -> crate::prelude::Result<()> {
+        todo!()
+    }
+
     fn texture_target(&self) -> Option<crate::prelude::TextureId> {
         todo!()
     }
@@ -53,6 +100,17 @@ impl TextureRenderer for Renderer {
         todo!()
     }
 
+    fn push_texture_target(
+        &mut self,
+        texture_id: crate::prelude::TextureId,
+    ) -> crate::prelude::PixResult<()> {
+        todo!()
+    }
+
+    fn pop_texture_target(&mut self) {
+        todo!()
+    }
+
     fn has_texture_target(&self) -> bool {
         todo!()
     }
@@ -6,6 +6,7 @@
 //!
 //! - [Contains]: Defines [`contains`] for shapes containing other shapes.
 //! - [Intersects]: Defines [`intersects`] for shapes intersecting other shapes.
+//! - [Collide]: Defines [`collide`] for shapes colliding with other shapes.
 //!
 //! Provided [`PixState`] methods;
 //!
@@ -17,7 +18,7 @@
 //! - [`PixState::rect`]: Draw a [Rect] to the current canvas.
 //! - [`PixState::rounded_rect`]: Draw a [Rect] with rounded corners to the current canvas.
 //! - [`PixState::quad`]: Draw a [Quad] to the current canvas.
-//! - [`PixState::polygon`]: Draw a polygon defined by a set of [Point]s to the current canvas.
+//! - [`PixState::polygon`]: Draw a [Polygon] defined by a set of [Point]s to the current canvas.
 //! - [`PixState::wireframe`]: Draw a wireframe defined by a set vertexes to the current canvas.
 //! - [`PixState::circle`]: Draw a circle [Ellipse] to the current canvas.
 //! - [`PixState::ellipse`]: Draw an [Ellipse] to the current canvas.
@@ -25,25 +26,37 @@
 //!
 //! [`contains`]: Contains::contains
 //! [`intersects`]: Intersects::intersects
+//! [`collide`]: Collide::collide
 
-use crate::{prelude::*, renderer::Rendering};
+use crate::{
+    camera::{ellipse_points, transform_point, transform_stroke_weight},
+    prelude::*,
+};
 use std::iter::Iterator;
 
+pub mod collide;
 #[macro_use]
 pub mod ellipse;
+mod geometry;
 #[macro_use]
 pub mod line;
+mod overlap;
 #[macro_use]
 pub mod point;
 #[macro_use]
 pub mod rect;
 #[macro_use]
+pub mod polygon;
+pub mod polygon_set;
+#[macro_use]
 pub mod quad;
 #[macro_use]
 pub mod sphere;
 #[macro_use]
 pub mod triangle;
 
+#[doc(inline)]
+pub use collide::*;
 #[doc(inline)]
 pub use ellipse::*;
 #[doc(inline)]
@@ -51,6 +64,10 @@ pub use line::*;
 #[doc(inline)]
 pub use point::*;
 #[doc(inline)]
+pub use polygon::*;
+#[doc(inline)]
+pub use polygon_set::*;
+#[doc(inline)]
 pub use quad::*;
 #[doc(inline)]
 pub use rect::*;
@@ -74,6 +91,13 @@ pub trait Intersects<S> {
     fn intersects(&self, shape: S) -> Option<Self::Result>;
 }
 
+/// Trait for shape collision operations.
+pub trait Collide<S> {
+    /// Returns a [Manifold] describing the collision with another shape or `None` if the shapes do
+    /// not overlap.
+    fn collide(&self, shape: S) -> Option<Manifold>;
+}
+
 impl PixState {
     /// Draw a [Point] to the current canvas. [`PixState::stroke`] controls whether the point is
     /// drawn or not. [`PixState::stroke_weight`] and [`PixState::fill`] have no effect.
@@ -100,7 +124,8 @@ impl PixState {
         P: Into<Point<i32>>,
     {
         if let Some(stroke) = self.settings.stroke {
-            self.renderer.point(p.into(), stroke)?;
+            let p = self.world_to_screen(p);
+            self.draw_command(DrawCommand::Point { p, color: stroke })?;
         }
         Ok(())
     }
@@ -132,8 +157,15 @@ impl PixState {
     {
         let s = &self.settings;
         if let Some(stroke) = s.stroke {
-            self.renderer
-                .line(line.into(), s.smooth, s.stroke_weight as u8, stroke)?;
+            let line: Line<i32> = line.into();
+            let line = Line::from(self.camera_points(*line));
+            let width = transform_stroke_weight(s.camera, s.stroke_weight);
+            self.draw_command(DrawCommand::Line {
+                line,
+                smooth: s.smooth,
+                width,
+                color: stroke,
+            })?;
         }
         Ok(())
     }
@@ -168,11 +200,15 @@ impl PixState {
         I: IntoIterator<Item = P>,
     {
         let s = &self.settings;
-        self.renderer.bezier(
-            points.into_iter().map(Into::into),
-            s.bezier_detail,
-            s.stroke,
-        )
+        let camera = s.camera;
+        self.draw_command(DrawCommand::Bezier {
+            points: points
+                .into_iter()
+                .map(|p| transform_point(camera, p.into()))
+                .collect(),
+            detail: s.bezier_detail,
+            stroke: s.stroke,
+        })
     }
 
     /// Draw a [Triangle][Tri] to the current canvas. [`PixState::fill`] and [`PixState::stroke`]
@@ -201,8 +237,14 @@ impl PixState {
         T: Into<Tri<i32>>,
     {
         let s = &self.settings;
-        self.renderer
-            .triangle(tri.into(), s.smooth, s.fill, s.stroke)
+        let tri: Tri<i32> = tri.into();
+        let tri = Tri::from(self.camera_points(*tri));
+        self.draw_command(DrawCommand::Triangle {
+            tri,
+            smooth: s.smooth,
+            fill: s.fill,
+            stroke: s.stroke,
+        })
     }
 
     /// Draw a square [Rect] to the current canvas. [`PixState::fill`] and [`PixState::stroke`] control
@@ -295,9 +337,8 @@ impl PixState {
     where
         R: Into<Rect<i32>>,
     {
-        let s = &self.settings;
         let rect = self.get_rect(rect);
-        self.renderer.rect(rect, None, s.fill, s.stroke)
+        self.draw_rect(rect, None)
     }
 
     /// Draw a rounded [Rectangle](Rect) to the current canvas. [`PixState::fill`] and
@@ -326,9 +367,8 @@ impl PixState {
     where
         R: Into<Rect<i32>>,
     {
-        let s = &self.settings;
         let rect = self.get_rect(rect);
-        self.renderer.rect(rect, Some(radius), s.fill, s.stroke)
+        self.draw_rect(rect, Some(radius))
     }
 
     /// Draw a [Quadrilateral](Quad) to the current canvas. [`PixState::fill`] and
@@ -358,7 +398,14 @@ impl PixState {
         Q: Into<Quad<i32>>,
     {
         let s = &self.settings;
-        self.renderer.quad(quad.into(), s.smooth, s.fill, s.stroke)
+        let quad: Quad<i32> = quad.into();
+        let quad = Quad::from(self.camera_points(*quad));
+        self.draw_command(DrawCommand::Quad {
+            quad,
+            smooth: s.smooth,
+            fill: s.fill,
+            stroke: s.stroke,
+        })
     }
 
     /// Draw a polygon to the current canvas. [`PixState::fill`] and [`PixState::stroke`] control
@@ -388,12 +435,16 @@ impl PixState {
         I: IntoIterator<Item = P>,
     {
         let s = &self.settings;
-        self.renderer.polygon(
-            points.into_iter().map(Into::into),
-            s.smooth,
-            s.fill,
-            s.stroke,
-        )
+        let camera = s.camera;
+        self.draw_command(DrawCommand::Polygon {
+            points: points
+                .into_iter()
+                .map(|p| transform_point(camera, p.into()))
+                .collect(),
+            smooth: s.smooth,
+            fill: s.fill,
+            stroke: s.stroke,
+        })
     }
 
     /// Draw a wireframe to the current canvas, translated to a given [Point] and optionally
@@ -518,7 +569,28 @@ impl PixState {
     {
         let s = &self.settings;
         let ellipse = self.get_ellipse(ellipse);
-        self.renderer.ellipse(ellipse, s.smooth, s.fill, s.stroke)
+        let ellipse = match s.camera {
+            Some(camera) if !camera.is_axis_aligned() && ellipse.width() != ellipse.height() => {
+                // Rotated ellipses can't be drawn directly, so approximate with a polygon
+                return self.polygon(ellipse_points(ellipse));
+            }
+            Some(camera) => {
+                let center = camera.transform_point(ellipse.center());
+                Ellipse::new(
+                    center.x(),
+                    center.y(),
+                    camera.transform_length(ellipse.width()),
+                    camera.transform_length(ellipse.height()),
+                )
+            }
+            None => ellipse,
+        };
+        self.draw_command(DrawCommand::Ellipse {
+            ellipse,
+            smooth: s.smooth,
+            fill: s.fill,
+            stroke: s.stroke,
+        })
     }
 
     /// Draw an arc of a given `radius` and length defined by `start` and `end` to the current
@@ -549,8 +621,59 @@ impl PixState {
         P: Into<Point<i32>>,
     {
         let s = &self.settings;
-        let p = p.into();
-        self.renderer
-            .arc(p, radius, start, end, s.arc_mode, s.fill, s.stroke)
+        let p = self.world_to_screen(p);
+        let radius = self.camera_length(radius);
+        let rotation = s
+            .camera
+            .map_or(0, |camera| camera.rotation().round() as i32);
+        self.draw_command(DrawCommand::Arc {
+            p,
+            radius,
+            start: start - rotation,
+            end: end - rotation,
+            mode: s.arc_mode,
+            fill: s.fill,
+            stroke: s.stroke,
+        })
+    }
+}
+
+impl PixState {
+    /// Draw a [Rect] transformed by the current [`Camera2D`], if set. Rotated rects are drawn as a
+    /// [Quad] without rounded corners.
+    fn draw_rect(&mut self, rect: Rect<i32>, radius: Option<i32>) -> PixResult<()> {
+        let s = &self.settings;
+        match s.camera {
+            Some(camera) if !camera.is_axis_aligned() => {
+                let quad = Quad::from(self.camera_points([
+                    rect.top_left(),
+                    rect.top_right(),
+                    rect.bottom_right(),
+                    rect.bottom_left(),
+                ]));
+                self.draw_command(DrawCommand::Quad {
+                    quad,
+                    smooth: s.smooth,
+                    fill: s.fill,
+                    stroke: s.stroke,
+                })
+            }
+            Some(camera) => {
+                let rect = camera.transform_rect(rect);
+                let radius = radius.map(|radius| camera.transform_length(radius));
+                self.draw_command(DrawCommand::Rect {
+                    rect,
+                    radius,
+                    fill: s.fill,
+                    stroke: s.stroke,
+                })
+            }
+            None => self.draw_command(DrawCommand::Rect {
+                rect,
+                radius,
+                fill: s.fill,
+                stroke: s.stroke,
+            }),
+        }
     }
 }
@@ -0,0 +1,410 @@
+//! Collision manifolds between 2D shapes.
+//!
+//! [Collide] goes beyond [Intersects] by returning a [Manifold] describing how two overlapping
+//! shapes collide, so the overlap can be resolved instead of only detected. Collisions are found
+//! using the separating axis theorem, so [Quad]s are expected to be convex and [Polygon]s collide
+//! using their [convex hull](Polygon::convex_hull). [Ellipse]s are treated as circles when their
+//! width and height are equal and approximated by a polygon otherwise.
+//!
+//! # Example
+//!
+//! ```
+//! use pix_engine::prelude::*;
+//!
+//! let wall = rect!(15.0, 0.0, 20.0, 100.0);
+//! let mut player = rect!(0.0, 40.0, 20.0, 20.0);
+//! if let Some(manifold) = player.collide(wall) {
+//!     // Push the player back out of the wall
+//!     player = player.offset(-manifold.translation());
+//! }
+//! assert_eq!(player, rect!(-5.0, 40.0, 20.0, 20.0));
+//! ```
+
+use super::{
+    geometry::{edges, signed_area, EPSILON},
+    overlap::ellipse_polygon,
+};
+use crate::prelude::*;
+use num_traits::AsPrimitive;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Collision information between two overlapping shapes.
+///
+/// Please see the [module-level documentation] for examples.
+///
+/// [module-level documentation]: crate::shape::collide
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Manifold {
+    normal: Vector<f64>,
+    depth: f64,
+    contacts: Vec<Point<f64>>,
+}
+
+impl Manifold {
+    /// Returns the unit collision normal pointing from the first shape towards the second shape.
+    #[inline]
+    pub fn normal(&self) -> Vector<f64> {
+        self.normal
+    }
+
+    /// Returns the penetration depth of the shapes along the collision [normal](Self::normal).
+    #[inline]
+    #[must_use]
+    pub fn depth(&self) -> f64 {
+        self.depth
+    }
+
+    /// Returns the points where the shapes are in contact.
+    #[inline]
+    pub fn contacts(&self) -> &[Point<f64>] {
+        &self.contacts
+    }
+
+    /// Returns the minimum translation to move the second shape by to separate it from the first
+    /// shape. Moving the first shape by the negated translation also separates them.
+    #[inline]
+    pub fn translation(&self) -> Vector<f64> {
+        self.normal * self.depth
+    }
+
+    /// Returns the manifold with the order of the colliding shapes swapped.
+    fn flipped(self) -> Self {
+        Self {
+            normal: -self.normal,
+            ..self
+        }
+    }
+}
+
+/// A convex 2D shape in `f64` coordinates.
+#[derive(Debug, Clone, PartialEq)]
+enum Convex {
+    /// A convex polygon with points wound clockwise on screen.
+    Polygon(Vec<Point<f64>>),
+    /// A circle with a center and radius.
+    Circle(Point<f64>, f64),
+}
+
+impl Convex {
+    /// Constructs a convex polygon, reversing the points if they are wound counter-clockwise on
+    /// screen.
+    fn polygon(mut points: Vec<Point<f64>>) -> Self {
+        if signed_area(&points) < 0.0 {
+            points.reverse();
+        }
+        Self::Polygon(points)
+    }
+
+    /// Returns the range of the shape projected onto an axis.
+    fn project(&self, axis: Vector<f64>) -> (f64, f64) {
+        match self {
+            Self::Polygon(points) => points
+                .iter()
+                .map(|&p| axis.dot(p))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), d| {
+                    (min.min(d), max.max(d))
+                }),
+            Self::Circle(c, r) => {
+                let d = axis.dot(*c);
+                (d - r, d + r)
+            }
+        }
+    }
+}
+
+/// Trait for converting shapes to [Convex] geometry.
+trait AsConvex {
+    /// Returns the shape as [Convex] geometry.
+    fn as_convex(&self) -> Convex;
+}
+
+impl<T: Num + AsPrimitive<f64>> AsConvex for Rect<T> {
+    fn as_convex(&self) -> Convex {
+        let rect: Rect<f64> = self.as_();
+        Convex::polygon(vec![
+            rect.top_left(),
+            rect.top_right(),
+            rect.bottom_right(),
+            rect.bottom_left(),
+        ])
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsConvex for Ellipse<T> {
+    fn as_convex(&self) -> Convex {
+        let ellipse: Ellipse<f64> = self.as_();
+        let center = point![ellipse.x(), ellipse.y()];
+        let rx = ellipse.width().abs() / 2.0;
+        let ry = ellipse.height().abs() / 2.0;
+        if (rx - ry).abs() <= EPSILON {
+            Convex::Circle(center, rx)
+        } else {
+            Convex::polygon(ellipse_polygon(center, rx, ry))
+        }
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsConvex for Tri<T> {
+    fn as_convex(&self) -> Convex {
+        Convex::polygon(self.as_().points().to_vec())
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsConvex for Quad<T> {
+    fn as_convex(&self) -> Convex {
+        Convex::polygon(self.as_().points().to_vec())
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsConvex for Polygon<T> {
+    fn as_convex(&self) -> Convex {
+        Convex::polygon(self.convex_hull().as_().to_vec())
+    }
+}
+
+/// Returns the [Manifold] for the collision between shape `a` and shape `b`.
+fn collide(a: &Convex, b: &Convex) -> Option<Manifold> {
+    match (a, b) {
+        (Convex::Circle(c1, r1), Convex::Circle(c2, r2)) => collide_circles(*c1, *r1, *c2, *r2),
+        (Convex::Polygon(points), Convex::Circle(c, r)) => {
+            let (normal, depth) = separating_axis(a, b, circle_axes(points, *c))?;
+            let contact = point![c.x() - normal.x() * r, c.y() - normal.y() * r];
+            Some(Manifold {
+                normal,
+                depth,
+                contacts: vec![contact],
+            })
+        }
+        (Convex::Circle(..), Convex::Polygon(..)) => collide(b, a).map(Manifold::flipped),
+        (Convex::Polygon(ps), Convex::Polygon(qs)) => {
+            let axes: Vec<_> = edge_normals(ps).chain(edge_normals(qs)).collect();
+            let (normal, depth) = separating_axis(a, b, axes)?;
+            Some(Manifold {
+                normal,
+                depth,
+                contacts: polygon_contacts(ps, qs, normal),
+            })
+        }
+    }
+}
+
+/// Returns the [Manifold] for the collision between two circles.
+fn collide_circles(c1: Point<f64>, r1: f64, c2: Point<f64>, r2: f64) -> Option<Manifold> {
+    let offset = vector![c2.x() - c1.x(), c2.y() - c1.y()];
+    let dist = offset.mag();
+    let depth = r1 + r2 - dist;
+    if depth < 0.0 {
+        return None;
+    }
+    // Concentric circles can be separated in any direction
+    let normal = if dist > 0.0 {
+        offset / dist
+    } else {
+        vector![1.0, 0.0]
+    };
+    let r = r1 - depth / 2.0;
+    Some(Manifold {
+        normal,
+        depth,
+        contacts: vec![point![c1.x() + normal.x() * r, c1.y() + normal.y() * r]],
+    })
+}
+
+/// Returns the axis of least penetration between two shapes pointing from shape `a` towards
+/// shape `b` and the penetration depth along it, or `None` if any axis separates the shapes.
+fn separating_axis<I>(a: &Convex, b: &Convex, axes: I) -> Option<(Vector<f64>, f64)>
+where
+    I: IntoIterator<Item = Vector<f64>>,
+{
+    let mut best: Option<(Vector<f64>, f64)> = None;
+    for axis in axes {
+        let (min_a, max_a) = a.project(axis);
+        let (min_b, max_b) = b.project(axis);
+        let (forward, backward) = (max_a - min_b, max_b - min_a);
+        let depth = forward.min(backward);
+        if depth < 0.0 {
+            return None;
+        }
+        if best.map_or(true, |(_, best_depth)| depth < best_depth) {
+            let normal = if forward <= backward { axis } else { -axis };
+            best = Some((normal, depth));
+        }
+    }
+    best
+}
+
+/// Returns the axes to test between a polygon and a circle, which are the polygon edge normals
+/// and the axis from the closest polygon vertex to the circle center.
+fn circle_axes(points: &[Point<f64>], c: Point<f64>) -> Vec<Vector<f64>> {
+    let closest = points.iter().copied().min_by(|p1, p2| {
+        p1.dist(c)
+            .partial_cmp(&p2.dist(c))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let vertex_axis = closest.and_then(|p| normalized(c.x() - p.x(), c.y() - p.y()));
+    edge_normals(points).chain(vertex_axis).collect()
+}
+
+/// Returns the contact points between two colliding convex polygons by clipping the incident
+/// edge of one polygon against the reference edge of the other.
+fn polygon_contacts(a: &[Point<f64>], b: &[Point<f64>], normal: Vector<f64>) -> Vec<Point<f64>> {
+    let (edge_a, alignment_a) = facing_edge(a, normal);
+    let (edge_b, alignment_b) = facing_edge(b, -normal);
+    // The reference edge is the one most perpendicular to the collision normal
+    let ((r1, r2), (i1, i2)) = if alignment_a >= alignment_b {
+        (edge_a, edge_b)
+    } else {
+        (edge_b, edge_a)
+    };
+    let contacts = normalized(r2.x() - r1.x(), r2.y() - r1.y()).map(|tangent| {
+        let clipped = clip(&[i1, i2], tangent, tangent.dot(r1));
+        let clipped = clip(&clipped, -tangent, -tangent.dot(r2));
+        let ref_normal = vector![tangent.y(), -tangent.x()];
+        let face = ref_normal.dot(r1);
+        clipped
+            .into_iter()
+            .filter(|&p| ref_normal.dot(p) <= face + EPSILON)
+            .collect::<Vec<_>>()
+    });
+    match contacts {
+        Some(contacts) if !contacts.is_empty() => contacts,
+        // Fall back to the deepest point of the second polygon
+        _ => b
+            .iter()
+            .copied()
+            .min_by(|p1, p2| {
+                normal
+                    .dot(*p1)
+                    .partial_cmp(&normal.dot(*p2))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Returns the edge of a polygon whose outward normal is most aligned with a direction and how
+/// closely they are aligned.
+fn facing_edge(points: &[Point<f64>], direction: Vector<f64>) -> ((Point<f64>, Point<f64>), f64) {
+    edges(points)
+        .filter_map(|(p1, p2)| {
+            let normal = outward_normal(p1, p2)?;
+            Some(((p1, p2), normal.dot(direction)))
+        })
+        .fold(
+            ((Point::origin(), Point::origin()), f64::NEG_INFINITY),
+            |best, edge| if edge.1 > best.1 { edge } else { best },
+        )
+}
+
+/// Returns the points of a segment on the side of a plane facing `direction`, clipped to where
+/// the segment crosses the plane.
+fn clip(points: &[Point<f64>], direction: Vector<f64>, offset: f64) -> Vec<Point<f64>> {
+    let dists: Vec<_> = points.iter().map(|&p| direction.dot(p) - offset).collect();
+    let mut clipped: Vec<_> = points
+        .iter()
+        .zip(&dists)
+        .filter(|&(_, &d)| d >= 0.0)
+        .map(|(&p, _)| p)
+        .collect();
+    if let ([p1, p2], [d1, d2]) = (points, dists.as_slice()) {
+        if d1 * d2 < 0.0 {
+            let t = d1 / (d1 - d2);
+            clipped.push(point![
+                p1.x() + (p2.x() - p1.x()) * t,
+                p1.y() + (p2.y() - p1.y()) * t
+            ]);
+        }
+    }
+    clipped
+}
+
+/// Returns the unit outward normals of the edges of a polygon wound clockwise on screen.
+fn edge_normals(points: &[Point<f64>]) -> impl Iterator<Item = Vector<f64>> + '_ {
+    edges(points).filter_map(|(p1, p2)| outward_normal(p1, p2))
+}
+
+/// Returns the unit outward normal of an edge of a polygon wound clockwise on screen or `None` if
+/// the edge has no length.
+fn outward_normal(p1: Point<f64>, p2: Point<f64>) -> Option<Vector<f64>> {
+    normalized(p2.y() - p1.y(), p1.x() - p2.x())
+}
+
+/// Returns a unit vector in the direction of `(x, y)` or `None` if it has no length.
+fn normalized(x: f64, y: f64) -> Option<Vector<f64>> {
+    let mag = x.hypot(y);
+    if mag > EPSILON {
+        Some(vector![x / mag, y / mag])
+    } else {
+        None
+    }
+}
+
+macro_rules! impl_collide {
+    ($($Shape:ident),+) => {
+        impl_collide!(@ [$($Shape),+] [$($Shape),+]);
+    };
+    (@ [$($Shape:ident),+] $Others:tt) => {$(
+        impl_collide!(@@ $Shape $Others);
+    )+};
+    (@@ $Shape:ident [$($Other:ident),+]) => {$(
+        impl<T: Num + AsPrimitive<f64>> Collide<$Other<T>> for $Shape<T> {
+            #[doc = concat!("Returns a [Manifold] describing the collision with a given [", stringify!($Other), "]")]
+            /// or `None` if the shapes do not overlap.
+            fn collide(&self, shape: $Other<T>) -> Option<Manifold> {
+                collide(&self.as_convex(), &shape.as_convex())
+            }
+        }
+    )+};
+}
+
+impl_collide!(Rect, Ellipse, Tri, Quad, Polygon);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifold<const N: usize>(normal: [f64; 2], depth: f64, contacts: [[f64; 2]; N]) -> Manifold {
+        Manifold {
+            normal: normal.into(),
+            depth,
+            contacts: contacts.into_iter().map(Point::from).collect(),
+        }
+    }
+
+    #[test]
+    fn test_collide_polygons() {
+        assert_eq!(
+            rect!(0, 0, 10, 10).collide(rect!(8, 5, 10, 10)),
+            Some(manifold([1.0, 0.0], 2.0, [[8.0, 5.0], [8.0, 10.0]]))
+        );
+        assert_eq!(
+            tri!([0, 0], [20, 0], [10, 10]).collide(rect!(8, 8, 4, 10)),
+            Some(manifold([0.0, 1.0], 2.0, [[10.0, 10.0], [12.0, 8.0]]))
+        );
+        assert_eq!(
+            rect!(0, 0, 10, 10).collide(tri!([11, 0], [20, 0], [20, 10])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_collide_circles() {
+        assert_eq!(
+            rect!(0, 0, 10, 10).collide(circle!(12, 5, 3)),
+            Some(manifold([1.0, 0.0], 1.0, [[9.0, 5.0]]))
+        );
+        assert_eq!(
+            circle!(12, 5, 3).collide(rect!(0, 0, 10, 10)),
+            Some(manifold([-1.0, 0.0], 1.0, [[9.0, 5.0]]))
+        );
+        assert_eq!(
+            circle!(0, 0, 10).collide(circle!(0, 15, 10)),
+            Some(manifold([0.0, 1.0], 5.0, [[0.0, 7.5]]))
+        );
+        assert_eq!(circle!(0, 0, 10).collide(circle!(30, 0, 10)), None);
+    }
+}
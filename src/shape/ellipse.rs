@@ -416,29 +416,6 @@ impl<T: Num> Ellipse<T> {
     }
 }
 
-impl<T: Num> Contains<Point<T>> for Ellipse<T> {
-    /// Returns whether this ellipse contains a given [Point].
-    fn contains(&self, p: Point<T>) -> bool {
-        let px = p.x() - self.x();
-        let py = p.y() - self.y();
-        let two = T::one() + T::one();
-        let rx = self.width() / two;
-        let ry = self.height() / two;
-        (px * px) / (rx * rx) + (py * py) / (ry * ry) <= T::one()
-    }
-}
-
-impl<T: Num> Contains<Ellipse<T>> for Ellipse<T> {
-    /// Returns whether this ellipse completely contains another ellipse.
-    fn contains(&self, ellipse: Ellipse<T>) -> bool {
-        let px = self.x() - ellipse.x();
-        let py = self.y() - ellipse.y();
-        let rx = self.width() + ellipse.width();
-        let ry = self.height() + ellipse.height();
-        (px * px) / (rx * rx) + (py * py) / (ry * ry) <= T::one()
-    }
-}
-
 impl Draw for Ellipse<i32> {
     /// Draw `Ellipse` to the current [`PixState`] canvas.
     fn draw(&self, s: &mut PixState) -> Result<()> {
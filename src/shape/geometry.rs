@@ -0,0 +1,63 @@
+//! Geometry helpers shared between 2D shape implementations.
+//!
+//! Orientations are described on screen, where the y-axis points down, so a positive [`cross`]
+//! product or [`signed_area`] means clockwise.
+
+use crate::prelude::*;
+
+/// Tolerance used when comparing floating-point values.
+pub(super) const EPSILON: f64 = 1e-9;
+
+/// Returns the smaller of two partially ordered values.
+pub(super) fn partial_min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+/// Returns the larger of two partially ordered values.
+pub(super) fn partial_max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+/// Returns the edges of a closed polygon as pairs of points.
+pub(super) fn edges<T: Copy>(
+    points: &[Point<T>],
+) -> impl Iterator<Item = (Point<T>, Point<T>)> + '_ {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(&p1, &p2)| (p1, p2))
+}
+
+/// Returns the cross product of the vectors from `o` to `a` and from `o` to `b`, which is
+/// positive if `o`, `a` and `b` turn clockwise on screen and zero if they are collinear.
+pub(super) fn cross(o: Point<f64>, a: Point<f64>, b: Point<f64>) -> f64 {
+    (a.x() - o.x()) * (b.y() - o.y()) - (a.y() - o.y()) * (b.x() - o.x())
+}
+
+/// Returns the signed area of a polygon, which is positive for polygons wound clockwise on screen.
+pub(super) fn signed_area(points: &[Point<f64>]) -> f64 {
+    edges(points)
+        .map(|(p1, p2)| p1.x() * p2.y() - p2.x() * p1.y())
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Returns the shortest distance from a point to the segment from `a` to `b`.
+pub(super) fn dist_to_segment(p: Point<f64>, a: Point<f64>, b: Point<f64>) -> f64 {
+    let (dx, dy) = (b.x() - a.x(), b.y() - a.y());
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((p.x() - a.x()) * dx + (p.y() - a.y()) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p.x() - a.x() - t * dx).hypot(p.y() - a.y() - t * dy)
+}
@@ -225,31 +225,6 @@ impl<T: Copy, const N: usize> Line<T, N> {
     }
 }
 
-impl<T: Float> Intersects<Line<T>> for Line<T> {
-    type Result = (Point<T>, T);
-
-    /// Returns the closest intersection point with a given line and distance along the line or
-    /// `None` if there is no intersection.
-    #[allow(clippy::many_single_char_names)]
-    fn intersects(&self, line: Line<T>) -> Option<Self::Result> {
-        let [x1, y1, x2, y2] = self.coords();
-        let [x3, y3, x4, y4] = line.coords();
-        let d = (x1 - x2) * (y3 - y4) - (y1 - y2) * (x3 - x4);
-        if d == T::zero() {
-            return None;
-        }
-        let t = ((x1 - x3) * (y3 - y4) - (y1 - y3) * (x3 - x4)) / d;
-        let u = ((x2 - x1) * (y1 - y3) - (y2 - y1) * (x1 - x3)) / d;
-        if (T::zero()..).contains(&t) && (T::zero()..=T::one()).contains(&u) {
-            let x = x1 + t * (x2 - x1);
-            let y = y1 + t * (y2 - y1);
-            Some((point!(x, y), t))
-        } else {
-            None
-        }
-    }
-}
-
 impl Draw for Line<i32> {
     /// Draw `Line` to the current [`PixState`] canvas.
     fn draw(&self, s: &mut PixState) -> Result<()> {
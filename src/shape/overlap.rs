@@ -0,0 +1,612 @@
+//! [Contains] and [Intersects] implementations between 2D shapes.
+//!
+//! Shapes are converted to `f64` geometry before being tested so that integer and floating-point
+//! shapes give the same results. [Tri]s, [Quad]s and [Polygon]s may be convex or concave and shape
+//! boundaries are inclusive, so shapes sharing an edge intersect and a shape contains itself. The
+//! only exception is a [Rect] containing or intersecting a [Point], which only includes the top and
+//! left edges to match the pixels a rectangle covers. Overlap between two non-circular [Ellipse]s
+//! is approximated by a polygon with [`ELLIPSE_SEGMENTS`] sides.
+//!
+//! Intersections of floating-point shapes with a [Line] return the closest intersection point and
+//! distance along the line, the same as intersecting two [Line]s. Integer shapes only return
+//! whether they intersect a [Line].
+
+use super::geometry::{cross, edges, EPSILON};
+use crate::prelude::*;
+use num_traits::AsPrimitive;
+
+/// Number of sides of the polygon used to approximate an [Ellipse] when testing it against
+/// another non-circular [Ellipse].
+const ELLIPSE_SEGMENTS: u32 = 64;
+
+/// A 2D shape in `f64` coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Shape {
+    /// A single point.
+    Point(Point<f64>),
+    /// A line segment between two points.
+    Segment(Point<f64>, Point<f64>),
+    /// A simple polygon through a list of points.
+    Polygon(Vec<Point<f64>>),
+    /// An ellipse with a center and non-zero `x` and `y` radii.
+    Ellipse(Point<f64>, f64, f64),
+}
+
+/// Trait for converting shapes to [Shape] geometry.
+pub(super) trait AsShape {
+    /// Returns the shape as [Shape] geometry.
+    fn as_shape(&self) -> Shape;
+}
+
+impl<T: Num + AsPrimitive<f64>> AsShape for Point<T> {
+    fn as_shape(&self) -> Shape {
+        Shape::Point(self.as_())
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsShape for Line<T> {
+    fn as_shape(&self) -> Shape {
+        let [start, end] = self.as_().points();
+        Shape::Segment(start, end)
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsShape for Rect<T> {
+    fn as_shape(&self) -> Shape {
+        let rect: Rect<f64> = self.as_();
+        Shape::Polygon(vec![
+            rect.top_left(),
+            rect.top_right(),
+            rect.bottom_right(),
+            rect.bottom_left(),
+        ])
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsShape for Ellipse<T> {
+    fn as_shape(&self) -> Shape {
+        let ellipse: Ellipse<f64> = self.as_();
+        let center = point![ellipse.x(), ellipse.y()];
+        let rx = ellipse.width().abs() / 2.0;
+        let ry = ellipse.height().abs() / 2.0;
+        // Flat ellipses only cover their center or a line through it
+        match (rx > 0.0, ry > 0.0) {
+            (true, true) => Shape::Ellipse(center, rx, ry),
+            (true, false) => Shape::Segment(
+                point![center.x() - rx, center.y()],
+                point![center.x() + rx, center.y()],
+            ),
+            (false, true) => Shape::Segment(
+                point![center.x(), center.y() - ry],
+                point![center.x(), center.y() + ry],
+            ),
+            (false, false) => Shape::Point(center),
+        }
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsShape for Tri<T> {
+    fn as_shape(&self) -> Shape {
+        Shape::Polygon(self.as_().points().to_vec())
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsShape for Quad<T> {
+    fn as_shape(&self) -> Shape {
+        Shape::Polygon(self.as_().points().to_vec())
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> AsShape for Polygon<T> {
+    fn as_shape(&self) -> Shape {
+        Shape::Polygon(self.as_().to_vec())
+    }
+}
+
+/// Returns whether shape `a` intersects with shape `b`.
+pub(super) fn intersects(a: &Shape, b: &Shape) -> bool {
+    match (a, b) {
+        (Shape::Point(p), Shape::Point(q)) => p == q,
+        (Shape::Point(p), other) | (other, Shape::Point(p)) => contains_point(other, *p),
+        (Shape::Segment(a1, a2), Shape::Segment(b1, b2)) => segments_intersect(*a1, *a2, *b1, *b2),
+        (Shape::Segment(p1, p2), Shape::Polygon(ps))
+        | (Shape::Polygon(ps), Shape::Segment(p1, p2)) => {
+            polygon_contains_point(ps, *p1)
+                || edges(ps).any(|(e1, e2)| segments_intersect(*p1, *p2, e1, e2))
+        }
+        (Shape::Segment(p1, p2), Shape::Ellipse(c, rx, ry))
+        | (Shape::Ellipse(c, rx, ry), Shape::Segment(p1, p2)) => {
+            let to_unit = |p| to_unit_circle(p, *c, *rx, *ry);
+            segment_dist_to_origin(to_unit(*p1), to_unit(*p2)) <= 1.0 + EPSILON
+        }
+        (Shape::Polygon(ps), Shape::Polygon(qs)) => {
+            edges(ps).any(|(p1, p2)| edges(qs).any(|(q1, q2)| segments_intersect(p1, p2, q1, q2)))
+                || ps.first().map_or(false, |&p| polygon_contains_point(qs, p))
+                || qs.first().map_or(false, |&q| polygon_contains_point(ps, q))
+        }
+        (Shape::Polygon(ps), Shape::Ellipse(c, rx, ry))
+        | (Shape::Ellipse(c, rx, ry), Shape::Polygon(ps)) => {
+            polygon_intersects_ellipse(ps, *c, *rx, *ry)
+        }
+        (Shape::Ellipse(c1, rx1, ry1), Shape::Ellipse(c2, rx2, ry2)) => {
+            if is_circle(*rx1, *ry1) && is_circle(*rx2, *ry2) {
+                c1.dist(*c2) <= rx1 + rx2 + EPSILON
+            } else {
+                polygon_intersects_ellipse(&ellipse_polygon(*c2, *rx2, *ry2), *c1, *rx1, *ry1)
+            }
+        }
+    }
+}
+
+/// Returns whether shape `outer` completely contains shape `inner`.
+pub(super) fn contains(outer: &Shape, inner: &Shape) -> bool {
+    match (outer, inner) {
+        (_, Shape::Point(p)) => contains_point(outer, *p),
+        (Shape::Point(_), _) => false,
+        (Shape::Segment(a1, a2), Shape::Segment(b1, b2)) => {
+            on_segment(*a1, *a2, *b1) && on_segment(*a1, *a2, *b2)
+        }
+        (Shape::Segment(..), _) => false,
+        (Shape::Polygon(ps), Shape::Segment(p1, p2)) => {
+            polygon_contains_polyline(ps, &[*p1, *p2], false)
+        }
+        (Shape::Polygon(ps), Shape::Polygon(qs)) => polygon_contains_polyline(ps, qs, true),
+        (Shape::Polygon(ps), Shape::Ellipse(c, rx, ry)) => {
+            let qs: Vec<_> = ps
+                .iter()
+                .map(|&p| to_unit_circle(p, *c, *rx, *ry))
+                .collect();
+            polygon_contains_point(&qs, point![0.0, 0.0])
+                && edges(&qs).all(|(q1, q2)| segment_dist_to_origin(q1, q2) >= 1.0 - EPSILON)
+        }
+        (Shape::Ellipse(c, rx, ry), Shape::Segment(p1, p2)) => {
+            // Ellipses are convex, so containing every vertex contains the whole shape
+            ellipse_contains_point(*c, *rx, *ry, *p1) && ellipse_contains_point(*c, *rx, *ry, *p2)
+        }
+        (Shape::Ellipse(c, rx, ry), Shape::Polygon(ps)) => {
+            ps.iter().all(|&p| ellipse_contains_point(*c, *rx, *ry, p))
+        }
+        (Shape::Ellipse(c1, rx1, ry1), Shape::Ellipse(c2, rx2, ry2)) => {
+            if is_circle(*rx1, *ry1) && is_circle(*rx2, *ry2) {
+                c1.dist(*c2) + rx2 <= rx1 + EPSILON
+            } else {
+                ellipse_polygon(*c2, *rx2, *ry2)
+                    .into_iter()
+                    .all(|p| ellipse_contains_point(*c1, *rx1, *ry1, p))
+            }
+        }
+    }
+}
+
+/// Returns the closest intersection point between a line and a list of edges and the distance
+/// along the line or `None` if there is no intersection.
+fn closest_intersection<T, I>(line: Line<T>, edges: I) -> Option<(Point<T>, T)>
+where
+    T: Float,
+    I: IntoIterator<Item = Line<T>>,
+{
+    edges
+        .into_iter()
+        .filter_map(|edge| line.line_intersection(edge))
+        .fold(None, |closest, intersection| {
+            let closest_t = closest.map_or_else(T::infinity, |c: (Point<T>, T)| c.1);
+            if intersection.1 < closest_t {
+                Some(intersection)
+            } else {
+                closest
+            }
+        })
+}
+
+/// Returns whether two values have strictly opposite signs.
+fn opposite_signs(a: f64, b: f64) -> bool {
+    (a > EPSILON && b < -EPSILON) || (a < -EPSILON && b > EPSILON)
+}
+
+/// Returns whether point `p` lies on the segment from `a` to `b`.
+fn on_segment(a: Point<f64>, b: Point<f64>, p: Point<f64>) -> bool {
+    cross(a, b, p).abs() <= EPSILON
+        && p.x() >= a.x().min(b.x()) - EPSILON
+        && p.x() <= a.x().max(b.x()) + EPSILON
+        && p.y() >= a.y().min(b.y()) - EPSILON
+        && p.y() <= a.y().max(b.y()) + EPSILON
+}
+
+/// Returns whether two segments cross through each other, not including touching.
+fn segments_cross(a1: Point<f64>, a2: Point<f64>, b1: Point<f64>, b2: Point<f64>) -> bool {
+    opposite_signs(cross(b1, b2, a1), cross(b1, b2, a2))
+        && opposite_signs(cross(a1, a2, b1), cross(a1, a2, b2))
+}
+
+/// Returns whether two segments intersect, including touching.
+fn segments_intersect(a1: Point<f64>, a2: Point<f64>, b1: Point<f64>, b2: Point<f64>) -> bool {
+    segments_cross(a1, a2, b1, b2)
+        || on_segment(b1, b2, a1)
+        || on_segment(b1, b2, a2)
+        || on_segment(a1, a2, b1)
+        || on_segment(a1, a2, b2)
+}
+
+/// Returns whether a shape contains a point.
+fn contains_point(shape: &Shape, p: Point<f64>) -> bool {
+    match shape {
+        Shape::Point(q) => *q == p,
+        Shape::Segment(a, b) => on_segment(*a, *b, p),
+        Shape::Polygon(ps) => polygon_contains_point(ps, p),
+        Shape::Ellipse(c, rx, ry) => ellipse_contains_point(*c, *rx, *ry, p),
+    }
+}
+
+/// Returns whether a polygon contains a point using the even-odd rule.
+fn polygon_contains_point(points: &[Point<f64>], p: Point<f64>) -> bool {
+    if edges(points).any(|(a, b)| on_segment(a, b, p)) {
+        return true;
+    }
+    let mut inside = false;
+    for (a, b) in edges(points) {
+        if (a.y() > p.y()) != (b.y() > p.y()) {
+            let x = a.x() + (p.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+            if p.x() < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Returns whether a polygon contains every segment of a polyline through `points`, closing the
+/// polyline if `closed` is `true`.
+fn polygon_contains_polyline(polygon: &[Point<f64>], points: &[Point<f64>], closed: bool) -> bool {
+    let segments: Vec<_> = if closed {
+        edges(points).collect()
+    } else {
+        points.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    // Edges may touch, but not cross, and the midpoints catch edges passing through a vertex
+    points.iter().all(|&p| polygon_contains_point(polygon, p))
+        && segments.iter().all(|&(p1, p2)| {
+            let mid = point![(p1.x() + p2.x()) / 2.0, (p1.y() + p2.y()) / 2.0];
+            polygon_contains_point(polygon, mid)
+                && edges(polygon).all(|(e1, e2)| !segments_cross(p1, p2, e1, e2))
+        })
+}
+
+/// Returns whether the `x` and `y` radii of an ellipse are equal.
+fn is_circle(rx: f64, ry: f64) -> bool {
+    (rx - ry).abs() <= EPSILON
+}
+
+/// Returns whether an ellipse contains a point.
+fn ellipse_contains_point(c: Point<f64>, rx: f64, ry: f64, p: Point<f64>) -> bool {
+    let p = to_unit_circle(p, c, rx, ry);
+    p.x() * p.x() + p.y() * p.y() <= 1.0 + EPSILON
+}
+
+/// Returns whether a polygon intersects an ellipse.
+fn polygon_intersects_ellipse(points: &[Point<f64>], c: Point<f64>, rx: f64, ry: f64) -> bool {
+    let ps: Vec<_> = points
+        .iter()
+        .map(|&p| to_unit_circle(p, c, rx, ry))
+        .collect();
+    polygon_contains_point(&ps, point![0.0, 0.0])
+        || edges(&ps).any(|(p1, p2)| segment_dist_to_origin(p1, p2) <= 1.0 + EPSILON)
+}
+
+/// Maps a point into the space where an ellipse is the unit circle centered on the origin.
+fn to_unit_circle(p: Point<f64>, c: Point<f64>, rx: f64, ry: f64) -> Point<f64> {
+    point![(p.x() - c.x()) / rx, (p.y() - c.y()) / ry]
+}
+
+/// Returns the shortest distance from the origin to the segment from `a` to `b`.
+fn segment_dist_to_origin(a: Point<f64>, b: Point<f64>) -> f64 {
+    let (dx, dy) = (b.x() - a.x(), b.y() - a.y());
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (-(a.x() * dx + a.y() * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (a.x() + t * dx).hypot(a.y() + t * dy)
+}
+
+/// Returns the points of a polygon approximating an ellipse.
+pub(super) fn ellipse_polygon(c: Point<f64>, rx: f64, ry: f64) -> Vec<Point<f64>> {
+    (0..ELLIPSE_SEGMENTS)
+        .map(|i| {
+            let angle = f64::from(i) * std::f64::consts::TAU / f64::from(ELLIPSE_SEGMENTS);
+            point![c.x() + rx * angle.cos(), c.y() + ry * angle.sin()]
+        })
+        .collect()
+}
+
+macro_rules! impl_contains {
+    ($($Shape:ident: $($Other:ident),+;)+) => {$($(
+        impl<T: Num + AsPrimitive<f64>> Contains<$Other<T>> for $Shape<T> {
+            #[doc = concat!("Returns whether this shape completely contains a given [", stringify!($Other), "].")]
+            fn contains(&self, shape: $Other<T>) -> bool {
+                contains(&self.as_shape(), &shape.as_shape())
+            }
+        }
+    )+)+};
+}
+
+impl_contains! {
+    Point: Point;
+    Line: Point, Line;
+    Rect: Line, Ellipse, Tri, Quad, Polygon;
+    Ellipse: Point, Line, Rect, Ellipse, Tri, Quad, Polygon;
+    Tri: Point, Line, Rect, Ellipse, Tri, Quad, Polygon;
+    Quad: Point, Line, Rect, Ellipse, Tri, Quad, Polygon;
+    Polygon: Point, Line, Rect, Ellipse, Tri, Quad, Polygon;
+}
+
+macro_rules! impl_intersects {
+    ($($Shape:ident: $($Other:ident),+;)+) => {$($(
+        impl<T: Num + AsPrimitive<f64>> Intersects<$Other<T>> for $Shape<T> {
+            type Result = ();
+
+            #[doc = concat!("Returns whether this shape intersects with a given [", stringify!($Other), "].")]
+            fn intersects(&self, shape: $Other<T>) -> Option<Self::Result> {
+                if intersects(&self.as_shape(), &shape.as_shape()) {
+                    Some(())
+                } else {
+                    None
+                }
+            }
+        }
+    )+)+};
+}
+
+impl_intersects! {
+    Point: Point, Line, Ellipse, Tri, Quad, Polygon;
+    Line: Point;
+    Rect: Ellipse, Tri, Quad, Polygon;
+    Ellipse: Point, Rect, Ellipse, Tri, Quad, Polygon;
+    Tri: Point, Rect, Ellipse, Tri, Quad, Polygon;
+    Quad: Point, Rect, Ellipse, Tri, Quad, Polygon;
+    Polygon: Point, Rect, Ellipse, Tri, Quad, Polygon;
+}
+
+/// Trait for finding the closest intersection of a floating-point [Line] with a shape.
+trait LineIntersection<T> {
+    /// Returns the closest intersection point with a given line and distance along the line or
+    /// `None` if there is no intersection.
+    fn line_intersection(&self, line: Line<T>) -> Option<(Point<T>, T)>;
+}
+
+impl<T: Float> LineIntersection<T> for Line<T> {
+    #[allow(clippy::many_single_char_names)]
+    fn line_intersection(&self, line: Line<T>) -> Option<(Point<T>, T)> {
+        let [x1, y1, x2, y2] = self.coords();
+        let [x3, y3, x4, y4] = line.coords();
+        let d = (x1 - x2) * (y3 - y4) - (y1 - y2) * (x3 - x4);
+        if d == T::zero() {
+            return None;
+        }
+        let t = ((x1 - x3) * (y3 - y4) - (y1 - y3) * (x3 - x4)) / d;
+        let u = ((x2 - x1) * (y1 - y3) - (y2 - y1) * (x1 - x3)) / d;
+        if (T::zero()..).contains(&t) && (T::zero()..=T::one()).contains(&u) {
+            let x = x1 + t * (x2 - x1);
+            let y = y1 + t * (y2 - y1);
+            Some((point!(x, y), t))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Float> LineIntersection<T> for Rect<T> {
+    fn line_intersection(&self, line: Line<T>) -> Option<(Point<T>, T)> {
+        closest_intersection(
+            line,
+            [
+                line_![self.top_left(), self.bottom_left()],
+                line_![self.top_right(), self.bottom_right()],
+                line_![self.top_left(), self.top_right()],
+                line_![self.bottom_left(), self.bottom_right()],
+            ],
+        )
+    }
+}
+
+impl<T: Float> LineIntersection<T> for Tri<T> {
+    fn line_intersection(&self, line: Line<T>) -> Option<(Point<T>, T)> {
+        closest_intersection(line, edges(&self.points()).map(|(p1, p2)| line_![p1, p2]))
+    }
+}
+
+impl<T: Float> LineIntersection<T> for Quad<T> {
+    fn line_intersection(&self, line: Line<T>) -> Option<(Point<T>, T)> {
+        closest_intersection(line, edges(&self.points()).map(|(p1, p2)| line_![p1, p2]))
+    }
+}
+
+impl<T: Float> LineIntersection<T> for Polygon<T> {
+    fn line_intersection(&self, line: Line<T>) -> Option<(Point<T>, T)> {
+        closest_intersection(line, edges(self.points()).map(|(p1, p2)| line_![p1, p2]))
+    }
+}
+
+impl<T: Float> LineIntersection<T> for Ellipse<T> {
+    #[allow(clippy::many_single_char_names)]
+    fn line_intersection(&self, line: Line<T>) -> Option<(Point<T>, T)> {
+        let two = T::one() + T::one();
+        let rx = self.width().abs() / two;
+        let ry = self.height().abs() / two;
+        if rx <= T::zero() || ry <= T::zero() {
+            return None;
+        }
+        // Solve for where the line crosses the unit circle after scaling the ellipse to it
+        let [x1, y1, x2, y2] = line.coords();
+        let (sx, sy) = ((x1 - self.x()) / rx, (y1 - self.y()) / ry);
+        let (dx, dy) = ((x2 - x1) / rx, (y2 - y1) / ry);
+        let a = dx * dx + dy * dy;
+        let b = two * (sx * dx + sy * dy);
+        let c = sx * sx + sy * sy - T::one();
+        let discriminant = b * b - two * two * a * c;
+        if a == T::zero() || discriminant < T::zero() {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        let t = [(-b - root) / (two * a), (-b + root) / (two * a)]
+            .into_iter()
+            .find(|&t| t >= T::zero())?;
+        Some((point!(x1 + t * (x2 - x1), y1 + t * (y2 - y1)), t))
+    }
+}
+
+// Floating-point and integer implementations are given for each primitive type, since generic
+// implementations for both would conflict.
+macro_rules! impl_float_line_intersects {
+    ($($T:ty),+) => {$(
+        impl_float_line_intersects!(@shape $T: Line, Rect, Ellipse, Tri, Quad, Polygon);
+        impl_float_line_intersects!(@line $T: Rect, Ellipse, Tri, Quad, Polygon);
+    )+};
+    (@shape $T:ty: $($Shape:ident),+) => {$(
+        impl Intersects<Line<$T>> for $Shape<$T> {
+            type Result = (Point<$T>, $T);
+
+            /// Returns the closest intersection point with a given line and distance along the
+            /// line or `None` if there is no intersection.
+            fn intersects(&self, line: Line<$T>) -> Option<Self::Result> {
+                self.line_intersection(line)
+            }
+        }
+    )+};
+    (@line $T:ty: $($Shape:ident),+) => {$(
+        impl Intersects<$Shape<$T>> for Line<$T> {
+            type Result = (Point<$T>, $T);
+
+            #[doc = concat!("Returns the closest intersection point with a given [", stringify!($Shape), "] and")]
+            /// distance along the line or `None` if there is no intersection.
+            fn intersects(&self, shape: $Shape<$T>) -> Option<Self::Result> {
+                shape.line_intersection(*self)
+            }
+        }
+    )+};
+}
+
+impl_float_line_intersects!(f32, f64);
+
+macro_rules! impl_int_line_intersects {
+    ($($T:ty),+) => {$(
+        impl_int_line_intersects!(@shape $T: Line, Rect, Ellipse, Tri, Quad, Polygon);
+        impl_int_line_intersects!(@line $T: Rect, Ellipse, Tri, Quad, Polygon);
+    )+};
+    (@shape $T:ty: $($Shape:ident),+) => {$(
+        impl Intersects<Line<$T>> for $Shape<$T> {
+            type Result = ();
+
+            /// Returns whether this shape intersects with a given [Line].
+            fn intersects(&self, line: Line<$T>) -> Option<Self::Result> {
+                intersects(&self.as_shape(), &line.as_shape()).then_some(())
+            }
+        }
+    )+};
+    (@line $T:ty: $($Shape:ident),+) => {$(
+        impl Intersects<$Shape<$T>> for Line<$T> {
+            type Result = ();
+
+            #[doc = concat!("Returns whether this line intersects with a given [", stringify!($Shape), "].")]
+            fn intersects(&self, shape: $Shape<$T>) -> Option<Self::Result> {
+                intersects(&self.as_shape(), &shape.as_shape()).then_some(())
+            }
+        }
+    )+};
+}
+
+impl_int_line_intersects!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_contains() {
+        let ellipse = ellipse!(50, 50, 40, 20);
+        assert!(ellipse.contains(point!(69, 50)));
+        assert!(!ellipse.contains(point!(69, 59)));
+        assert!(ellipse.contains(rect!(40, 45, 20, 10)));
+        assert!(!ellipse.contains(rect!(30, 40, 40, 20)));
+        assert!(ellipse.contains(ellipse!(50, 50, 20, 10)));
+        assert!(ellipse!(50.0, 50.0, 40.0, 20.0).contains(ellipse!(50.0, 50.0, 20.0, 10.0)));
+
+        let tri = tri!([0, 0], [100, 0], [0, 100]);
+        assert!(tri.contains(point!(50, 50)));
+        assert!(!tri.contains(point!(51, 50)));
+        assert!(tri.contains(rect!(10, 10, 20, 20)));
+        assert!(tri.contains(tri!([0, 0], [50, 0], [0, 50])));
+        assert!(!tri.contains(ellipse!(50, 50, 10, 10)));
+
+        // Concave quad with a notch cut into the top
+        let quad = quad!([0, 0], [50, 40], [100, 0], [50, 100]);
+        assert!(quad.contains(point!(50, 60)));
+        assert!(!quad.contains(point!(50, 20)));
+        assert!(!quad.contains(line_!([20, 30], [80, 30])));
+        assert!(line_!([0, 0], [10, 10]).contains(point!(5, 5)));
+    }
+
+    #[test]
+    fn test_rect_quad_consistency() {
+        let rect = rect!(0, 0, 10, 10);
+        let quad = quad!([0, 0], [10, 0], [10, 10], [0, 10]);
+        assert!(rect.contains(rect));
+        assert!(rect.contains(quad));
+        assert!(quad.contains(rect));
+
+        let touching = rect!(10, 0, 10, 10);
+        let touching_quad = quad!([10, 0], [20, 0], [20, 10], [10, 10]);
+        assert!(rect.intersects(touching).is_some());
+        assert!(rect.intersects(touching_quad).is_some());
+        assert!(quad.intersects(touching).is_some());
+
+        let apart = rect!(11, 0, 10, 10);
+        let apart_quad = quad!([11, 0], [21, 0], [21, 10], [11, 10]);
+        assert!(rect.intersects(apart).is_none());
+        assert!(rect.intersects(apart_quad).is_none());
+    }
+
+    #[test]
+    fn test_intersects() {
+        let rect = rect!(0, 0, 100, 100);
+        assert!(rect.intersects(rect!(50, 50, 100, 100)).is_some());
+        assert!(rect.intersects(rect!(50, 150, 10, 10)).is_none());
+        assert!(rect.intersects(ellipse!(110, 50, 20, 20)).is_some());
+        assert!(rect.intersects(ellipse!(115, 115, 20, 20)).is_none());
+        assert!(tri!([0, 0], [10, 0], [0, 10])
+            .intersects(tri!([10, 10], [4, 5], [10, 0]))
+            .is_some());
+        assert!(quad!([0, 0], [10, 0], [10, 10], [0, 10])
+            .intersects(point!(5, 5))
+            .is_some());
+        assert!(ellipse!(0, 0, 20, 10)
+            .intersects(ellipse!(25, 0, 32, 10))
+            .is_some());
+        assert!(ellipse!(0, 0, 20, 10)
+            .intersects(ellipse!(0, 25, 30, 10))
+            .is_none());
+        assert_eq!(
+            ellipse!(0.0, 0.0, 20.0, 20.0).intersects(line_!([-20.0, 0.0], [20.0, 0.0])),
+            Some((point!(-10.0, 0.0), 0.25))
+        );
+        assert_eq!(
+            line_!([0.0, 5.0], [20.0, 5.0]).intersects(tri!(
+                [10.0, 0.0],
+                [20.0, 10.0],
+                [10.0, 10.0]
+            )),
+            Some((point!(10.0, 5.0), 0.5))
+        );
+        assert!(rect.intersects(line_!([-10, 50], [10, 50])).is_some());
+        assert!(line_!([150, 0], [150, 100]).intersects(rect).is_none());
+        assert!(line_!([-10, 0], [10, 0])
+            .intersects(ellipse!(0, 0, 20, 10))
+            .is_some());
+        assert!(tri!([0, 0], [10, 0], [0, 10])
+            .intersects(line_!([10, 10], [20, 20]))
+            .is_none());
+        assert!(line_!([0, 0], [10, 10])
+            .intersects(line_!([0, 10], [10, 0]))
+            .is_some());
+    }
+}
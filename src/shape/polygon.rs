@@ -0,0 +1,588 @@
+//! A shape type representing polygons used for drawing.
+//!
+//! `Polygon` is a closed shape through any number of points, such as those drawn by
+//! [`PixState::polygon`], that can be stored, tested and transformed as a value.
+//!
+//! # Examples
+//!
+//! You can create a [Polygon] using [`Polygon::new`]:
+//!
+//! ```
+//! use pix_engine::prelude::*;
+//!
+//! let polygon = Polygon::new([[10, 10], [50, 20], [70, 30], [60, 50], [10, 50]]);
+//! ```
+//!
+//! ...or by using the [polygon!] macro:
+//!
+//! ```
+//! use pix_engine::prelude::*;
+//!
+//! let polygon = polygon!([10, 10], [50, 20], [70, 30], [60, 50], [10, 50]);
+//!
+//! // using points
+//! let polygon = polygon!(point![10, 10], point![50, 20], point![60, 50]);
+//! ```
+
+use super::geometry::{
+    cross, dist_to_segment, edges, partial_max, partial_min, signed_area, EPSILON,
+};
+use crate::{error::Result, prelude::*};
+use num_traits::AsPrimitive;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// A `Polygon`, a closed shape through a list of points.
+///
+/// Please see the [module-level documentation] for examples.
+///
+/// [module-level documentation]: crate::shape::polygon
+#[derive(Default, Debug, Clone, Eq, PartialEq, Hash)]
+#[must_use]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = "T: Serialize + DeserializeOwned"))]
+pub struct Polygon<T = i32>(pub(crate) Vec<Point<T>>);
+
+/// Constructs a [Polygon] through a list of points.
+///
+/// ```
+/// # use pix_engine::prelude::*;
+/// let p = polygon!([10, 10], point![50, 20], [60, 50]);
+/// assert_eq!(p.points(), [point![10, 10], point![50, 20], point![60, 50]]);
+/// ```
+#[macro_export]
+macro_rules! polygon {
+    ($($p:expr),+ $(,)?) => {
+        $crate::prelude::Polygon::new([$($crate::prelude::Point::from($p)),+])
+    };
+}
+
+/// The order the points of a [Polygon] are wound in, as seen on screen where `y` increases
+/// downwards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Winding {
+    /// Points are wound clockwise.
+    Clockwise,
+    /// Points are wound counter-clockwise.
+    CounterClockwise,
+}
+
+impl<T> Polygon<T> {
+    /// Constructs a `Polygon` through the given [Point]s.
+    ///
+    /// ```
+    /// use pix_engine::prelude::*;
+    /// let polygon = Polygon::new([[10, 20], [30, 10], [20, 25]]);
+    /// assert_eq!(polygon.points(), [point![10, 20], point![30, 10], point![20, 25]]);
+    /// ```
+    pub fn new<P, I>(points: I) -> Self
+    where
+        P: Into<Point<T>>,
+        I: IntoIterator<Item = P>,
+    {
+        Self(points.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the number of points in the polygon.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the polygon has no points.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `Polygon` points as a slice `&[Point<T>]`.
+    #[inline]
+    pub fn points(&self) -> &[Point<T>] {
+        &self.0
+    }
+
+    /// Returns `Polygon` points as a mutable [Vec] `&mut Vec<Point<T>>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut polygon = polygon!([10, 20], [30, 10], [20, 25]);
+    /// polygon.points_mut().push(point![5, 15]);
+    /// assert_eq!(polygon.len(), 4);
+    /// ```
+    #[inline]
+    pub fn points_mut(&mut self) -> &mut Vec<Point<T>> {
+        &mut self.0
+    }
+
+    /// Returns `Polygon` points as a [Vec].
+    #[inline]
+    #[must_use]
+    pub fn to_vec(self) -> Vec<Point<T>> {
+        self.0
+    }
+}
+
+impl<T: Copy> Polygon<T> {
+    /// Converts `Polygon<T>` to `Polygon<U>`.
+    #[inline]
+    pub fn as_<U>(&self) -> Polygon<U>
+    where
+        U: 'static + Copy,
+        T: AsPrimitive<U>,
+    {
+        Polygon(self.0.iter().map(Point::as_).collect())
+    }
+}
+
+impl<T: Num> Polygon<T> {
+    /// Offsets a polygon by shifting every point by a given amount.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let polygon = polygon!([10, 20], [30, 10], [20, 25]).offset([5, -5]);
+    /// assert_eq!(polygon.points(), [point![15, 15], point![35, 5], point![25, 20]]);
+    /// ```
+    pub fn offset<P>(&self, offsets: P) -> Self
+    where
+        P: Into<Point<T>>,
+    {
+        let offsets = offsets.into();
+        Self(
+            self.0
+                .iter()
+                .map(|&p| point![p.x() + offsets.x(), p.y() + offsets.y()])
+                .collect(),
+        )
+    }
+
+    /// Returns the smallest [Rect] containing every point of the polygon or `None` if the polygon
+    /// has no points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let polygon = polygon!([10, 20], [30, 10], [20, 25]);
+    /// assert_eq!(polygon.bounding_rect(), Some(rect![10, 10, 20, 15]));
+    /// ```
+    #[must_use]
+    pub fn bounding_rect(&self) -> Option<Rect<T>> {
+        let (first, rest) = self.0.split_first()?;
+        let (min, max) = rest.iter().fold((*first, *first), |(min, max), p| {
+            (
+                point![partial_min(min.x(), p.x()), partial_min(min.y(), p.y())],
+                point![partial_max(max.x(), p.x()), partial_max(max.y(), p.y())],
+            )
+        });
+        Some(Rect::new(
+            min.x(),
+            min.y(),
+            max.x() - min.x(),
+            max.y() - min.y(),
+        ))
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> Polygon<T> {
+    /// Returns the area of the polygon.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let polygon = polygon!([0, 0], [10, 0], [10, 10], [0, 10]);
+    /// assert_eq!(polygon.area(), 100.0);
+    /// ```
+    #[must_use]
+    pub fn area(&self) -> f64 {
+        signed_area(&self.points_f64()).abs()
+    }
+
+    /// Returns the [Winding] order of the polygon or `None` if the polygon has no area.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let polygon = polygon!([0, 0], [10, 0], [10, 10], [0, 10]);
+    /// assert_eq!(polygon.winding(), Some(Winding::Clockwise));
+    /// ```
+    #[must_use]
+    pub fn winding(&self) -> Option<Winding> {
+        let area = signed_area(&self.points_f64());
+        if area > EPSILON {
+            Some(Winding::Clockwise)
+        } else if area < -EPSILON {
+            Some(Winding::CounterClockwise)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the centroid, or center of mass, of the polygon. Polygons with no area return the
+    /// average of their points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let polygon = polygon!([0, 0], [10, 0], [10, 10], [0, 10]);
+    /// assert_eq!(polygon.centroid(), point![5.0, 5.0]);
+    /// ```
+    pub fn centroid(&self) -> Point<f64> {
+        let points = self.points_f64();
+        let area = signed_area(&points);
+        if area.abs() > EPSILON {
+            let (x, y) = edges(&points).fold((0.0, 0.0), |(x, y), (p1, p2)| {
+                let cross = p1.x() * p2.y() - p2.x() * p1.y();
+                (x + (p1.x() + p2.x()) * cross, y + (p1.y() + p2.y()) * cross)
+            });
+            point![x / (6.0 * area), y / (6.0 * area)]
+        } else if points.is_empty() {
+            point![0.0, 0.0]
+        } else {
+            let count = points.len() as f64;
+            let (x, y) = points
+                .iter()
+                .fold((0.0, 0.0), |(x, y), p| (x + p.x(), y + p.y()));
+            point![x / count, y / count]
+        }
+    }
+
+    /// Returns whether the polygon is convex. Collinear points are ignored and polygons with fewer
+    /// than three points or that intersect themselves are not convex.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// assert!(polygon!([0, 0], [10, 0], [10, 10], [0, 10]).is_convex());
+    /// assert!(!polygon!([0, 0], [10, 0], [5, 5], [10, 10], [0, 10]).is_convex());
+    /// ```
+    #[must_use]
+    pub fn is_convex(&self) -> bool {
+        let points = self.points_f64();
+        let n = points.len();
+        if n < 3 {
+            return false;
+        }
+        let mut sign = 0.0;
+        let mut turning = 0.0;
+        for i in 0..n {
+            let (a, b, c) = (points[i], points[(i + 1) % n], points[(i + 2) % n]);
+            let cross = cross(a, b, c);
+            let dot = (b.x() - a.x()) * (c.x() - b.x()) + (b.y() - a.y()) * (c.y() - b.y());
+            if cross.abs() > EPSILON {
+                if sign * cross < 0.0 {
+                    return false;
+                }
+                sign = cross.signum();
+            }
+            turning += cross.atan2(dot);
+        }
+        // Self-intersecting polygons turn in the same direction, but more than once
+        sign.abs() > 0.0 && (turning.abs() - std::f64::consts::TAU).abs() < 1e-6
+    }
+
+    /// Returns the convex hull of the polygon, the smallest convex polygon containing every point,
+    /// wound [clockwise](Winding::Clockwise).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let polygon = polygon!([0, 0], [10, 0], [6, 5], [10, 10], [0, 10]);
+    /// assert_eq!(
+    ///     polygon.convex_hull().points(),
+    ///     [point![0, 0], point![10, 0], point![10, 10], point![0, 10]]
+    /// );
+    /// ```
+    pub fn convex_hull(&self) -> Self {
+        let mut points = self.0.clone();
+        points.sort_by(|a, b| {
+            a.x()
+                .partial_cmp(&b.x())
+                .and_then(|ordering| Some(ordering.then(a.y().partial_cmp(&b.y())?)))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        points.dedup();
+        if points.len() < 3 {
+            return Self(points);
+        }
+        // Andrew's monotone chain, building the upper and lower hulls from left to right
+        let turns_right = |hull: &[Point<T>], p: Point<T>| {
+            let n = hull.len();
+            n >= 2 && cross(hull[n - 2].as_(), hull[n - 1].as_(), p.as_()) <= EPSILON
+        };
+        let mut hull: Vec<Point<T>> = Vec::with_capacity(points.len() + 1);
+        for &p in &points {
+            while turns_right(&hull, p) {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        let lower_len = hull.len() + 1;
+        for &p in points.iter().rev().skip(1) {
+            while hull.len() >= lower_len && turns_right(&hull, p) {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop();
+        Self(hull)
+    }
+
+    /// Returns the polygon split into triangles using ear clipping. The polygon may be convex or
+    /// concave, but must not intersect itself.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let polygon = polygon!([0, 0], [10, 0], [6, 5], [10, 10], [0, 10]);
+    /// let triangles = polygon.triangulate();
+    /// assert_eq!(triangles.len(), 3);
+    /// ```
+    #[must_use]
+    pub fn triangulate(&self) -> Vec<Tri<T>> {
+        let points = self.points_f64();
+        let orientation = signed_area(&points).signum();
+        let mut indices: Vec<usize> = (0..points.len()).collect();
+        let mut triangles = Vec::with_capacity(points.len().saturating_sub(2));
+        while indices.len() >= 3 {
+            let corner_points = |i| {
+                let (a, b, c) = corner(&indices, i);
+                (points[a], points[b], points[c])
+            };
+            // Collinear points don't form a triangle, so they can be removed
+            let collinear = (0..indices.len()).find(|&i| {
+                let (a, b, c) = corner_points(i);
+                cross(a, b, c).abs() <= EPSILON
+            });
+            if let Some(i) = collinear {
+                indices.remove(i);
+                continue;
+            }
+            let ear = (0..indices.len()).find(|&i| {
+                let (a, b, c) = corner_points(i);
+                let (prev, current, next) = corner(&indices, i);
+                cross(a, b, c) * orientation > 0.0
+                    && !indices.iter().any(|&j| {
+                        j != prev
+                            && j != current
+                            && j != next
+                            && triangle_contains(a, b, c, points[j])
+                    })
+            });
+            match ear {
+                Some(i) => {
+                    let (a, b, c) = corner(&indices, i);
+                    triangles.push(Tri::new(self.0[a], self.0[b], self.0[c]));
+                    indices.remove(i);
+                }
+                // Self-intersecting polygons may have no ears left to clip
+                None => break,
+            }
+        }
+        triangles
+    }
+
+    /// Returns the polygon simplified using the Ramer–Douglas–Peucker algorithm, removing points
+    /// closer than `epsilon` to the outline of the simplified polygon.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let polygon = polygon!([0, 0], [5, 1], [10, 0], [10, 10], [0, 10]);
+    /// assert_eq!(
+    ///     polygon.simplify(2.0).points(),
+    ///     [point![0, 0], point![10, 0], point![10, 10], point![0, 10]]
+    /// );
+    /// ```
+    pub fn simplify(&self, epsilon: f64) -> Self {
+        if self.len() < 4 {
+            return self.clone();
+        }
+        // Close the outline so the edge back to the first point is simplified as well
+        let mut points = self.points_f64();
+        points.push(points[0]);
+        let mut keep = vec![false; points.len()];
+        keep[0] = true;
+        keep[points.len() - 1] = true;
+        simplify_range(&points, 0, points.len() - 1, epsilon, &mut keep);
+        Self(
+            self.0
+                .iter()
+                .zip(keep)
+                .filter(|&(_, keep)| keep)
+                .map(|(&p, _)| p)
+                .collect(),
+        )
+    }
+
+    /// Returns the polygon points as `f64`.
+    fn points_f64(&self) -> Vec<Point<f64>> {
+        self.0.iter().map(Point::as_).collect()
+    }
+}
+
+impl Draw for Polygon<i32> {
+    /// Draw `Polygon` to the current [`PixState`] canvas.
+    fn draw(&self, s: &mut PixState) -> Result<()> {
+        s.polygon(self.0.iter().copied())
+    }
+}
+
+impl<T> From<Vec<Point<T>>> for Polygon<T> {
+    /// Converts `Vec<Point<T>>` into `Polygon<T>`.
+    #[inline]
+    fn from(points: Vec<Point<T>>) -> Self {
+        Self(points)
+    }
+}
+
+impl<T> From<Polygon<T>> for Vec<Point<T>> {
+    /// Converts `Polygon<T>` into `Vec<Point<T>>`.
+    #[inline]
+    fn from(polygon: Polygon<T>) -> Self {
+        polygon.0
+    }
+}
+
+impl<T> From<Tri<T>> for Polygon<T> {
+    /// Converts `Tri<T>` into `Polygon<T>`.
+    #[inline]
+    fn from(tri: Tri<T>) -> Self {
+        Self(tri.0.into())
+    }
+}
+
+impl<T> From<Quad<T>> for Polygon<T> {
+    /// Converts `Quad<T>` into `Polygon<T>`.
+    #[inline]
+    fn from(quad: Quad<T>) -> Self {
+        Self(quad.0.into())
+    }
+}
+
+impl<T: Num> From<Rect<T>> for Polygon<T> {
+    /// Converts `Rect<T>` into `Polygon<T>`.
+    #[inline]
+    fn from(rect: Rect<T>) -> Self {
+        Self(vec![
+            rect.top_left(),
+            rect.top_right(),
+            rect.bottom_right(),
+            rect.bottom_left(),
+        ])
+    }
+}
+
+impl<T> FromIterator<Point<T>> for Polygon<T> {
+    #[inline]
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Point<T>>,
+    {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for Polygon<T> {
+    type Item = Point<T>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Polygon<T> {
+    type Item = &'a Point<T>;
+    type IntoIter = std::slice::Iter<'a, Point<T>>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Returns the indexes of the previous, current and next points around the `i`th remaining index.
+fn corner(indices: &[usize], i: usize) -> (usize, usize, usize) {
+    let n = indices.len();
+    (indices[(i + n - 1) % n], indices[i], indices[(i + 1) % n])
+}
+
+/// Returns whether a triangle contains a point, including its edges.
+fn triangle_contains(a: Point<f64>, b: Point<f64>, c: Point<f64>, p: Point<f64>) -> bool {
+    let (d1, d2, d3) = (cross(a, b, p), cross(b, c, p), cross(c, a, p));
+    let has_negative = d1 < -EPSILON || d2 < -EPSILON || d3 < -EPSILON;
+    let has_positive = d1 > EPSILON || d2 > EPSILON || d3 > EPSILON;
+    !(has_negative && has_positive)
+}
+
+/// Marks the points between `start` and `end` to keep when simplifying a polyline.
+fn simplify_range(
+    points: &[Point<f64>],
+    start: usize,
+    end: usize,
+    epsilon: f64,
+    keep: &mut [bool],
+) {
+    let farthest = (start + 1..end)
+        .map(|i| (i, dist_to_segment(points[i], points[start], points[end])))
+        .fold(
+            None,
+            |farthest: Option<(usize, f64)>, (i, dist)| match farthest {
+                Some((_, max)) if max >= dist => farthest,
+                _ => Some((i, dist)),
+            },
+        );
+    if let Some((i, dist)) = farthest {
+        if dist > epsilon {
+            keep[i] = true;
+            simplify_range(points, start, i, epsilon, keep);
+            simplify_range(points, i, end, epsilon, keep);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_triangulate() {
+        // Concave arrow shape with a collinear point
+        let polygon = polygon!([0, 0], [5, 0], [10, 0], [6, 5], [10, 10], [0, 10]);
+        let triangles = polygon.triangulate();
+        assert_eq!(triangles.len(), 3);
+        let area: f64 = triangles
+            .into_iter()
+            .map(|tri| Polygon::from(tri).area())
+            .sum();
+        assert_eq!(area, polygon.area());
+
+        // Winding order doesn't matter
+        let reversed: Polygon = polygon.clone().into_iter().rev().collect();
+        assert_eq!(reversed.winding(), Some(Winding::CounterClockwise));
+        assert_eq!(reversed.triangulate().len(), 3);
+    }
+
+    #[test]
+    fn test_is_convex() {
+        assert!(polygon!([0, 0], [5, 0], [10, 0], [10, 10], [0, 10]).is_convex());
+        // Pentagram turns in the same direction at every point
+        let star = polygon!([50, 0], [80, 90], [0, 35], [100, 35], [20, 90]);
+        assert!(!star.is_convex());
+        assert!(star.convex_hull().is_convex());
+    }
+}
@@ -0,0 +1,677 @@
+//! A shape type representing sets of polygons with holes and boolean operations between them.
+//!
+//! A [`PolygonSet`] is a list of [Polygon] rings filled using the even-odd rule, so rings inside
+//! other rings are holes. Polygon sets are the result of clipping polygons against each other,
+//! such as for level editors, fog-of-war or area-of-effect shapes.
+//!
+//! Provided boolean operations:
+//!
+//! - [`Polygon::union`] and [`PolygonSet::union`]: The area inside either shape.
+//! - [`Polygon::intersection`] and [`PolygonSet::intersection`]: The area inside both shapes.
+//! - [`Polygon::difference`] and [`PolygonSet::difference`]: The area inside the first shape but
+//!   not the second.
+//! - [`Polygon::xor`] and [`PolygonSet::xor`]: The area inside exactly one of the shapes.
+//!
+//! Results are [`PolygonSet<f64>`] with outer rings wound [clockwise](Winding::Clockwise) and holes
+//! wound [counter-clockwise](Winding::CounterClockwise).
+//!
+//! # Example
+//!
+//! ```
+//! use pix_engine::prelude::*;
+//!
+//! let room = polygon!([0, 0], [100, 0], [100, 100], [0, 100]);
+//! let pillar = polygon!([40, 40], [60, 40], [60, 60], [40, 60]);
+//! let floor = room.difference(&pillar);
+//! assert_eq!(floor.len(), 2);
+//! assert_eq!(floor.area(), 9600.0);
+//! assert!(!floor.contains(point![50.0, 50.0]));
+//! ```
+
+use super::geometry::{cross, dist_to_segment, edges, signed_area, EPSILON};
+use crate::{error::Result, prelude::*};
+use num_traits::AsPrimitive;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, iter::FromIterator};
+
+/// Scale used to match points computed separately along different edges.
+const POINT_KEY_SCALE: f64 = 1e6;
+
+/// A set of [Polygon] rings filled using the even-odd rule.
+///
+/// Please see the [module-level documentation] for examples.
+///
+/// [module-level documentation]: crate::shape::polygon_set
+#[derive(Default, Debug, Clone, Eq, PartialEq, Hash)]
+#[must_use]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = "T: Serialize + DeserializeOwned"))]
+pub struct PolygonSet<T = i32>(pub(crate) Vec<Polygon<T>>);
+
+/// A boolean operation between two polygon sets.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Operation {
+    Union,
+    Intersection,
+    Difference,
+    Xor,
+}
+
+/// Where an edge lies relative to another polygon set.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Location {
+    Inside,
+    Outside,
+    /// On an edge of the polygon set going the same direction.
+    SameBoundary,
+    /// On an edge of the polygon set going the opposite direction.
+    OppositeBoundary,
+}
+
+/// A directed edge between two points.
+type Edge = (Point<f64>, Point<f64>);
+
+impl<T> PolygonSet<T> {
+    /// Constructs a `PolygonSet` with the given [Polygon] rings.
+    ///
+    /// ```
+    /// use pix_engine::prelude::*;
+    /// let frame = PolygonSet::new([
+    ///     polygon!([0, 0], [30, 0], [30, 30], [0, 30]),
+    ///     polygon!([10, 10], [20, 10], [20, 20], [10, 20]),
+    /// ]);
+    /// assert_eq!(frame.len(), 2);
+    /// ```
+    pub fn new<P, I>(rings: I) -> Self
+    where
+        P: Into<Polygon<T>>,
+        I: IntoIterator<Item = P>,
+    {
+        Self(rings.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the number of rings in the polygon set.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the polygon set has no rings.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `PolygonSet` rings as a slice `&[Polygon<T>]`.
+    #[inline]
+    pub fn rings(&self) -> &[Polygon<T>] {
+        &self.0
+    }
+
+    /// Returns `PolygonSet` rings as a mutable [Vec] `&mut Vec<Polygon<T>>`.
+    #[inline]
+    pub fn rings_mut(&mut self) -> &mut Vec<Polygon<T>> {
+        &mut self.0
+    }
+
+    /// Returns `PolygonSet` rings as a [Vec].
+    #[inline]
+    #[must_use]
+    pub fn to_vec(self) -> Vec<Polygon<T>> {
+        self.0
+    }
+}
+
+impl<T: Copy> PolygonSet<T> {
+    /// Converts `PolygonSet<T>` to `PolygonSet<U>`.
+    #[inline]
+    pub fn as_<U>(&self) -> PolygonSet<U>
+    where
+        U: 'static + Copy,
+        T: AsPrimitive<U>,
+    {
+        PolygonSet(self.0.iter().map(Polygon::as_).collect())
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> PolygonSet<T> {
+    /// Returns the area of the polygon set, not including holes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let frame = PolygonSet::new([
+    ///     polygon!([0, 0], [30, 0], [30, 30], [0, 30]),
+    ///     polygon!([10, 10], [20, 10], [20, 20], [10, 20]),
+    /// ]);
+    /// assert_eq!(frame.area(), 800.0);
+    /// ```
+    #[must_use]
+    pub fn area(&self) -> f64 {
+        let rings = self.rings_f64();
+        (0..rings.len())
+            .map(|i| {
+                let area = signed_area(&rings[i]).abs();
+                if is_hole(&rings, i) {
+                    -area
+                } else {
+                    area
+                }
+            })
+            .sum()
+    }
+
+    /// Returns the area inside either polygon set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let a = PolygonSet::from(polygon!([0, 0], [10, 0], [10, 10], [0, 10]));
+    /// let b = PolygonSet::from(polygon!([5, 5], [15, 5], [15, 15], [5, 15]));
+    /// assert_eq!(a.union(&b).area(), 175.0);
+    /// ```
+    pub fn union(&self, other: &Self) -> PolygonSet<f64> {
+        boolean(self.rings_f64(), other.rings_f64(), Operation::Union)
+    }
+
+    /// Returns the area inside both polygon sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let a = PolygonSet::from(polygon!([0, 0], [10, 0], [10, 10], [0, 10]));
+    /// let b = PolygonSet::from(polygon!([5, 5], [15, 5], [15, 15], [5, 15]));
+    /// assert_eq!(a.intersection(&b).area(), 25.0);
+    /// ```
+    pub fn intersection(&self, other: &Self) -> PolygonSet<f64> {
+        boolean(self.rings_f64(), other.rings_f64(), Operation::Intersection)
+    }
+
+    /// Returns the area inside this polygon set but not inside the other polygon set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let a = PolygonSet::from(polygon!([0, 0], [10, 0], [10, 10], [0, 10]));
+    /// let b = PolygonSet::from(polygon!([5, 5], [15, 5], [15, 15], [5, 15]));
+    /// assert_eq!(a.difference(&b).area(), 75.0);
+    /// ```
+    pub fn difference(&self, other: &Self) -> PolygonSet<f64> {
+        boolean(self.rings_f64(), other.rings_f64(), Operation::Difference)
+    }
+
+    /// Returns the area inside exactly one of the polygon sets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let a = PolygonSet::from(polygon!([0, 0], [10, 0], [10, 10], [0, 10]));
+    /// let b = PolygonSet::from(polygon!([5, 5], [15, 5], [15, 15], [5, 15]));
+    /// assert_eq!(a.xor(&b).area(), 150.0);
+    /// ```
+    pub fn xor(&self, other: &Self) -> PolygonSet<f64> {
+        boolean(self.rings_f64(), other.rings_f64(), Operation::Xor)
+    }
+
+    /// Returns the polygon set rings as `f64` points.
+    fn rings_f64(&self) -> Vec<Vec<Point<f64>>> {
+        self.0
+            .iter()
+            .map(|ring| ring.as_::<f64>().to_vec())
+            .collect()
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> Polygon<T> {
+    /// Returns the area inside either polygon. See [`PolygonSet::union`].
+    pub fn union(&self, other: &Self) -> PolygonSet<f64> {
+        boolean(self.rings_f64(), other.rings_f64(), Operation::Union)
+    }
+
+    /// Returns the area inside both polygons. See [`PolygonSet::intersection`].
+    pub fn intersection(&self, other: &Self) -> PolygonSet<f64> {
+        boolean(self.rings_f64(), other.rings_f64(), Operation::Intersection)
+    }
+
+    /// Returns the area inside this polygon but not inside the other polygon. See
+    /// [`PolygonSet::difference`].
+    pub fn difference(&self, other: &Self) -> PolygonSet<f64> {
+        boolean(self.rings_f64(), other.rings_f64(), Operation::Difference)
+    }
+
+    /// Returns the area inside exactly one of the polygons. See [`PolygonSet::xor`].
+    pub fn xor(&self, other: &Self) -> PolygonSet<f64> {
+        boolean(self.rings_f64(), other.rings_f64(), Operation::Xor)
+    }
+
+    /// Returns the polygon as a single ring of `f64` points.
+    fn rings_f64(&self) -> Vec<Vec<Point<f64>>> {
+        vec![self.as_::<f64>().to_vec()]
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> Contains<Point<T>> for PolygonSet<T> {
+    /// Returns whether this polygon set contains a given [Point], including points on its edges.
+    fn contains(&self, p: Point<T>) -> bool {
+        let p: Point<f64> = p.as_();
+        let rings = self.rings_f64();
+        rings
+            .iter()
+            .flat_map(|ring| edges(ring))
+            .any(|(p1, p2)| dist_to_segment(p, p1, p2) <= EPSILON)
+            || rings_contain(&rings, p)
+    }
+}
+
+impl Draw for PolygonSet<i32> {
+    /// Draw `PolygonSet` to the current [`PixState`] canvas.
+    fn draw(&self, s: &mut PixState) -> Result<()> {
+        let (fill, stroke) = (s.settings.fill, s.settings.stroke);
+        // Rings are joined into a single outline so holes are filled using the even-odd rule,
+        // then outlined separately so the joins aren't drawn
+        let anchor = self
+            .0
+            .iter()
+            .find_map(|ring| ring.points().first().copied());
+        let outline = self.0.iter().flat_map(|ring| {
+            ring.points()
+                .iter()
+                .chain(ring.points().first())
+                .copied()
+                .chain(anchor)
+        });
+        s.settings.stroke = None;
+        let result = s.polygon(anchor.into_iter().chain(outline)).and_then(|()| {
+            s.settings.fill = None;
+            s.settings.stroke = stroke;
+            self.0
+                .iter()
+                .try_for_each(|ring| s.polygon(ring.points().iter().copied()))
+        });
+        s.settings.fill = fill;
+        s.settings.stroke = stroke;
+        result
+    }
+}
+
+impl<T> From<Polygon<T>> for PolygonSet<T> {
+    /// Converts `Polygon<T>` into `PolygonSet<T>`.
+    #[inline]
+    fn from(polygon: Polygon<T>) -> Self {
+        Self(vec![polygon])
+    }
+}
+
+impl<T> From<Vec<Polygon<T>>> for PolygonSet<T> {
+    /// Converts `Vec<Polygon<T>>` into `PolygonSet<T>`.
+    #[inline]
+    fn from(rings: Vec<Polygon<T>>) -> Self {
+        Self(rings)
+    }
+}
+
+impl<T> FromIterator<Polygon<T>> for PolygonSet<T> {
+    #[inline]
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Polygon<T>>,
+    {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for PolygonSet<T> {
+    type Item = Polygon<T>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PolygonSet<T> {
+    type Item = &'a Polygon<T>;
+    type IntoIter = std::slice::Iter<'a, Polygon<T>>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// Returns the result of a boolean operation between two sets of rings.
+///
+/// Edges of both sets are split wherever they cross, then each piece is kept or discarded based on
+/// whether it lies inside, outside or along the edges of the other set. The kept pieces are then
+/// joined back together into rings.
+fn boolean(a: Vec<Vec<Point<f64>>>, b: Vec<Vec<Point<f64>>>, op: Operation) -> PolygonSet<f64> {
+    let (a, b) = (orient_rings(a), orient_rings(b));
+    let (a_edges, b_edges) = split_edges(&a, &b);
+    let mut kept = Vec::new();
+    for &(p1, p2) in &a_edges {
+        match (op, locate((p1, p2), &b, &b_edges)) {
+            (Operation::Union | Operation::Difference | Operation::Xor, Location::Outside)
+            | (Operation::Intersection, Location::Inside)
+            | (Operation::Union | Operation::Intersection, Location::SameBoundary)
+            | (Operation::Difference, Location::OppositeBoundary) => kept.push((p1, p2)),
+            (Operation::Xor, Location::Inside) => kept.push((p2, p1)),
+            _ => (),
+        }
+    }
+    // Edges shared by both sets are only kept from the first set
+    for &(p1, p2) in &b_edges {
+        match (op, locate((p1, p2), &a, &a_edges)) {
+            (Operation::Union | Operation::Xor, Location::Outside)
+            | (Operation::Intersection, Location::Inside) => kept.push((p1, p2)),
+            (Operation::Difference | Operation::Xor, Location::Inside) => kept.push((p2, p1)),
+            _ => (),
+        }
+    }
+    PolygonSet(join_edges(&kept).into_iter().map(Polygon).collect())
+}
+
+/// Returns rings without repeated points or empty rings, with outer rings wound clockwise and holes
+/// wound counter-clockwise so the inside of every ring is on the same side of its edges.
+fn orient_rings(rings: Vec<Vec<Point<f64>>>) -> Vec<Vec<Point<f64>>> {
+    let mut rings: Vec<_> = rings
+        .into_iter()
+        .map(|mut ring| {
+            ring.dedup_by(|p1, p2| point_key(*p1) == point_key(*p2));
+            while ring.len() > 1
+                && ring.first().map(|&p| point_key(p)) == ring.last().map(|&p| point_key(p))
+            {
+                ring.pop();
+            }
+            ring
+        })
+        .filter(|ring| ring.len() >= 3 && signed_area(ring).abs() > EPSILON)
+        .collect();
+    let holes: Vec<_> = (0..rings.len()).map(|i| is_hole(&rings, i)).collect();
+    for (ring, hole) in rings.iter_mut().zip(holes) {
+        if (signed_area(ring) > 0.0) == hole {
+            ring.reverse();
+        }
+    }
+    rings
+}
+
+/// Returns whether the `i`th ring is a hole, which is when it is inside an odd number of the other
+/// rings.
+fn is_hole(rings: &[Vec<Point<f64>>], i: usize) -> bool {
+    let p = rings[i][0];
+    let depth = rings
+        .iter()
+        .enumerate()
+        .filter(|&(j, ring)| j != i && ring_contains(ring, p))
+        .count();
+    depth % 2 == 1
+}
+
+/// Returns the edges of both sets of rings split at every point where they cross or touch.
+fn split_edges(a: &[Vec<Point<f64>>], b: &[Vec<Point<f64>>]) -> (Vec<Edge>, Vec<Edge>) {
+    let a_edges: Vec<Edge> = a.iter().flat_map(|ring| edges(ring)).collect();
+    let b_edges: Vec<Edge> = b.iter().flat_map(|ring| edges(ring)).collect();
+    let mut a_splits: Vec<Vec<(f64, Point<f64>)>> = a_edges
+        .iter()
+        .map(|&(p1, p2)| vec![(0.0, p1), (1.0, p2)])
+        .collect();
+    let mut b_splits: Vec<Vec<(f64, Point<f64>)>> = b_edges
+        .iter()
+        .map(|&(p1, p2)| vec![(0.0, p1), (1.0, p2)])
+        .collect();
+    for (i, &a_edge) in a_edges.iter().enumerate() {
+        for (j, &b_edge) in b_edges.iter().enumerate() {
+            for (t, u, p) in edge_intersections(a_edge, b_edge) {
+                a_splits[i].push((t, p));
+                b_splits[j].push((u, p));
+            }
+        }
+    }
+    (subdivide(a_splits), subdivide(b_splits))
+}
+
+/// Returns every point where two edges cross or touch, with the distance along each edge between
+/// `0.0` and `1.0`.
+fn edge_intersections((a1, a2): Edge, (b1, b2): Edge) -> Vec<(f64, f64, Point<f64>)> {
+    let (rx, ry) = (a2.x() - a1.x(), a2.y() - a1.y());
+    let (sx, sy) = (b2.x() - b1.x(), b2.y() - b1.y());
+    let (qx, qy) = (b1.x() - a1.x(), b1.y() - a1.y());
+    let (r_len, s_len) = (rx.hypot(ry), sx.hypot(sy));
+    let denom = rx * sy - ry * sx;
+    let on_edge = |t: f64| (-EPSILON..=1.0 + EPSILON).contains(&t);
+    if denom.abs() > EPSILON * r_len * s_len {
+        let t = (qx * sy - qy * sx) / denom;
+        let u = (qx * ry - qy * rx) / denom;
+        if !on_edge(t) || !on_edge(u) {
+            return vec![];
+        }
+        // Snap to existing points so both edges are split at exactly the same point
+        let p = if t <= EPSILON {
+            a1
+        } else if t >= 1.0 - EPSILON {
+            a2
+        } else if u <= EPSILON {
+            b1
+        } else if u >= 1.0 - EPSILON {
+            b2
+        } else {
+            point![a1.x() + rx * t, a1.y() + ry * t]
+        };
+        vec![(t.clamp(0.0, 1.0), u.clamp(0.0, 1.0), p)]
+    } else if (qx * ry - qy * rx).abs() <= EPSILON * r_len * qx.hypot(qy).max(1.0) {
+        // Collinear edges overlap between the endpoints that lie on the other edge
+        let along_a =
+            |p: Point<f64>| ((p.x() - a1.x()) * rx + (p.y() - a1.y()) * ry) / (r_len * r_len);
+        let along_b =
+            |p: Point<f64>| ((p.x() - b1.x()) * sx + (p.y() - b1.y()) * sy) / (s_len * s_len);
+        let b_on_a = [(b1, 0.0), (b2, 1.0)]
+            .into_iter()
+            .map(|(p, u)| (along_a(p), u, p));
+        let a_on_b = [(a1, 0.0), (a2, 1.0)]
+            .into_iter()
+            .map(|(p, t)| (t, along_b(p), p));
+        b_on_a
+            .chain(a_on_b)
+            .filter(|&(t, u, _)| on_edge(t) && on_edge(u))
+            .map(|(t, u, p)| (t.clamp(0.0, 1.0), u.clamp(0.0, 1.0), p))
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+/// Returns edges split at each of their split points.
+fn subdivide(splits: Vec<Vec<(f64, Point<f64>)>>) -> Vec<Edge> {
+    splits
+        .into_iter()
+        .flat_map(|mut points| {
+            points.sort_by(|(t1, _), (t2, _)| {
+                t1.partial_cmp(t2).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            points.dedup_by(|(_, p1), (_, p2)| point_key(*p1) == point_key(*p2));
+            points
+                .windows(2)
+                .map(|w| (w[0].1, w[1].1))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Returns where an edge lies relative to a set of rings and their split edges.
+fn locate((p1, p2): Edge, rings: &[Vec<Point<f64>>], edges: &[Edge]) -> Location {
+    let mid = point![(p1.x() + p2.x()) / 2.0, (p1.y() + p2.y()) / 2.0];
+    let boundary = edges
+        .iter()
+        .find(|&&(e1, e2)| dist_to_segment(mid, e1, e2) <= EPSILON.sqrt());
+    match boundary {
+        Some(&(e1, e2)) => {
+            let dot = (p2.x() - p1.x()) * (e2.x() - e1.x()) + (p2.y() - p1.y()) * (e2.y() - e1.y());
+            if dot > 0.0 {
+                Location::SameBoundary
+            } else {
+                Location::OppositeBoundary
+            }
+        }
+        None if rings_contain(rings, mid) => Location::Inside,
+        None => Location::Outside,
+    }
+}
+
+/// Returns rings joined from edges that end where another edge starts.
+fn join_edges(edges: &[Edge]) -> Vec<Vec<Point<f64>>> {
+    let mut starts: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (i, &(p1, _)) in edges.iter().enumerate() {
+        starts.entry(point_key(p1)).or_default().push(i);
+    }
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    for i in 0..edges.len() {
+        if used[i] {
+            continue;
+        }
+        used[i] = true;
+        let (start, mut end) = edges[i];
+        let mut ring = vec![start];
+        let closed = loop {
+            if point_key(end) == point_key(start) {
+                break true;
+            }
+            let next = starts
+                .get(&point_key(end))
+                .and_then(|candidates| candidates.iter().copied().find(|&j| !used[j]));
+            match next {
+                Some(j) => {
+                    used[j] = true;
+                    ring.push(edges[j].0);
+                    end = edges[j].1;
+                }
+                None => break false,
+            }
+        };
+        if closed {
+            rings.extend(
+                split_loops(ring)
+                    .iter()
+                    .map(|ring| remove_collinear(ring))
+                    .filter(|ring| ring.len() >= 3 && signed_area(ring).abs() > EPSILON),
+            );
+        }
+    }
+    rings
+}
+
+/// Returns a ring split into separate loops wherever it passes through the same point twice, such
+/// as where two result rings touch at a corner.
+fn split_loops(ring: Vec<Point<f64>>) -> Vec<Vec<Point<f64>>> {
+    let mut loops = Vec::new();
+    let mut path: Vec<Point<f64>> = Vec::with_capacity(ring.len());
+    for p in ring {
+        if let Some(i) = path.iter().position(|&q| point_key(q) == point_key(p)) {
+            loops.push(path.split_off(i));
+        }
+        path.push(p);
+    }
+    loops.push(path);
+    loops
+}
+
+/// Returns a ring without points that lie on a straight line between their neighbors.
+fn remove_collinear(ring: &[Point<f64>]) -> Vec<Point<f64>> {
+    let n = ring.len();
+    (0..n)
+        .filter(|&i| cross(ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]).abs() > EPSILON)
+        .map(|i| ring[i])
+        .collect()
+}
+
+/// Returns whether a point is inside an odd number of rings.
+fn rings_contain(rings: &[Vec<Point<f64>>], p: Point<f64>) -> bool {
+    rings.iter().filter(|ring| ring_contains(ring, p)).count() % 2 == 1
+}
+
+/// Returns whether a point is inside a ring using ray casting.
+fn ring_contains(ring: &[Point<f64>], p: Point<f64>) -> bool {
+    edges(ring)
+        .filter(|&(a, b)| {
+            (a.y() > p.y()) != (b.y() > p.y())
+                && p.x() < a.x() + (p.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y())
+        })
+        .count()
+        % 2
+        == 1
+}
+
+/// Returns a key to match points computed separately along different edges.
+fn point_key(p: Point<f64>) -> (i64, i64) {
+    (
+        (p.x() * POINT_KEY_SCALE).round() as i64,
+        (p.y() * POINT_KEY_SCALE).round() as i64,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_boolean_operations() {
+        let a = polygon!([0, 0], [10, 0], [10, 10], [0, 10]);
+        let b = polygon!([5, 5], [15, 5], [15, 15], [5, 15]);
+        assert_eq!(a.union(&b).area(), 175.0);
+        assert_eq!(a.union(&b).len(), 1);
+        assert_eq!(a.intersection(&b).area(), 25.0);
+        assert_eq!(a.difference(&b).area(), 75.0);
+        assert_eq!(b.difference(&a).area(), 75.0);
+        assert_eq!(a.xor(&b).area(), 150.0);
+        assert_eq!(a.xor(&b).len(), 2);
+
+        // Disjoint polygons
+        let c = polygon!([20, 0], [30, 0], [30, 10], [20, 10]);
+        assert_eq!(a.union(&c).len(), 2);
+        assert!(a.intersection(&c).is_empty());
+        assert_eq!(a.difference(&c).area(), 100.0);
+    }
+
+    #[test]
+    fn test_shared_edges() {
+        // Adjacent squares merge into a single rectangle
+        let a = polygon!([0, 0], [10, 0], [10, 10], [0, 10]);
+        let b = polygon!([10, 0], [20, 0], [20, 10], [10, 10]);
+        let union = a.union(&b);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union.rings()[0].len(), 4);
+        assert_eq!(union.area(), 200.0);
+        assert!(a.intersection(&b).is_empty());
+        assert_eq!(a.difference(&b).area(), 100.0);
+
+        // Identical polygons
+        assert_eq!(a.union(&a).area(), 100.0);
+        assert_eq!(a.intersection(&a).area(), 100.0);
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn test_holes() {
+        let square = polygon!([0, 0], [30, 0], [30, 30], [0, 30]);
+        let hole = polygon!([10, 10], [20, 10], [20, 20], [10, 20]);
+        let frame = square.difference(&hole);
+        assert_eq!(frame.len(), 2);
+        assert_eq!(frame.area(), 800.0);
+        assert_eq!(frame.rings()[0].winding(), Some(Winding::Clockwise));
+        assert_eq!(frame.rings()[1].winding(), Some(Winding::CounterClockwise));
+        assert!(frame.contains(point![5.0, 5.0]));
+        assert!(!frame.contains(point![15.0, 15.0]));
+
+        // Filling the hole back in leaves just the square
+        let filled = frame.union(&PolygonSet::from(hole.as_::<f64>()));
+        assert_eq!(filled.len(), 1);
+        assert_eq!(filled.area(), 900.0);
+    }
+}
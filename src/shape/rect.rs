@@ -26,6 +26,7 @@
 //! let s = square!(point![10, 20], 100);
 //! ```
 
+use super::geometry::{partial_max, partial_min};
 use crate::{error::Result, prelude::*};
 use num_traits::{AsPrimitive, Bounded, NumCast};
 #[cfg(feature = "serde")]
@@ -44,6 +45,22 @@ use std::ops::{Add, Sub};
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Rect<T = i32>(pub(crate) [T; 4]);
 
+/// The rounding policy used when converting a [Rect] with floating-point coordinates to integer
+/// coordinates with [`Rect::to_int`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Rounding {
+    /// Round each edge to the nearest integer.
+    Nearest,
+    /// Round each edge outward so the result covers the original rectangle, such as for culling.
+    Outward,
+    /// Round each edge inward so the result fits inside the original rectangle, such as for
+    /// layout.
+    Inward,
+    /// Truncate the position and size towards zero, the same as [`Rect::as_`].
+    Truncate,
+}
+
 /// Constructs a [Rect] at position `(x, y)` with `width` and `height`.
 ///
 /// ```
@@ -495,6 +512,251 @@ impl<T: Num> Rect<T> {
             });
         Self::with_points([min_x, min_y], [max_x, max_y])
     }
+
+    /// Returns the smallest `Rect` containing both this rectangle and another rectangle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let a = rect!(0, 0, 10, 10);
+    /// let b = rect!(20, 5, 10, 10);
+    /// assert_eq!(a.union(b), rect!(0, 0, 30, 15));
+    /// ```
+    pub fn union(&self, other: Self) -> Self {
+        let left = partial_min(self.left(), other.left());
+        let top = partial_min(self.top(), other.top());
+        let right = partial_max(self.right(), other.right());
+        let bottom = partial_max(self.bottom(), other.bottom());
+        Self::new(left, top, right - left, bottom - top)
+    }
+
+    /// Returns the area where this rectangle overlaps another rectangle, or `None` if they don't
+    /// overlap.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let a = rect!(0, 0, 10, 10);
+    /// assert_eq!(a.intersection(rect!(5, 5, 10, 10)), Some(rect!(5, 5, 5, 5)));
+    /// assert_eq!(a.intersection(rect!(10, 0, 10, 10)), None);
+    /// ```
+    #[must_use]
+    pub fn intersection(&self, other: Self) -> Option<Self> {
+        let left = partial_max(self.left(), other.left());
+        let top = partial_max(self.top(), other.top());
+        let right = partial_min(self.right(), other.right());
+        let bottom = partial_min(self.bottom(), other.bottom());
+        if right > left && bottom > top {
+            Some(Self::new(left, top, right - left, bottom - top))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the area of this rectangle not covered by another rectangle as up to four
+    /// non-overlapping rectangles: full-width bands above and below the overlap, followed by the
+    /// areas to its left and right.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let r = rect!(0, 0, 30, 30);
+    /// assert_eq!(r.difference(rect!(10, 0, 30, 30)), vec![rect!(0, 0, 10, 30)]);
+    /// assert_eq!(r.difference(rect!(10, 10, 10, 10)).len(), 4);
+    /// assert_eq!(r.difference(rect!(50, 50, 10, 10)), vec![r]);
+    /// ```
+    #[must_use]
+    pub fn difference(&self, other: Self) -> Vec<Self> {
+        let overlap = match self.intersection(other) {
+            Some(overlap) => overlap,
+            None => return vec![*self],
+        };
+        let mut rects = Vec::with_capacity(4);
+        if overlap.top() > self.top() {
+            let height = overlap.top() - self.top();
+            rects.push(Self::new(self.left(), self.top(), self.width(), height));
+        }
+        if self.bottom() > overlap.bottom() {
+            let height = self.bottom() - overlap.bottom();
+            rects.push(Self::new(
+                self.left(),
+                overlap.bottom(),
+                self.width(),
+                height,
+            ));
+        }
+        if overlap.left() > self.left() {
+            let width = overlap.left() - self.left();
+            rects.push(Self::new(
+                self.left(),
+                overlap.top(),
+                width,
+                overlap.height(),
+            ));
+        }
+        if self.right() > overlap.right() {
+            let width = self.right() - overlap.right();
+            rects.push(Self::new(
+                overlap.right(),
+                overlap.top(),
+                width,
+                overlap.height(),
+            ));
+        }
+        rects
+    }
+
+    /// Returns the closest [Point] to `p` that lies inside or on the edges of this rectangle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let r = rect!(0, 0, 100, 100);
+    /// assert_eq!(r.clamp_point([150, -20]), point!(100, 0));
+    /// assert_eq!(r.clamp_point([50, 50]), point!(50, 50));
+    /// ```
+    pub fn clamp_point<P: Into<Point<T>>>(&self, p: P) -> Point<T> {
+        let p = p.into();
+        point!(
+            partial_min(partial_max(p.x(), self.left()), self.right()),
+            partial_min(partial_max(p.y(), self.top()), self.bottom())
+        )
+    }
+
+    /// Splits the rectangle into left and right rectangles at a horizontal `offset` from the left
+    /// edge. The `offset` is clamped to the width of the rectangle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let (left, right) = rect!(0, 0, 100, 50).split_horizontally(30);
+    /// assert_eq!(left, rect!(0, 0, 30, 50));
+    /// assert_eq!(right, rect!(30, 0, 70, 50));
+    /// ```
+    pub fn split_horizontally(&self, offset: T) -> (Self, Self) {
+        let width = partial_min(partial_max(offset, T::zero()), self.width());
+        (
+            Self::new(self.x(), self.y(), width, self.height()),
+            Self::new(
+                self.x() + width,
+                self.y(),
+                self.width() - width,
+                self.height(),
+            ),
+        )
+    }
+
+    /// Splits the rectangle into top and bottom rectangles at a vertical `offset` from the top
+    /// edge. The `offset` is clamped to the height of the rectangle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let (top, bottom) = rect!(0, 0, 100, 50).split_vertically(20);
+    /// assert_eq!(top, rect!(0, 0, 100, 20));
+    /// assert_eq!(bottom, rect!(0, 20, 100, 30));
+    /// ```
+    pub fn split_vertically(&self, offset: T) -> (Self, Self) {
+        let height = partial_min(partial_max(offset, T::zero()), self.height());
+        (
+            Self::new(self.x(), self.y(), self.width(), height),
+            Self::new(
+                self.x(),
+                self.y() + height,
+                self.width(),
+                self.height() - height,
+            ),
+        )
+    }
+
+    /// Returns the largest rectangle with the same aspect ratio as this rectangle that fits inside
+    /// `bounds`, centered within it. Integer sizes are rounded down so the result never exceeds
+    /// `bounds`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let video = rect!(0, 0, 160, 90);
+    /// assert_eq!(video.aspect_fit_into(rect!(0, 0, 800, 600)), rect!(0, 75, 800, 450));
+    /// ```
+    pub fn aspect_fit_into(&self, bounds: Self) -> Self {
+        let (width, height) = (self.width(), self.height());
+        if width <= T::zero() || height <= T::zero() {
+            return Self::from_center(bounds.center(), T::zero(), T::zero());
+        }
+        let (width, height) = if bounds.width() * height <= bounds.height() * width {
+            (bounds.width(), bounds.width() * height / width)
+        } else {
+            (bounds.height() * width / height, bounds.height())
+        };
+        let two = T::one() + T::one();
+        Self::new(
+            bounds.x() + (bounds.width() - width) / two,
+            bounds.y() + (bounds.height() - height) / two,
+            width,
+            height,
+        )
+    }
+}
+
+impl<T: Num + AsPrimitive<f64>> Rect<T> {
+    /// Converts `Rect<T>` to `Rect<f64>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// assert_eq!(rect!(10, 20, 30, 40).to_float(), rect!(10.0, 20.0, 30.0, 40.0));
+    /// ```
+    #[inline]
+    pub fn to_float(&self) -> Rect<f64> {
+        self.as_()
+    }
+}
+
+impl<T: Float + AsPrimitive<i32>> Rect<T> {
+    /// Converts `Rect<T>` to `Rect<i32>` using the given [Rounding] policy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let r = rect!(0.4, 0.6, 10.2, 10.2);
+    /// assert_eq!(r.to_int(Rounding::Nearest), rect!(0, 1, 11, 10));
+    /// assert_eq!(r.to_int(Rounding::Outward), rect!(0, 0, 11, 11));
+    /// assert_eq!(r.to_int(Rounding::Inward), rect!(1, 1, 9, 9));
+    /// assert_eq!(r.to_int(Rounding::Truncate), rect!(0, 0, 10, 10));
+    /// ```
+    pub fn to_int(&self, rounding: Rounding) -> Rect<i32> {
+        let [left, top, right, bottom] = match rounding {
+            Rounding::Nearest => {
+                [self.left(), self.top(), self.right(), self.bottom()].map(T::round)
+            }
+            Rounding::Outward => [
+                self.left().floor(),
+                self.top().floor(),
+                self.right().ceil(),
+                self.bottom().ceil(),
+            ],
+            Rounding::Inward => [
+                self.left().ceil(),
+                self.top().ceil(),
+                self.right().floor(),
+                self.bottom().floor(),
+            ],
+            Rounding::Truncate => return self.as_(),
+        }
+        .map(AsPrimitive::<i32>::as_);
+        // Inward rounding of rectangles smaller than a pixel can cross over
+        Rect::new(left, top, (right - left).max(0), (bottom - top).max(0))
+    }
 }
 
 impl<T: Num> Contains<Point<T>> for Rect<T> {
@@ -505,37 +767,35 @@ impl<T: Num> Contains<Point<T>> for Rect<T> {
 }
 
 impl<T: Num> Contains<Rect<T>> for Rect<T> {
-    /// Returns whether this rectangle completely contains another rectangle.
+    /// Returns whether this rectangle completely contains another rectangle, including rectangles
+    /// that share an edge with it.
     fn contains(&self, rect: Rect<T>) -> bool {
         rect.left() >= self.left()
-            && rect.right() < self.right()
+            && rect.right() <= self.right()
             && rect.top() >= self.top()
-            && rect.bottom() < self.bottom()
+            && rect.bottom() <= self.bottom()
     }
 }
 
-impl<T: Float> Intersects<Line<T>> for Rect<T> {
-    type Result = (Point<T>, T);
-
-    /// Returns the closest intersection point with a given line and distance along the line or
-    /// `None` if there is no intersection.
-    fn intersects(&self, line: Line<T>) -> Option<Self::Result> {
-        let left = line.intersects(line_![self.top_left(), self.bottom_left()]);
-        let right = line.intersects(line_![self.top_right(), self.bottom_right()]);
-        let top = line.intersects(line_![self.top_left(), self.top_right()]);
-        let bottom = line.intersects(line_![self.bottom_left(), self.bottom_right()]);
-        [left, right, top, bottom]
-            .iter()
-            .filter_map(|&p| p)
-            .fold(None, |closest, intersection| {
-                let closest_t = closest.map_or_else(T::infinity, |c| c.1);
-                let t = intersection.1;
-                if t < closest_t {
-                    Some(intersection)
-                } else {
-                    closest
-                }
-            })
+impl<T: Num> Intersects<Point<T>> for Rect<T> {
+    type Result = ();
+
+    /// Returns whether this rectangle intersects with a given [Point].
+    fn intersects(&self, p: Point<T>) -> Option<Self::Result> {
+        if self.contains(p) {
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Num> Intersects<Rect<T>> for Point<T> {
+    type Result = ();
+
+    /// Returns whether this point intersects with a given [Rect].
+    fn intersects(&self, rect: Rect<T>) -> Option<Self::Result> {
+        rect.intersects(*self)
     }
 }
 
@@ -543,14 +803,15 @@ impl<T: Num> Intersects<Rect<T>> for Rect<T> {
     // FIXME: Provide a better intersection result
     type Result = ();
 
-    /// Returns whether this rectangle intersects with another rectangle.
+    /// Returns whether this rectangle intersects with another rectangle, including rectangles
+    /// that only share an edge or corner.
     fn intersects(&self, rect: Rect<T>) -> Option<Self::Result> {
         let tl = self.top_left();
         let br = self.bottom_right();
         let otl = rect.top_left();
         let obr = rect.bottom_right();
         // Both rectangle corner x and y values overlap ranges
-        if tl.x() < obr.x() && br.x() > otl.x() && tl.y() < otl.y() && br.y() > obr.y() {
+        if tl.x() <= obr.x() && br.x() >= otl.x() && tl.y() <= obr.y() && br.y() >= otl.y() {
             Some(())
         } else {
             None
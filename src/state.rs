@@ -42,8 +42,11 @@
 //! # }
 //! ```
 
+#[cfg(feature = "recording")]
+use crate::recording::Recording;
 use crate::{
     gui::state::UiState,
+    layer::Layer,
     prelude::*,
     renderer::{Renderer, RendererSettings, Rendering, WindowRenderer},
     texture::TextureRenderer,
@@ -64,6 +67,11 @@ pub struct PixState {
     pub(crate) ui: UiState,
     pub(crate) settings: Settings,
     pub(crate) setting_stack: Vec<Settings>,
+    pub(crate) layers: Vec<Layer>,
+    pub(crate) active_layer: Option<(usize, Option<TextureId>)>,
+    pub(crate) display_list: Option<DisplayList>,
+    #[cfg(feature = "recording")]
+    pub(crate) recording: Option<Recording>,
     pub(crate) theme: Theme,
 }
 
@@ -393,6 +401,11 @@ impl PixState {
             ui: UiState::default(),
             settings: Settings::default(),
             setting_stack: Vec::new(),
+            layers: Vec::new(),
+            active_layer: None,
+            display_list: None,
+            #[cfg(feature = "recording")]
+            recording: None,
             theme: theme.clone(),
         };
         state.background(theme.colors.background);
@@ -417,6 +430,7 @@ impl PixState {
     /// Handle state updates for this frame.
     #[inline]
     pub(crate) fn on_update(&mut self) -> PixResult<()> {
+        self.draw_layers()?;
         for texture in self.ui.textures.iter_mut().filter(|t| t.visible) {
             self.renderer
                 .texture(texture.id, texture.src, texture.dst, 0.0, None, None, None)?;
@@ -63,6 +63,8 @@ impl PixState {
     /// Present all renderer changes since last frame.
     #[inline]
     pub fn present(&mut self) {
+        #[cfg(feature = "recording")]
+        self.record_frame();
         self.renderer.present();
     }
 
@@ -12,6 +12,10 @@
 //! - [`BlendMode`]: Determines how images and textures are blended.
 //! - [`AngleMode`]: Determines how angles are interpreted.
 //! - [`FontStyle`]: Determines how text is rendered.
+//! - [`TextAlign`]: Determines how lines of text are aligned horizontally.
+//! - [`VerticalAlign`]: Determines how text is aligned vertically.
+//! - [`TextOverflow`]: Determines how text wider than the wrap width is drawn.
+//! - [`ClipMask`]: Determines the area rendering is restricted to.
 //!
 //! Provided [`PixState`] methods:
 //!
@@ -19,11 +23,17 @@
 //! - [`PixState::fill`]: Sets the [Color] used to fill shapes.
 //! - [`PixState::stroke`]: Sets the [Color] used to stroke shapes and text.
 //! - [`PixState::stroke_weight`]: Sets the stroke line thickness for lines and text.
-//! - [`PixState::text_shadow`]: Sets the shadow distance for drawing text.
+//! - [`PixState::text_outline`]: Sets the outline width and color for drawing text.
+//! - [`PixState::text_shadow`]: Sets the shadow offset, color and blur for drawing text.
 //! - [`PixState::smooth`]: Enables the anti-alias smoothing option for drawing shapes.
 //! - [`PixState::bezier_detail`]: Set the resolution at which Bezier curves are dispalyed.
 //! - [`PixState::wrap`]: Sets the wrap width for rendering text.
+//! - [`PixState::text_align`]: Sets the horizontal and vertical alignment for rendering text.
+//! - [`PixState::text_overflow`]: Sets how text wider than the wrap width is rendered.
 //! - [`PixState::clip`]: Sets a clip rectangle for rendering.
+//! - [`PixState::push_clip`]: Push a clip rectangle intersected with the current clip to a stack.
+//! - [`PixState::pop_clip`]: Pop the previously pushed clip rectangle off the stack, restoring it.
+//! - [`PixState::clip_mask`]: Restrict rendering to a [`ClipMask`] shape.
 //! - [`PixState::fullscreen`]: Sets fullscreen mode to enabled or disabled.
 //! - [`PixState::toggle_fullscreen`]: Toggles fullscreen.
 //! - [`PixState::vsync`]: Sets vertical sync mode to enabled or disabled.
@@ -49,7 +59,9 @@
 use crate::{
     prelude::*,
     renderer::{Rendering, WindowRenderer},
+    texture::TextureRenderer,
 };
+use anyhow::anyhow;
 use bitflags::bitflags;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -111,6 +123,65 @@ pub enum AngleMode {
     Degrees,
 }
 
+/// Horizontal alignment of each line of text relative to the `x` coordinate text is drawn at.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextAlign {
+    /// Lines start at `x`. Default.
+    Left,
+    /// Lines are centered on `x`.
+    Center,
+    /// Lines end at `x`.
+    Right,
+}
+
+impl TextAlign {
+    /// Returns the offset of a line within the `extra` space left between it and the widest line.
+    #[inline]
+    pub(crate) const fn offset(self, extra: u32) -> i32 {
+        match self {
+            Self::Left => 0,
+            Self::Center => extra as i32 / 2,
+            Self::Right => extra as i32,
+        }
+    }
+}
+
+/// Vertical alignment of text relative to the `y` coordinate text is drawn at.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VerticalAlign {
+    /// The top of the text is at `y`. Default.
+    Top,
+    /// The text is centered on `y`.
+    Middle,
+    /// The baseline of the first line of text is at `y`.
+    Baseline,
+    /// The bottom of the text is at `y`.
+    Bottom,
+}
+
+/// How text wider than the [`PixState::wrap`] width is drawn. Has no effect when the wrap width
+/// is not set.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextOverflow {
+    /// Lines are wrapped on spaces. Words wider than the wrap width overflow it. Default.
+    Wrap,
+    /// Lines are not wrapped and are clipped at the wrap width.
+    Clip,
+    /// Lines are not wrapped and are truncated with `…` after the last character that fits.
+    Ellipsis,
+    /// Lines are not wrapped and are truncated with `…` after the last word that fits, or after
+    /// the last character that fits if not even the first word fits.
+    WordEllipsis,
+    /// Lines are not wrapped and the font size is reduced until the widest line fits.
+    ShrinkToFit,
+}
+
 bitflags! {
     /// Font style for drawing text.
     #[derive(Default)]
@@ -130,6 +201,94 @@ bitflags! {
     }
 }
 
+/// Shape used to restrict rendering to a non-rectangular area with [`PixState::clip_mask`].
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub enum ClipMask<'a> {
+    /// Restrict rendering to a [Rect], interpreted based on the current [`RectMode`].
+    Rect(Rect<i32>),
+    /// Restrict rendering to an [Ellipse], interpreted based on the current [`EllipseMode`].
+    Ellipse(Ellipse<i32>),
+    /// Restrict rendering to a polygon formed by a list of points.
+    Polygon(Vec<Point<i32>>),
+    /// Restrict rendering to the alpha channel of an [Image] drawn with its top-left corner at
+    /// the given position.
+    Image(&'a Image, Point<i32>),
+}
+
+impl From<Rect<i32>> for ClipMask<'_> {
+    fn from(rect: Rect<i32>) -> Self {
+        Self::Rect(rect)
+    }
+}
+
+impl From<Ellipse<i32>> for ClipMask<'_> {
+    fn from(ellipse: Ellipse<i32>) -> Self {
+        Self::Ellipse(ellipse)
+    }
+}
+
+impl From<Tri<i32>> for ClipMask<'_> {
+    fn from(tri: Tri<i32>) -> Self {
+        Self::Polygon(tri.to_vec())
+    }
+}
+
+impl From<Quad<i32>> for ClipMask<'_> {
+    fn from(quad: Quad<i32>) -> Self {
+        Self::Polygon(quad.to_vec())
+    }
+}
+
+impl From<Vec<Point<i32>>> for ClipMask<'_> {
+    fn from(points: Vec<Point<i32>>) -> Self {
+        Self::Polygon(points)
+    }
+}
+
+impl<'a> From<(&'a Image, Point<i32>)> for ClipMask<'a> {
+    fn from((img, position): (&'a Image, Point<i32>)) -> Self {
+        Self::Image(img, position)
+    }
+}
+
+/// A shadow drawn behind text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct TextShadow {
+    pub(crate) offset: Point<i32>,
+    pub(crate) color: Color,
+    pub(crate) blur: u16,
+}
+
+impl TextShadow {
+    /// Returns the offset and color of each copy of the text drawn for the shadow. Blur is
+    /// approximated by spreading translucent copies one pixel apart over a disc with a radius of
+    /// `blur`, so the number of copies grows with the square of `blur`.
+    pub(crate) fn copies(&self) -> Vec<(Point<i32>, Color)> {
+        let blur = i32::from(self.blur);
+        if blur == 0 {
+            return vec![(self.offset, self.color)];
+        }
+        let offsets: Vec<Point<i32>> = (-blur..=blur)
+            .flat_map(|y| (-blur..=blur).map(move |x| point![x, y]))
+            .filter(|p| p.x() * p.x() + p.y() * p.y() <= blur * blur)
+            .collect();
+        // Blend each copy so that all of them overlapping accumulates to the shadow alpha
+        let alpha = f64::from(self.color.alpha()) / 255.0;
+        let copy_alpha = 1.0 - (1.0 - alpha).powf(1.0 / offsets.len() as f64);
+        let mut color = self.color;
+        color.set_alpha((copy_alpha * 255.0).ceil().min(255.0) as u8);
+        offsets
+            .into_iter()
+            .map(|p| {
+                let offset = point![self.offset.x() + p.x(), self.offset.y() + p.y()];
+                (offset, color)
+            })
+            .collect()
+    }
+}
+
 /// Several settings used to change various functionality of the engine.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -142,11 +301,17 @@ pub(crate) struct Settings {
     pub(crate) font_style: FontStyle,
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) font_family: Font,
-    pub(crate) text_shadow: Option<u16>,
+    pub(crate) text_outline: Option<(u16, Color)>,
+    pub(crate) text_shadow: Option<TextShadow>,
     pub(crate) smooth: bool,
     pub(crate) bezier_detail: i32,
     pub(crate) wrap_width: Option<u32>,
+    pub(crate) text_align: TextAlign,
+    pub(crate) vertical_align: VerticalAlign,
+    pub(crate) text_overflow: TextOverflow,
     pub(crate) clip: Option<Rect<i32>>,
+    pub(crate) clip_stack: Vec<Option<Rect<i32>>>,
+    pub(crate) camera: Option<Camera2D>,
     pub(crate) running: bool,
     pub(crate) show_frame_rate: bool,
     pub(crate) target_frame_rate: Option<usize>,
@@ -174,11 +339,17 @@ impl Default for Settings {
             font_size: 14,
             font_style: FontStyle::NORMAL,
             font_family: Font::default(),
+            text_outline: None,
             text_shadow: None,
             smooth: true,
             bezier_detail: 20,
             wrap_width: None,
+            text_align: TextAlign::Left,
+            vertical_align: VerticalAlign::Top,
+            text_overflow: TextOverflow::Wrap,
             clip: None,
+            clip_stack: Vec::new(),
+            camera: None,
             running: true,
             show_frame_rate: false,
             target_frame_rate: None,
@@ -377,7 +548,37 @@ impl PixState {
         self.renderer.font_family(&self.settings.font_family)
     }
 
-    /// Sets the text shadow distance used to draw text on the canvas.
+    /// Sets the outline width and color used to draw text on the canvas, instead of outlining
+    /// text using [`PixState::stroke`] and [`PixState::stroke_weight`]. A `width` of `0` or a
+    /// `color` of `None` restores outlining text using the stroke settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.fill(Color::WHITE);
+    ///     s.text_outline(2, Color::BLACK);
+    ///     // Draws white text with a 2-pixel black outline
+    ///     s.text("Outlined")?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn text_outline<C>(&mut self, width: u16, color: C)
+    where
+        C: Into<Option<Color>>,
+    {
+        self.settings.text_outline = color.into().filter(|_| width > 0).map(|c| (width, c));
+    }
+
+    /// Sets the shadow drawn behind text on the canvas, offset from the text by `offset` and
+    /// softened by `blur` pixels. A `color` of `None` disables the text shadow, which is the
+    /// default. Blur draws translucent copies of the text one pixel apart, so large values are
+    /// expensive.
     ///
     /// # Example
     ///
@@ -386,19 +587,28 @@ impl PixState {
     /// # struct App;
     /// # impl PixEngine for App {
     /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
-    ///     s.text_shadow(2);
-    ///     // Draws a 2-pixel offset shhadow
+    ///     s.text_shadow([2, 2], Color::BLACK, 0);
+    ///     // Draws a 2-pixel offset shadow
     ///     s.text("Shadowed")?;
+    ///     s.text_shadow([0, 4], Color::rgba(0, 0, 0, 160), 3);
+    ///     // Draws a soft shadow below the text
+    ///     s.text("Soft shadow")?;
     ///     Ok(())
     /// }
     /// # }
     /// ```
     #[inline]
-    pub fn text_shadow<D>(&mut self, distance: D)
+    pub fn text_shadow<P, C>(&mut self, offset: P, color: C, blur: u16)
     where
-        D: Into<Option<u16>>,
+        P: Into<Point<i32>>,
+        C: Into<Option<Color>>,
     {
-        self.settings.text_shadow = distance.into();
+        let offset = offset.into();
+        self.settings.text_shadow = color.into().map(|color| TextShadow {
+            offset,
+            color,
+            blur,
+        });
     }
 
     /// Enable or disable the anti-alias option used for drawing shapes on the canvas. `smooth` is
@@ -481,6 +691,60 @@ impl PixState {
         self.settings.wrap_width = width.into();
     }
 
+    /// Sets the horizontal and vertical alignment used to draw text on the canvas. Each line of
+    /// text, including lines broken by [`PixState::wrap`], is aligned horizontally to the `x`
+    /// coordinate. The default is [`TextAlign::Left`] and [`VerticalAlign::Top`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Renders each line centered on the middle of the window
+    ///     s.text_align(TextAlign::Center, VerticalAlign::Middle);
+    ///     s.set_cursor_pos(s.center()?);
+    ///     s.text("Centered\ntext")?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn text_align(&mut self, horizontal: TextAlign, vertical: VerticalAlign) {
+        self.settings.text_align = horizontal;
+        self.settings.vertical_align = vertical;
+        self.renderer.text_align(horizontal);
+    }
+
+    /// Sets how text wider than the [`PixState::wrap`] width is drawn. The default is
+    /// [`TextOverflow::Wrap`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.wrap(100);
+    ///     // Renders as (depending on font width):
+    ///     //
+    ///     // Lorem ipsum…
+    ///     s.text_overflow(TextOverflow::WordEllipsis);
+    ///     s.text("Lorem ipsum dolor sit amet")?;
+    ///     // Renders with a smaller font size so the text fits within 100 pixels.
+    ///     s.text_overflow(TextOverflow::ShrinkToFit);
+    ///     s.text("Lorem ipsum dolor sit amet")?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn text_overflow(&mut self, overflow: TextOverflow) {
+        self.settings.text_overflow = overflow;
+    }
+
     /// Sets the clip [Rect] used by the renderer to draw to the current canvas. `None` disables
     /// clipping.
     ///
@@ -511,6 +775,153 @@ impl PixState {
         self.renderer.clip(self.settings.clip)
     }
 
+    /// Pushes the current clip [Rect] to a stack and sets a new clip [Rect] intersected with the
+    /// current clip, so that nested clips only allow rendering where all of them overlap. If they
+    /// don't overlap, nothing is rendered. Call [`PixState::pop_clip`] to restore the previous
+    /// clip.
+    ///
+    /// # Errors
+    ///
+    /// If the current render target is closed or dropped, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.push_clip(rect![0, 0, 100, 100])?;
+    ///     s.push_clip(rect![50, 50, 100, 100])?;
+    ///     // Only renders within `rect![50, 50, 50, 50]`
+    ///     s.circle([100, 100, 200, 200])?;
+    ///     s.pop_clip()?;
+    ///     s.pop_clip()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn push_clip<R>(&mut self, rect: R) -> PixResult<()>
+    where
+        R: Into<Rect<i32>>,
+    {
+        let s = &mut self.settings;
+        let clip = intersect_clip(s.clip, rect.into());
+        s.clip_stack.push(s.clip);
+        s.clip = Some(clip);
+        self.renderer.clip(s.clip)
+    }
+
+    /// Restores the clip [Rect] previously saved with [`PixState::push_clip`], if present. If the
+    /// clip stack is empty, the clip will remain unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the current render target is closed or dropped, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.push_clip(rect![0, 0, 100, 100])?;
+    ///     s.circle([100, 100, 200, 200])?;
+    ///     s.pop_clip()?;
+    ///     // Renders without clipping
+    ///     s.circle([100, 100, 200, 200])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn pop_clip(&mut self) -> PixResult<()> {
+        let s = &mut self.settings;
+        if let Some(clip) = s.clip_stack.pop() {
+            s.clip = clip;
+        }
+        self.renderer.clip(s.clip)
+    }
+
+    /// Restricts everything rendered by `f` to the area covered by a [`ClipMask`], such as an
+    /// [Ellipse], a polygon or the alpha channel of an [Image]. The current clip [Rect] continues
+    /// to apply.
+    ///
+    /// # Note
+    ///
+    /// Rendering is done to an intermediate texture the size of the current render target, which
+    /// is then blended onto the target, so using masks every frame has a performance cost.
+    ///
+    /// Masks are composited immediately and can't be deferred to a [`PixState::layer`]. Mask
+    /// shapes are also in render target coordinates and are not transformed by the current
+    /// [`Camera2D`], though drawing done by `f` is.
+    ///
+    /// # Errors
+    ///
+    /// If called while drawing to a [`PixState::layer`], the current render target is closed or
+    /// dropped, the renderer does not support masking, or `f` returns an error, then an error is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { portrait: Image };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Renders a circular portrait
+    ///     s.clip_mask(ellipse![100, 100, 64, 64], |s: &mut PixState| {
+    ///         s.image(&self.portrait, [68, 68])
+    ///     })?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn clip_mask<'a, M, F>(&mut self, mask: M, f: F) -> PixResult<()>
+    where
+        M: Into<ClipMask<'a>>,
+        F: FnOnce(&mut PixState) -> PixResult<()>,
+    {
+        if matches!(self.active_layer, Some((_, target)) if target == self.renderer.texture_target())
+        {
+            return Err(anyhow!("clip masks can't be drawn to a layer"));
+        }
+        let mask = mask.into();
+        let (width, height) = self.renderer.dimensions()?;
+        let viewport = Some(self.renderer.viewport()?)
+            .filter(|&viewport| viewport != rect![0, 0, width as i32, height as i32]);
+        let layer_id = self
+            .renderer
+            .create_texture(width, height, Some(PixelFormat::Rgba))?;
+        let mask_id = self
+            .renderer
+            .create_texture(width, height, Some(PixelFormat::Rgba))?;
+
+        let mut result = self.render_clip_mask(&mask, layer_id, mask_id, viewport, f);
+        if result.is_ok() {
+            // The layer already has the viewport and scale applied, so draw it unchanged
+            let s = &self.settings;
+            result = self
+                .renderer
+                .set_texture_blend_mode(layer_id, Some(BlendMode::Blend))
+                .and_then(|()| self.renderer.set_viewport(None))
+                .and_then(|()| self.renderer.scale(1.0, 1.0))
+                .and_then(|()| {
+                    self.renderer
+                        .texture(layer_id, None, None, 0.0, None, None, None)
+                });
+            result = result
+                .and(self.renderer.set_viewport(viewport))
+                .and(self.renderer.scale(s.scale_x, s.scale_y));
+        }
+        // Attempt to delete both textures, even if one fails
+        let deleted = self.renderer.delete_texture(layer_id);
+        let deleted = deleted.and(self.renderer.delete_texture(mask_id));
+        result.and(deleted)
+    }
+
     /// Set the application to fullscreen or not.
     ///
     /// # Errors
@@ -1085,6 +1496,7 @@ impl PixState {
         // Excluding restoring cursor - as it's used for mouse hover.
         let _ = self.renderer.font_size(s.font_size);
         self.renderer.font_style(s.font_style);
+        self.renderer.text_align(s.text_align);
         let _ = self.renderer.font_family(&s.font_family);
         self.renderer.blend_mode(s.blend_mode);
     }
@@ -1099,6 +1511,76 @@ impl PixState {
         self.renderer.cursor(Some(cursor))
     }
 
+    /// Renders the `mask` shape to `mask_id`, the contents of `f` to `layer_id` and masks the
+    /// alpha of the layer. Both are rendered with the current clip, `viewport` and scale.
+    fn render_clip_mask<F>(
+        &mut self,
+        mask: &ClipMask<'_>,
+        layer_id: TextureId,
+        mask_id: TextureId,
+        viewport: Option<Rect<i32>>,
+        f: F,
+    ) -> PixResult<()>
+    where
+        F: FnOnce(&mut PixState) -> PixResult<()>,
+    {
+        self.renderer.push_texture_target(mask_id)?;
+        let result = self
+            .begin_clip_mask_target(viewport)
+            .and_then(|()| self.draw_clip_mask(mask));
+        self.renderer.pop_texture_target();
+        result?;
+
+        self.renderer.push_texture_target(layer_id)?;
+        let result = self.begin_clip_mask_target(viewport).and_then(|()| f(self));
+        self.renderer.pop_texture_target();
+        result?;
+
+        self.renderer.mask_texture(layer_id, mask_id)
+    }
+
+    /// Clears a pushed [`PixState::clip_mask`] texture target and applies the current clip,
+    /// `viewport` and scale to it.
+    fn begin_clip_mask_target(&mut self, viewport: Option<Rect<i32>>) -> PixResult<()> {
+        let s = &self.settings;
+        self.renderer.set_draw_color(Color::TRANSPARENT)?;
+        self.renderer.clear()?;
+        self.renderer.set_viewport(viewport)?;
+        self.renderer.scale(s.scale_x, s.scale_y)?;
+        self.renderer.clip(s.clip)
+    }
+
+    /// Draws a [`ClipMask`] shape in white to the current render target.
+    fn draw_clip_mask(&mut self, mask: &ClipMask<'_>) -> PixResult<()> {
+        let smooth = self.settings.smooth;
+        let fill = Some(Color::WHITE);
+        match *mask {
+            ClipMask::Rect(rect) => {
+                let rect = self.get_rect(rect);
+                self.renderer.rect(rect, None, fill, None)?;
+            }
+            ClipMask::Ellipse(ellipse) => {
+                let ellipse = self.get_ellipse(ellipse);
+                self.renderer.ellipse(ellipse, smooth, fill, None)?;
+            }
+            ClipMask::Polygon(ref points) => {
+                self.renderer
+                    .polygon(points.iter().copied(), smooth, fill, None)?;
+            }
+            ClipMask::Image(img, position) => {
+                // Copy image alpha as-is instead of blending it with the cleared mask
+                self.renderer.blend_mode(BlendMode::None);
+                let dst = img.bounding_rect_offset(position);
+                let result = self
+                    .renderer
+                    .image(img, None, Some(dst), 0.0, None, None, None);
+                self.renderer.blend_mode(self.settings.blend_mode);
+                result?;
+            }
+        }
+        Ok(())
+    }
+
     /// Get the target delta time between frames.
     #[inline]
     pub(crate) fn target_delta_time(&self) -> Option<Duration> {
@@ -1111,3 +1593,35 @@ impl PixState {
         self.renderer.vsync()
     }
 }
+
+/// Clip that lies entirely outside of any render target, hiding everything drawn. Renderers treat
+/// an empty clip [Rect] as at least a single pixel, so one can't be used instead.
+const EMPTY_CLIP: Rect<i32> = Rect::new(-1, -1, 1, 1);
+
+/// Intersect a new clip [Rect] with the current clip. Clips that don't overlap or are empty return
+/// [`EMPTY_CLIP`], hiding everything drawn until the clip is popped.
+pub(crate) fn intersect_clip(clip: Option<Rect<i32>>, rect: Rect<i32>) -> Rect<i32> {
+    clip.map_or(Some(rect), |clip| clip.intersection(rect))
+        .filter(|clip| clip.width() > 0 && clip.height() > 0)
+        .unwrap_or(EMPTY_CLIP)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disjoint_clips_hide_everything() {
+        let clip = rect![0, 0, 100, 100];
+        assert_eq!(intersect_clip(None, clip), clip);
+        assert_eq!(
+            intersect_clip(Some(clip), rect![50, 50, 100, 100]),
+            rect![50, 50, 50, 50]
+        );
+
+        let empty = intersect_clip(Some(clip), rect![200, 200, 10, 10]);
+        assert!(empty.right() <= 0 && empty.bottom() <= 0);
+        assert_eq!(intersect_clip(Some(empty), clip), empty);
+        assert_eq!(intersect_clip(None, rect![10, 10, 0, 10]), empty);
+    }
+}
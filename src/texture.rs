@@ -7,11 +7,22 @@
 //! - [`PixState::texture`]: Render a portion of a texture to the current canvas.
 //! - [`PixState::texture_transformed`]: Render a transformed portion of a texture to the current
 //!   canvas.
+//! - [`PixState::texture_tiled`]: Render a portion of a texture repeated to fill a region of the
+//!   current canvas.
 //! - [`PixState::create_texture`]: Creates a new texture to render to.
 //! - [`PixState::delete_texture`]: Delete a texture.
 //! - [`PixState::update_texture`]: Update texture with [u8] [slice] of pixel data.
+//! - [`PixState::set_texture_scale_quality`]: Set the [`ScaleQuality`] used when a texture is
+//!   scaled.
+//! - [`PixState::set_texture_color_mod`]: Set a persistent color modulation for a texture.
+//! - [`PixState::set_texture_alpha_mod`]: Set a persistent alpha modulation for a texture.
+//! - [`PixState::set_texture_blend_mode`]: Set a persistent [`BlendMode`] for a texture.
 //! - [`PixState::set_texture_target`]: Target a texture for rendering.
 //! - [`PixState::clear_texture_target`]: Clear texture target back to primary canvas for rendering.
+//! - [`PixState::push_texture_target`]: Target a texture for rendering, saving the current target.
+//! - [`PixState::pop_texture_target`]: Restore the previously saved render target.
+//! - [`PixState::clear_texture_targets`]: Restore every saved render target back to the primary
+//!   canvas.
 //!
 //! # Example
 //!
@@ -47,7 +58,9 @@
 //! # }
 //! ```
 
-use crate::prelude::*;
+use crate::{ops::clamp_dimensions, prelude::*, renderer::WindowRenderer};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::{
     fmt,
     ops::{Deref, DerefMut},
@@ -55,6 +68,7 @@ use std::{
 
 /// `Texture` identifier used to reference and target an internally managed texture.
 #[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TextureId(pub(crate) usize);
 
 impl fmt::Display for TextureId {
@@ -76,6 +90,20 @@ impl DerefMut for TextureId {
     }
 }
 
+/// Filtering used when a texture is scaled during rendering.
+#[non_exhaustive]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScaleQuality {
+    /// Nearest pixel sampling. Default. Keeps pixel-art crisp.
+    #[default]
+    Nearest,
+    /// Linear filtering.
+    Linear,
+    /// Anisotropic filtering, if supported. Falls back to `Linear` otherwise.
+    Best,
+}
+
 impl PixState {
     /// Draw a portion `src` of a texture to the current render target translated and resized to
     /// the target `dst`. Passing `None` for `src` renders the entire texture. Passing `None` for
@@ -121,8 +149,20 @@ impl PixState {
         R1: Into<Option<Rect<i32>>>,
         R2: Into<Option<Rect<i32>>>,
     {
-        self.renderer
-            .texture(texture_id, src.into(), dst.into(), 0.0, None, None, None)
+        let (mut dst, mut angle, mut center) = (dst.into(), 0.0, None);
+        if let (Some(camera), Some(rect)) = (self.settings.camera, dst) {
+            let transformed = camera.transform_dst(rect, angle, center);
+            (dst, angle, center) = (Some(transformed.0), transformed.1, transformed.2);
+        }
+        self.draw_command(DrawCommand::Texture {
+            texture_id,
+            src: src.into(),
+            dst,
+            angle,
+            center,
+            flipped: None,
+            tint: None,
+        })
     }
 
     /// Draw a transformed portion `src` of a texture to the current render target translated and
@@ -195,15 +235,93 @@ impl PixState {
         if s.angle_mode == AngleMode::Radians {
             angle = angle.to_degrees();
         };
-        self.renderer.texture(
+        let (mut dst, mut center) = (dst.into(), center.into());
+        if let (Some(camera), Some(rect)) = (s.camera, dst) {
+            let transformed = camera.transform_dst(rect, angle, center);
+            (dst, angle, center) = (Some(transformed.0), transformed.1, transformed.2);
+        }
+        self.draw_command(DrawCommand::Texture {
             texture_id,
-            src.into(),
-            dst.into(),
+            src: src.into(),
+            dst,
             angle,
-            center.into(),
-            flipped.into(),
-            s.image_tint,
-        )
+            center,
+            flipped: flipped.into(),
+            tint: s.image_tint,
+        })
+    }
+
+    /// Draw a portion `src` of a texture repeated to fill the target `dst`, scrolled by `offset`.
+    /// Each tile is drawn at the size of `src` and tiles crossing the edges of `dst` are clipped.
+    /// Passing `None` for `src` repeats the entire texture. Passing `None` for `dst` fills the
+    /// entire render target.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for any of the following:
+    ///     - The current render target is closed or dropped.
+    ///     - The texture being rendered has been dropped.
+    ///     - The target texture is the same as the texture being rendered.
+    ///     - The renderer fails to draw to the texture.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { texture_id: TextureId, scroll: i32 };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Scrolling parallax background
+    ///     self.scroll += 1;
+    ///     s.texture_tiled(self.texture_id, None, None, [-self.scroll, 0])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn texture_tiled<R1, R2, P>(
+        &mut self,
+        texture_id: TextureId,
+        src: R1,
+        dst: R2,
+        offset: P,
+    ) -> PixResult<()>
+    where
+        R1: Into<Option<Rect<i32>>>,
+        R2: Into<Option<Rect<i32>>>,
+        P: Into<Point<i32>>,
+    {
+        let src = match src.into() {
+            Some(src) => src,
+            None => {
+                let (width, height) = self.renderer.texture_dimensions(texture_id)?;
+                let (width, height) = clamp_dimensions(width, height);
+                rect![0, 0, width, height]
+            }
+        };
+        let dst = match dst.into() {
+            Some(dst) => dst,
+            None => {
+                let (width, height) = self.renderer.dimensions()?;
+                let (width, height) = clamp_dimensions(width, height);
+                rect![0, 0, width, height]
+            }
+        };
+        let camera = self.settings.camera;
+        for (src, dst) in tile_rects(src, dst, offset.into()) {
+            let (dst, angle, center) = camera.map_or((dst, 0.0, None), |camera| {
+                camera.transform_dst(dst, 0.0, None)
+            });
+            self.draw_command(DrawCommand::Texture {
+                texture_id,
+                src: Some(src),
+                dst: Some(dst),
+                angle,
+                center,
+                flipped: None,
+                tint: None,
+            })?;
+        }
+        Ok(())
     }
 
     /// Constructs a `Texture` to render to. Passing `None` for [`PixelFormat`] will use
@@ -329,9 +447,133 @@ impl PixState {
             .update_texture(texture_id, rect, pixels, pitch)
     }
 
+    /// Set the [`ScaleQuality`] used when rendering a `Texture` at a size other than its own. The
+    /// setting persists for the lifetime of the texture.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, or the renderer fails to set the scale
+    /// quality, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { sprites: TextureId, background: TextureId };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     self.sprites = s.create_texture(64, 64, None)?;
+    ///     self.background = s.create_texture(320, 240, None)?;
+    ///     // Crisp pixel-art sprites over a smoothly scaled background
+    ///     s.set_texture_scale_quality(self.sprites, ScaleQuality::Nearest)?;
+    ///     s.set_texture_scale_quality(self.background, ScaleQuality::Linear)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn set_texture_scale_quality(
+        &mut self,
+        texture_id: TextureId,
+        quality: ScaleQuality,
+    ) -> PixResult<()> {
+        self.renderer.set_texture_scale_quality(texture_id, quality)
+    }
+
+    /// Set a persistent color modulation for a `Texture`, multiplied into every render of the
+    /// texture in place of [`PixState::image_tint`]. The alpha channel of `color` is ignored, see
+    /// [`PixState::set_texture_alpha_mod`]. Passing `None` clears the color modulation.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { texture_id: TextureId };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.set_texture_color_mod(self.texture_id, Color::RED)?;
+    ///     // Renders tinted red
+    ///     s.texture(self.texture_id, None, rect![0, 0, 100, 100])?;
+    ///     s.set_texture_color_mod(self.texture_id, None)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn set_texture_color_mod<C>(&mut self, texture_id: TextureId, color: C) -> PixResult<()>
+    where
+        C: Into<Option<Color>>,
+    {
+        self.renderer
+            .set_texture_color_mod(texture_id, color.into())
+    }
+
+    /// Set a persistent alpha modulation for a `Texture`, multiplied into every render of the
+    /// texture in place of the alpha channel of [`PixState::image_tint`]. Passing `None` clears
+    /// the alpha modulation.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { texture_id: TextureId };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.set_texture_alpha_mod(self.texture_id, 128)?;
+    ///     s.set_texture_blend_mode(self.texture_id, BlendMode::Blend)?;
+    ///     // Renders at half opacity
+    ///     s.texture(self.texture_id, None, rect![0, 0, 100, 100])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn set_texture_alpha_mod<A>(&mut self, texture_id: TextureId, alpha: A) -> PixResult<()>
+    where
+        A: Into<Option<u8>>,
+    {
+        self.renderer
+            .set_texture_alpha_mod(texture_id, alpha.into())
+    }
+
+    /// Set a persistent [`BlendMode`] for a `Texture`, used for every render of the texture in
+    /// place of [`PixState::blend_mode`]. Passing `None` clears the blend mode.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { texture_id: TextureId };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.set_texture_blend_mode(self.texture_id, BlendMode::Add)?;
+    ///     s.texture(self.texture_id, None, rect![0, 0, 100, 100])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn set_texture_blend_mode<B>(&mut self, texture_id: TextureId, mode: B) -> PixResult<()>
+    where
+        B: Into<Option<BlendMode>>,
+    {
+        self.renderer
+            .set_texture_blend_mode(texture_id, mode.into())
+    }
+
     /// Set a `Texture` as the priamry target for drawing operations. Pushes current settings and UI
     /// cursor to the stack, so any changes made while a texture target is set will be in effect
-    /// until [`PixState::reset_texture_target`] is called.
+    /// until [`PixState::clear_texture_target`] is called. Does nothing if a texture target is
+    /// already set, use [`PixState::push_texture_target`] to nest texture targets.
     ///
     /// # Errors
     ///
@@ -356,10 +598,7 @@ impl PixState {
     /// ```
     pub fn set_texture_target(&mut self, id: TextureId) -> PixResult<()> {
         if self.renderer.texture_target().is_none() {
-            self.push();
-            self.ui.push_cursor();
-            self.set_cursor_pos(self.theme.spacing.frame_pad);
-            self.renderer.set_texture_target(id)
+            self.push_texture_target(id)
         } else {
             Ok(())
         }
@@ -367,7 +606,10 @@ impl PixState {
 
     /// Clears `Texture` target back to the primary canvas for drawing operations. Pops previous
     /// settings and UI cursor off the stack, so that changes made while texture target was set are
-    /// reverted.
+    /// reverted. Only a single target is cleared, so if texture targets were pushed with
+    /// [`PixState::push_texture_target`], this restores the previous one like
+    /// [`PixState::pop_texture_target`]. Use [`PixState::clear_texture_targets`] to clear every
+    /// texture target.
     pub fn clear_texture_target(&mut self) {
         if self.renderer.texture_target().is_some() {
             self.renderer.clear_texture_target();
@@ -375,6 +617,129 @@ impl PixState {
             self.pop();
         }
     }
+
+    /// Pops every texture target pushed with [`PixState::push_texture_target`] back to the
+    /// primary canvas for drawing operations, restoring the settings and UI cursor saved by each.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { panel: TextureId, icon: TextureId };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.push_texture_target(self.panel)?;
+    ///     s.push_texture_target(self.icon)?;
+    ///     s.background(Color::random());
+    ///     s.clear_texture_targets(); // Back to rendering to the window
+    ///     s.texture(self.panel, None, None)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn clear_texture_targets(&mut self) {
+        while self.renderer.texture_target().is_some() {
+            self.pop_texture_target();
+        }
+    }
+
+    /// Set a `Texture` as the primary target for drawing operations, saving the current render
+    /// target, even if it's another texture. Pushes current settings and UI cursor to the stack
+    /// and starts the texture with no clip, viewport or scale, so any changes made will be in
+    /// effect until [`PixState::pop_texture_target`] is called.
+    ///
+    /// # Errors
+    ///
+    /// If the target has been dropped or is invalid, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { panel: TextureId, icon: TextureId };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.push_texture_target(self.panel)?;
+    ///     s.background(Color::GRAY);
+    ///     s.push_texture_target(self.icon)?;
+    ///     s.background(Color::random());
+    ///     s.pop_texture_target(); // Back to rendering to `panel`
+    ///     s.texture(self.icon, None, rect![10, 10, 32, 32])?;
+    ///     s.pop_texture_target(); // Back to rendering to the window
+    ///     s.texture(self.panel, None, None)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn push_texture_target(&mut self, id: TextureId) -> PixResult<()> {
+        self.renderer.push_texture_target(id)?;
+        self.push();
+        self.ui.push_cursor();
+        self.set_cursor_pos(self.theme.spacing.frame_pad);
+        // Clips and scale are relative to the render target, so start with none
+        let s = &mut self.settings;
+        s.clip = None;
+        s.clip_stack.clear();
+        s.scale_x = 1.0;
+        s.scale_y = 1.0;
+        Ok(())
+    }
+
+    /// Restores the render target saved by the last call to [`PixState::push_texture_target`],
+    /// along with its clip, viewport and scale. Pops previous settings and UI cursor off the stack,
+    /// so that changes made while the texture target was set are reverted. Does nothing if no
+    /// texture target is set.
+    pub fn pop_texture_target(&mut self) {
+        if self.renderer.texture_target().is_some() {
+            self.renderer.pop_texture_target();
+            self.ui.pop_cursor();
+            self.pop();
+        }
+    }
+}
+
+/// Returns the `(src, dst)` pairs needed to repeat `src` across `dst`, scrolled by `offset`. Tiles
+/// crossing the edges of `dst` are clipped, with their `src` adjusted to match.
+pub(crate) fn tile_rects(
+    src: Rect<i32>,
+    dst: Rect<i32>,
+    offset: Point<i32>,
+) -> Vec<(Rect<i32>, Rect<i32>)> {
+    let (tile_width, tile_height) = (src.width(), src.height());
+    if tile_width <= 0 || tile_height <= 0 || dst.width() <= 0 || dst.height() <= 0 {
+        return vec![];
+    }
+
+    let start = |edge: i32, offset: i32, size: i32| {
+        let start = edge + offset.rem_euclid(size);
+        if start > edge {
+            start - size
+        } else {
+            start
+        }
+    };
+    let start_x = start(dst.left(), offset.x(), tile_width);
+    let start_y = start(dst.top(), offset.y(), tile_height);
+
+    let mut tiles = Vec::new();
+    let mut y = start_y;
+    while y < dst.bottom() {
+        let top = y.max(dst.top());
+        let bottom = (y + tile_height).min(dst.bottom());
+        let mut x = start_x;
+        while x < dst.right() {
+            let left = x.max(dst.left());
+            let right = (x + tile_width).min(dst.right());
+            let (width, height) = (right - left, bottom - top);
+            tiles.push((
+                rect![src.x() + left - x, src.y() + top - y, width, height],
+                rect![left, top, width, height],
+            ));
+            x += tile_width;
+        }
+        y += tile_height;
+    }
+    tiles
 }
 
 /// Trait for texture operations on the underlying `Renderer`.
@@ -437,6 +802,63 @@ pub(crate) trait TextureRenderer {
         tint: Option<Color>,
     ) -> PixResult<()>;
 
+    /// Set the [`ScaleQuality`] used when rendering a `Texture`.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, or the renderer fails to set the scale
+    /// quality, then an error is returned.
+    fn set_texture_scale_quality(
+        &mut self,
+        texture_id: TextureId,
+        quality: ScaleQuality,
+    ) -> PixResult<()>;
+
+    /// Set or clear a persistent color modulation for a `Texture`.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    fn set_texture_color_mod(
+        &mut self,
+        texture_id: TextureId,
+        color: Option<Color>,
+    ) -> PixResult<()>;
+
+    /// Set or clear a persistent alpha modulation for a `Texture`.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    fn set_texture_alpha_mod(&mut self, texture_id: TextureId, alpha: Option<u8>) -> PixResult<()>;
+
+    /// Set or clear a persistent [`BlendMode`] for a `Texture`.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    fn set_texture_blend_mode(
+        &mut self,
+        texture_id: TextureId,
+        mode: Option<BlendMode>,
+    ) -> PixResult<()>;
+
+    /// Returns the dimensions of a `Texture` as `(width, height)`.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    fn texture_dimensions(&self, texture_id: TextureId) -> PixResult<(u32, u32)>;
+
+    /// Multiply the alpha channel of a `Texture` by the alpha channel of a `mask` texture of the
+    /// same size.
+    ///
+    /// # Errors
+    ///
+    /// If either texture has been dropped or is invalid, or the renderer does not support
+    /// masking, then an error is returned.
+    fn mask_texture(&mut self, texture_id: TextureId, mask_id: TextureId) -> PixResult<()>;
+
     /// Returns texture used as the target for drawing operations, if set.
     fn texture_target(&self) -> Option<TextureId>;
 
@@ -448,12 +870,64 @@ pub(crate) trait TextureRenderer {
     /// If the texture has been dropped or is invalid, then an error is returned.
     fn set_texture_target(&mut self, texture_id: TextureId) -> PixResult<()>;
 
-    /// Clear `Texture` target back to the window target canvas for drawing operations.
+    /// Clear the `Texture` target set by `set_texture_target` or the last call to
+    /// `push_texture_target`, restoring the previous render target.
     fn clear_texture_target(&mut self);
 
+    /// Push the current render target and its clip, viewport and scale to a stack and set a
+    /// `Texture` as the primary target for drawing operations.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    fn push_texture_target(&mut self, texture_id: TextureId) -> PixResult<()>;
+
+    /// Restore the render target and its clip, viewport and scale saved by the last call to
+    /// `push_texture_target`, or the window target canvas if the stack is empty.
+    fn pop_texture_target(&mut self);
+
     /// Returns whether a texture is set as the target for drawing operations.
+    #[cfg_attr(feature = "terminal", allow(dead_code))]
     fn has_texture_target(&self) -> bool;
 
     /// Clear internal texture cache.
+    #[cfg_attr(feature = "terminal", allow(dead_code))]
     fn clear_texture_cache(&mut self);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_rects_clips_to_dst() {
+        let src = rect![8, 8, 16, 16];
+        let dst = rect![10, 10, 40, 20];
+
+        let tiles = tile_rects(src, dst, point![0, 0]);
+        assert_eq!(
+            tiles,
+            vec![
+                (rect![8, 8, 16, 16], rect![10, 10, 16, 16]),
+                (rect![8, 8, 16, 16], rect![26, 10, 16, 16]),
+                (rect![8, 8, 8, 16], rect![42, 10, 8, 16]),
+                (rect![8, 8, 16, 4], rect![10, 26, 16, 4]),
+                (rect![8, 8, 16, 4], rect![26, 26, 16, 4]),
+                (rect![8, 8, 8, 4], rect![42, 26, 8, 4]),
+            ]
+        );
+
+        let tiles = tile_rects(src, rect![0, 0, 16, 16], point![-4, 20]);
+        assert_eq!(
+            tiles,
+            vec![
+                (rect![12, 20, 12, 4], rect![0, 0, 12, 4]),
+                (rect![8, 20, 4, 4], rect![12, 0, 4, 4]),
+                (rect![12, 8, 12, 12], rect![0, 4, 12, 12]),
+                (rect![8, 8, 4, 12], rect![12, 4, 4, 12]),
+            ]
+        );
+
+        assert!(tile_rects(rect![0, 0, 0, 16], dst, point![0, 0]).is_empty());
+    }
+}
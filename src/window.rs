@@ -63,6 +63,24 @@ impl Default for Position {
     }
 }
 
+/// Determines how a logical size set with [`EngineBuilder::logical_size`] is scaled to fit the
+/// window.
+#[non_exhaustive]
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScaleMode {
+    /// Stretch to fill the entire window, ignoring aspect ratio.
+    Stretch,
+    /// Scale to fit within the window preserving aspect ratio, letterboxing any remaining space.
+    #[default]
+    Fit,
+    /// Scale by the largest whole number factor that fits within the window, letterboxing any
+    /// remaining space. Keeps pixel art crisp.
+    Integer,
+    /// Scale to fill the entire window preserving aspect ratio, cropping any overflow.
+    Fill,
+}
+
 /// Window Identifier.
 #[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct WindowId(pub(crate) u32);
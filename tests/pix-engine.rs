@@ -101,3 +101,274 @@ fn single_thread_engine_stop() -> PixResult<()> {
     assert_eq!(app.stop_count, 2, "on_stop was called");
     Ok(())
 }
+
+#[derive(Default)]
+struct RecordApp {
+    recorded: DisplayList,
+    replayed: DisplayList,
+}
+
+impl PixEngine for RecordApp {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        self.recorded = s.record_display_list(|s| {
+            s.font_family(Font::NOTO)?;
+            s.text("Recorded")?;
+            s.font_family(Font::default())?;
+            s.rect([0, 0, 10, 10])
+        })?;
+        let recorded = &self.recorded;
+        self.replayed = s.record_display_list(|s| recorded.draw(s))?;
+        s.quit();
+        Ok(())
+    }
+}
+
+#[test]
+#[ignore = "engine can only be tested in the main thread. --test-threads=1"]
+fn single_thread_display_list_replay() -> PixResult<()> {
+    let mut eng = create_engine()?;
+    let mut app = RecordApp::default();
+    eng.run(&mut app)?;
+    assert!(
+        matches!(
+            app.recorded.iter().next(),
+            Some(DrawCommand::Text { font_family, .. }) if *font_family == Font::NOTO
+        ),
+        "text recorded with its font family"
+    );
+    assert_eq!(
+        app.recorded, app.replayed,
+        "replay records the same commands"
+    );
+    Ok(())
+}
+
+#[derive(Default)]
+struct TextRecordApp {
+    recorded: DisplayList,
+    replayed: DisplayList,
+}
+
+impl PixEngine for TextRecordApp {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        self.recorded = s.record_display_list(|s| {
+            s.fill(Color::WHITE);
+            s.text_align(TextAlign::Center, VerticalAlign::Middle);
+            s.text_outline(2, Color::BLACK);
+            s.text_shadow([2, 2], Color::GRAY, 0);
+            s.wrap(20);
+            s.text_overflow(TextOverflow::Clip);
+            s.set_cursor_pos([100, 100]);
+            s.text("Clipped text with an outline and shadow")?;
+            Ok(())
+        })?;
+        let recorded = &self.recorded;
+        self.replayed = s.record_display_list(|s| recorded.draw(s))?;
+        s.quit();
+        Ok(())
+    }
+}
+
+#[test]
+#[ignore = "engine can only be tested in the main thread. --test-threads=1"]
+fn single_thread_display_list_text_settings() -> PixResult<()> {
+    let mut eng = create_engine()?;
+    let mut app = TextRecordApp::default();
+    eng.run(&mut app)?;
+    let passes: Vec<_> = app
+        .recorded
+        .iter()
+        .filter_map(|command| match command {
+            DrawCommand::Text {
+                fill,
+                outline,
+                clip,
+                ..
+            } => Some((*fill, *outline, clip.is_some())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        passes,
+        [
+            (Some(Color::GRAY), 2, true),
+            (Some(Color::BLACK), 2, true),
+            (Some(Color::WHITE), 0, true),
+        ],
+        "shadow, outline and fill passes recorded with their clip"
+    );
+    assert_eq!(
+        app.recorded, app.replayed,
+        "replay records the same commands"
+    );
+    Ok(())
+}
+
+#[derive(Default)]
+struct CameraTextApp {
+    recorded: DisplayList,
+    size: (u32, u32),
+}
+
+impl PixEngine for CameraTextApp {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        let mut camera = Camera2D::new(200, 200);
+        camera.set_position([10.0, 10.0]);
+        camera.set_zoom(2.0);
+        s.font_size(12)?;
+        let mut size = (0, 0);
+        self.recorded = s.record_display_list(|s| {
+            s.set_camera(camera);
+            s.set_cursor_pos([10, 10]);
+            size = s.text("World")?;
+            s.set_camera(None);
+            Ok(())
+        })?;
+        self.size = size;
+        s.quit();
+        Ok(())
+    }
+}
+
+#[test]
+#[ignore = "engine can only be tested in the main thread. --test-threads=1"]
+fn single_thread_text_through_camera() -> PixResult<()> {
+    let mut eng = create_engine()?;
+    let mut app = CameraTextApp::default();
+    eng.run(&mut app)?;
+    assert!(
+        matches!(
+            app.recorded.iter().next(),
+            Some(DrawCommand::Text { position, font_size: 24, .. }) if *position == point![100, 100]
+        ),
+        "text positioned and scaled by the camera"
+    );
+    assert!(app.size.0 > 0, "text size returned in world units");
+    Ok(())
+}
+
+#[derive(Default)]
+struct TextureLayerApp {
+    texture_id: Option<TextureId>,
+    pixel: Option<Color>,
+}
+
+impl PixEngine for TextureLayerApp {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        match self.texture_id {
+            None => {
+                let texture_id = s.create_texture(16, 16, None)?;
+                self.texture_id = Some(texture_id);
+                s.push_texture_target(texture_id)?;
+                s.background(Color::BLACK);
+                let result = s.layer(1, |s| {
+                    s.fill(Color::RED);
+                    s.stroke(None);
+                    s.rect([0, 0, 16, 16])
+                });
+                s.pop_texture_target();
+                result
+            }
+            Some(texture_id) => {
+                // Checked a frame later, after layers have been replayed
+                let path = std::env::temp_dir().join("pix_engine_texture_layer.png");
+                s.push_texture_target(texture_id)?;
+                let result = s.save_canvas(None, &path);
+                s.pop_texture_target();
+                result?;
+                self.pixel = Some(Image::from_file(path)?.get_pixel(8, 8));
+                s.quit();
+                Ok(())
+            }
+        }
+    }
+}
+
+#[test]
+#[ignore = "engine can only be tested in the main thread. --test-threads=1"]
+fn single_thread_layer_in_texture_target() -> PixResult<()> {
+    let mut eng = create_engine()?;
+    let mut app = TextureLayerApp::default();
+    eng.run(&mut app)?;
+    assert_eq!(app.pixel, Some(Color::RED), "layer drawn to the texture");
+    Ok(())
+}
+
+#[derive(Default)]
+struct MaskLayerApp {
+    layer_result: Option<PixResult<()>>,
+}
+
+impl PixEngine for MaskLayerApp {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        let mut result = None;
+        s.layer(1, |s| {
+            result = Some(s.clip_mask(rect![0, 0, 10, 10], |s| s.rect([0, 0, 20, 20])));
+            Ok(())
+        })?;
+        self.layer_result = result;
+        s.quit();
+        Ok(())
+    }
+}
+
+#[test]
+#[ignore = "engine can only be tested in the main thread. --test-threads=1"]
+fn single_thread_clip_mask_in_layer() -> PixResult<()> {
+    let mut eng = create_engine()?;
+    let mut app = MaskLayerApp::default();
+    eng.run(&mut app)?;
+    assert!(
+        matches!(&app.layer_result, Some(Err(err)) if err.to_string().contains("layer")),
+        "clip masks are rejected inside a layer"
+    );
+    Ok(())
+}
+
+#[cfg(feature = "recording")]
+#[derive(Default)]
+struct GifApp {
+    frames: u32,
+}
+
+#[cfg(feature = "recording")]
+impl PixEngine for GifApp {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        let path = std::env::temp_dir().join("pix_engine_logical_size.gif");
+        s.start_recording(path, RecordingFormat::Gif)
+    }
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        self.frames += 1;
+        let gray = self.frames as u8 * 40;
+        s.background(Color::rgb(gray, gray, gray));
+        if self.frames > 3 {
+            s.quit();
+        }
+        Ok(())
+    }
+    fn on_stop(&mut self, s: &mut PixState) -> PixResult<()> {
+        assert!(s.is_recording(), "frames were recorded without errors");
+        s.stop_recording()
+    }
+}
+
+#[cfg(feature = "recording")]
+#[test]
+#[ignore = "engine can only be tested in the main thread. --test-threads=1"]
+fn single_thread_recording_logical_size() -> PixResult<()> {
+    let mut eng = Engine::builder()
+        .title("pix-engine integration test")
+        .dimensions(400, 300)
+        .logical_size(200, 100)
+        .position_centered()
+        .hidden()
+        .build()?;
+    let mut app = GifApp::default();
+    eng.run(&mut app)?;
+    let path = std::env::temp_dir().join("pix_engine_logical_size.gif");
+    let gif = std::fs::read(&path)?;
+    std::fs::remove_file(path)?;
+    assert!(gif.starts_with(b"GIF89a"), "gif header written");
+    assert_eq!(gif.last(), Some(&0x3B), "gif trailer written");
+    Ok(())
+}